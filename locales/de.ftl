@@ -0,0 +1,200 @@
+# Deutsch
+
+## Months and periods
+
+month-1 = Januar
+month-2 = Februar
+month-3 = März
+month-4 = April
+month-5 = Mai
+month-6 = Juni
+month-7 = Juli
+month-8 = August
+month-9 = September
+month-10 = Oktober
+month-11 = November
+month-12 = Dezember
+season-winter = Winter
+season-spring = Frühling
+season-summer = Sommer
+season-fall = Herbst
+
+## Table columns
+
+column-year = Jahr
+column-month = Monat
+column-hours = Stunden
+column-change = Änderung
+column-rank = Rang
+column-share = Anteil
+column-unlocks = Erfolge
+column-per-hour = Pro Std.
+column-member = Mitglied
+column-games = Spiele
+
+## report
+
+report-title = Steam-Jahresrückblick
+report-year-summary = { $year }: { $hours } Stunden in { $games ->
+        [one] { $games } Spiel
+       *[other] { $games } Spielen
+    }
+report-top = Top: { $game } ({ $hours } Std.)
+report-months = Monate: { $months }
+report-app-fallback = App { $app_id }
+report-hours-by-quarter = Stunden pro Quartal
+report-hours-by-season = Stunden pro Jahreszeit
+report-release-split = Neuerscheinungen und ältere Spiele
+report-new-releases = Neu erschienen
+report-back-catalog = Ältere Spiele
+report-unknown-release = Unbekannt
+report-games = { $count ->
+        [one] Spiel
+       *[other] Spiele
+    }
+report-no-release-date = Warnung: Kein Erscheinungsdatum für App-ID { $app_id }: { $error }
+report-deltas = Veränderungen zum Vormonat
+report-new = neu
+report-biggest-ramps = Größte Zuwächse
+report-biggest-drop-offs = Größte Rückgänge
+report-achievements = Am nächsten an 100 % der Erfolge
+report-nothing-left = Alles abgeschlossen!
+report-timeline = Erfolge im Zeitverlauf
+report-most-productive = Produktivster Monat: { $month } ({ $rate } Erfolge pro Stunde)
+report-forecast = Prognose
+report-forecast-none = Zu wenige vollständige Jahre für eine Prognose
+report-forecast-total = { $year }: etwa { $hours } Stunden (Trend über { $years ->
+        [one] { $years } Jahr
+       *[other] { $years } Jahre
+    })
+report-forecast-busiest = Stärkste Monate: { $months }
+report-forecast-top = Voraussichtliches Top-Spiel: { $game } (etwa { $hours } h)
+report-forecast-pace = { $year } bisher: { $hours } Stunden in { $months ->
+        [one] { $months } Monat
+       *[other] { $months } Monaten
+    }, auf Kurs für { $projected } Stunden
+report-milestones = Meilensteine
+report-milestones-none = Keine Meilensteine erreicht
+report-milestones-no-lifetime = Gesamtspielzeit importieren (import-local, community-games), um Meilensteine pro Spiel zu sehen
+report-milestone-year = { $hours } Stunden gespielt in { $year }
+report-milestone-lifetime = { $game } hat insgesamt { $hours } Stunden überschritten
+report-reviews = Rezensionen deiner meistgespielten Spiele
+report-reviews-summary = { $reviewed } deiner Top { $games } rezensiert ({ $recommended } empfohlen)
+report-review-recommended = Empfohlen
+report-review-not-recommended = Nicht empfohlen
+report-review-none = nicht rezensiert
+report-review-hours = { $at_review } bei der Rezension, { $now } jetzt
+report-narrative = Deine Jahre im Rückblick
+narrative-overview = { $year } hast du { $games ->
+        [one] ein Spiel
+       *[other] { $games } Spiele
+    } { $hours } Stunden lang gespielt.
+narrative-change = { $direction ->
+        [up] Das sind { $percent } % mehr als { $previous }.
+        [down] Das sind { $percent } % weniger als { $previous }.
+       *[flat] Das ist etwa so viel wie { $previous }.
+    }
+narrative-new-games = { $new ->
+        [0] Jedes davon hattest du schon vorher gespielt.
+        [one] Eines davon war neu für dich.
+       *[other] { $new } davon waren neu für dich.
+    }
+narrative-top-game = { $kind ->
+        [dominant] { $game } hat das Jahr beherrscht, mit { $share } % deiner Spielzeit ({ $hours } Stunden).
+       *[leading] { $game } lag mit { $hours } Stunden vorn, { $share } % deiner Spielzeit.
+    }
+narrative-biggest-month = Der { $month } war dein stärkster Monat ({ $hours } Stunden), vor allem dank { $game }.
+narrative-quietest-month = { $kind ->
+        [off] Im { $month } hast du ganz pausiert.
+       *[quiet] Der { $month } war mit { $hours } Stunden am ruhigsten.
+    }
+narrative-streak = { $kind ->
+        [all-year] { $game } hast du jeden einzelnen Monat gespielt.
+       *[run] Zu { $game } bist du { $months } Monate in Folge zurückgekehrt.
+    }
+report-tags = Stunden nach Tag
+report-tags-none = Keine Spielzeit für die getaggten Spiele
+report-tag-games = { $games ->
+        [one] 1 Spiel
+       *[other] { $games } Spiele
+    }
+
+## Profile section
+
+profile-title = Profil
+profile-level = Level: { $level }
+profile-xp = EP: { $xp }
+profile-badges-earned = Abzeichen erhalten { $year }: { $count }
+profile-unknown-badge = Unbekanntes Abzeichen
+
+## game
+
+game-heading = { $name } (App-ID { $app_id })
+game-all-years = Alle Jahre: { $hours } Stunden, { $share } % der gesamten Spielzeit
+game-hours-per-month = Stunden pro Monat
+game-lifetime = Gesamtspielzeit (importiert): { $hours } Stunden
+game-tags = Tags: { $tags }
+game-notes = Notizen: { $notes }
+
+## heatmap
+
+heatmap-empty = Keine monatliche Spielzeit im Datensatz
+heatmap-title = Stunden pro Monat
+heatmap-no-months = keine Monatswerte
+heatmap-legend = { $none } keine  { $low } wenig  { $high } viel  (dunkelste = { $max } Std.)
+heatmap-saved = Heatmap gespeichert unter: { $path }
+
+## compare-global
+
+compare-title = Vergleich mit Steams globalen Jahresrückblick-Zahlen
+compare-baseline = Quelle: { $source }
+compare-no-figures = { $year }: keine globalen Zahlen in { $path }
+compare-games-played = Gespielte Spiele:
+compare-new-release-playtime = Anteil Neuerscheinungen:
+compare-global-average = (globaler Schnitt { $value })
+compare-global-share = (global { $value } %)
+compare-no-release-dates = (keine Erscheinungsdaten im Cache; 'details' ausführen)
+compare-sessions-by-platform = Sitzungen nach Plattform
+compare-top-games-played = Globale Top-Spiele gespielt: { $played }/{ $total }
+
+## goals
+
+goals-title = Ziele für { $year }
+goals-elapsed = { $percent } % des Jahres sind vergangen
+goals-status-met = erreicht
+goals-status-on-track = im Plan
+goals-status-behind = im Rückstand
+goals-status-missed = verfehlt
+goals-status-over-pace = zu schnell
+goals-bound = { $bound ->
+        [min] mindestens
+       *[max] höchstens
+    }
+goals-default-name = { $bound ->
+        [min] Mindestens
+       *[max] Höchstens
+    } { $target } { $metric ->
+        [played] gespielt
+        [new] erstmals in diesem Jahr gespielt
+        [new-releases] in Neuerscheinungen
+        [back-catalog] in älteren Spielen
+       *[listed] in den gelisteten Spielen
+    }
+goals-games = { $count ->
+        [one] { $count } Spiel
+       *[other] { $count } Spiele
+    }
+goals-summary = { $met } von { $total ->
+        [one] { $total } Ziel
+       *[other] { $total } Zielen
+    } erreicht
+
+## family report
+
+family-title = Steam-Familienbericht
+family-none = Keine Spielzeit gefunden
+family-total = Familie
+family-top-games = Top-Spiele ({ $shared } von mehr als einem Mitglied gespielt)
+family-borrowed = (geliehen)
+family-owned-by = im Besitz von { $owners }
+family-saved = Familien-Spielzeit gespeichert unter: { $path }
@@ -0,0 +1,201 @@
+# Report labels. Messages missing from another locale fall back to these;
+# every locale is expected to have them all.
+
+## Months and periods
+
+month-1 = January
+month-2 = February
+month-3 = March
+month-4 = April
+month-5 = May
+month-6 = June
+month-7 = July
+month-8 = August
+month-9 = September
+month-10 = October
+month-11 = November
+month-12 = December
+season-winter = Winter
+season-spring = Spring
+season-summer = Summer
+season-fall = Fall
+
+## Table columns
+
+column-year = Year
+column-month = Month
+column-hours = Hours
+column-change = Change
+column-rank = Rank
+column-share = Share
+column-unlocks = Unlocks
+column-per-hour = Per hour
+column-member = Member
+column-games = Games
+
+## report
+
+report-title = Steam Replay Report
+report-year-summary = { $year }: { $hours } hours across { $games ->
+        [one] { $games } game
+       *[other] { $games } games
+    }
+report-top = top: { $game } ({ $hours }h)
+report-months = Months: { $months }
+report-app-fallback = App { $app_id }
+report-hours-by-quarter = Hours by quarter
+report-hours-by-season = Hours by season
+report-release-split = New releases vs back catalog
+report-new-releases = New releases
+report-back-catalog = Back catalog
+report-unknown-release = Unknown
+report-games = { $count ->
+        [one] game
+       *[other] games
+    }
+report-no-release-date = Warning: No release date for app ID { $app_id }: { $error }
+report-deltas = Month-over-month changes
+report-new = new
+report-biggest-ramps = Biggest ramps
+report-biggest-drop-offs = Biggest drop-offs
+report-achievements = Closest to 100% achievements
+report-nothing-left = Nothing left to complete!
+report-timeline = Achievement timeline
+report-most-productive = Most productive month: { $month } ({ $rate } achievements per hour)
+report-forecast = Forecast
+report-forecast-none = Not enough complete years to project from
+report-forecast-total = { $year }: about { $hours } hours (trend over { $years ->
+        [one] { $years } year
+       *[other] { $years } years
+    })
+report-forecast-busiest = Busiest months: { $months }
+report-forecast-top = Likely top game: { $game } (about { $hours }h)
+report-forecast-pace = { $year } so far: { $hours } hours in { $months ->
+        [one] { $months } month
+       *[other] { $months } months
+    }, on pace for { $projected } hours
+report-milestones = Milestones
+report-milestones-none = No milestones crossed
+report-milestones-no-lifetime = Import lifetime playtime (import-local, community-games) to include per-game milestones
+report-milestone-year = { $hours } hours played in { $year }
+report-milestone-lifetime = { $game } passed { $hours } hours in total
+report-reviews = Reviews of your most played games
+report-reviews-summary = Reviewed { $reviewed } of your top { $games } ({ $recommended } recommended)
+report-review-recommended = Recommended
+report-review-not-recommended = Not recommended
+report-review-none = not reviewed
+report-review-hours = { $at_review } at review, { $now } now
+report-narrative = Your years in review
+narrative-overview = In { $year } you played { $games ->
+        [one] one game
+       *[other] { $games } games
+    } for { $hours } hours.
+narrative-change = { $direction ->
+        [up] That's { $percent }% more than in { $previous }.
+        [down] That's { $percent }% less than in { $previous }.
+       *[flat] That's about the same as in { $previous }.
+    }
+narrative-new-games = { $new ->
+        [0] Every one of them was a game you'd played before.
+        [one] One of them was new to you.
+       *[other] { $new } of them were new to you.
+    }
+narrative-top-game = { $kind ->
+        [dominant] { $game } dominated the year, taking { $share }% of your playtime ({ $hours } hours).
+       *[leading] { $game } led the way with { $hours } hours, { $share }% of your playtime.
+    }
+narrative-biggest-month = { $month } was your biggest month ({ $hours } hours), thanks mostly to { $game }.
+narrative-quietest-month = { $kind ->
+        [off] You took { $month } off entirely.
+       *[quiet] { $month } was the quietest, at { $hours } hours.
+    }
+narrative-streak = { $kind ->
+        [all-year] You played { $game } every single month.
+       *[run] You kept coming back to { $game } for { $months } months in a row.
+    }
+report-tags = Hours by tag
+report-tags-none = No playtime for the tagged games
+report-tag-games = { $games ->
+        [one] 1 game
+       *[other] { $games } games
+    }
+
+## Profile section
+
+profile-title = Profile
+profile-level = Level: { $level }
+profile-xp = XP: { $xp }
+profile-badges-earned = Badges earned in { $year }: { $count }
+profile-unknown-badge = Unknown badge
+
+## game
+
+game-heading = { $name } (app ID { $app_id })
+game-all-years = All years: { $hours } hours, { $share }% of all playtime
+game-hours-per-month = Hours per month
+game-lifetime = Lifetime playtime (imported): { $hours } hours
+game-tags = Tags: { $tags }
+game-notes = Notes: { $notes }
+
+## heatmap
+
+heatmap-empty = No monthly playtime in the dataset
+heatmap-title = Hours per month
+heatmap-no-months = no monthly breakdown
+heatmap-legend = { $none } none  { $low } low  { $high } high  (darkest = { $max }h)
+heatmap-saved = Heatmap saved to: { $path }
+
+## compare-global
+
+compare-title = Compared with Steam's global replay figures
+compare-baseline = Baseline: { $source }
+compare-no-figures = { $year }: no global figures in { $path }
+compare-games-played = Games played:
+compare-new-release-playtime = New-release playtime:
+compare-global-average = (global average { $value })
+compare-global-share = (global { $value }%)
+compare-no-release-dates = (no release dates cached; run 'details')
+compare-sessions-by-platform = Sessions by platform
+compare-top-games-played = Global top games played: { $played }/{ $total }
+
+## goals
+
+goals-title = Goals for { $year }
+goals-elapsed = { $percent }% of the year has passed
+goals-status-met = met
+goals-status-on-track = on track
+goals-status-behind = behind
+goals-status-missed = missed
+goals-status-over-pace = over pace
+goals-bound = { $bound ->
+        [min] at least
+       *[max] at most
+    }
+goals-default-name = { $bound ->
+        [min] At least
+       *[max] At most
+    } { $target } { $metric ->
+        [played] played
+        [new] first played this year
+        [new-releases] in new releases
+        [back-catalog] in back-catalog games
+       *[listed] in the listed games
+    }
+goals-games = { $count ->
+        [one] { $count } game
+       *[other] { $count } games
+    }
+goals-summary = { $met } of { $total ->
+        [one] { $total } goal
+       *[other] { $total } goals
+    } met
+
+## family report
+
+family-title = Steam Family Report
+family-none = No playtime found
+family-total = Family
+family-top-games = Top games ({ $shared } played by more than one member)
+family-borrowed = (borrowed)
+family-owned-by = owned by { $owners }
+family-saved = Family playtime saved to: { $path }
@@ -0,0 +1,200 @@
+# Español
+
+## Months and periods
+
+month-1 = enero
+month-2 = febrero
+month-3 = marzo
+month-4 = abril
+month-5 = mayo
+month-6 = junio
+month-7 = julio
+month-8 = agosto
+month-9 = septiembre
+month-10 = octubre
+month-11 = noviembre
+month-12 = diciembre
+season-winter = Invierno
+season-spring = Primavera
+season-summer = Verano
+season-fall = Otoño
+
+## Table columns
+
+column-year = Año
+column-month = Mes
+column-hours = Horas
+column-change = Cambio
+column-rank = Puesto
+column-share = Cuota
+column-unlocks = Logros
+column-per-hour = Por hora
+column-member = Miembro
+column-games = Juegos
+
+## report
+
+report-title = Resumen del año en Steam
+report-year-summary = { $year }: { $hours } horas en { $games ->
+        [one] { $games } juego
+       *[other] { $games } juegos
+    }
+report-top = más jugado: { $game } ({ $hours } h)
+report-months = Meses: { $months }
+report-app-fallback = App { $app_id }
+report-hours-by-quarter = Horas por trimestre
+report-hours-by-season = Horas por estación
+report-release-split = Novedades frente a catálogo
+report-new-releases = Novedades
+report-back-catalog = Catálogo
+report-unknown-release = Desconocido
+report-games = { $count ->
+        [one] juego
+       *[other] juegos
+    }
+report-no-release-date = Aviso: sin fecha de lanzamiento para la app { $app_id }: { $error }
+report-deltas = Cambios respecto al mes anterior
+report-new = nuevo
+report-biggest-ramps = Mayores subidas
+report-biggest-drop-offs = Mayores caídas
+report-achievements = Más cerca del 100 % de logros
+report-nothing-left = ¡No queda nada por completar!
+report-timeline = Cronología de logros
+report-most-productive = Mes más productivo: { $month } ({ $rate } logros por hora)
+report-forecast = Previsión
+report-forecast-none = No hay suficientes años completos para una previsión
+report-forecast-total = { $year }: unas { $hours } horas (tendencia de { $years ->
+        [one] { $years } año
+       *[other] { $years } años
+    })
+report-forecast-busiest = Meses con más juego: { $months }
+report-forecast-top = Juego más probable: { $game } (unas { $hours } h)
+report-forecast-pace = { $year } hasta ahora: { $hours } horas en { $months ->
+        [one] { $months } mes
+       *[other] { $months } meses
+    }, a ritmo de { $projected } horas
+report-milestones = Hitos
+report-milestones-none = Ningún hito alcanzado
+report-milestones-no-lifetime = Importa el tiempo de juego total (import-local, community-games) para ver hitos por juego
+report-milestone-year = { $hours } horas jugadas en { $year }
+report-milestone-lifetime = { $game } superó las { $hours } horas en total
+report-reviews = Reseñas de tus juegos más jugados
+report-reviews-summary = Has reseñado { $reviewed } de tus { $games } principales ({ $recommended } recomendados)
+report-review-recommended = Recomendado
+report-review-not-recommended = No recomendado
+report-review-none = sin reseña
+report-review-hours = { $at_review } al reseñar, { $now } ahora
+report-narrative = Tus años en resumen
+narrative-overview = En { $year } jugaste { $games ->
+        [one] un juego
+       *[other] { $games } juegos
+    } durante { $hours } horas.
+narrative-change = { $direction ->
+        [up] Es un { $percent } % más que en { $previous }.
+        [down] Es un { $percent } % menos que en { $previous }.
+       *[flat] Es más o menos lo mismo que en { $previous }.
+    }
+narrative-new-games = { $new ->
+        [0] Todos eran juegos que ya habías jugado antes.
+        [one] Uno de ellos era nuevo para ti.
+       *[other] { $new } de ellos eran nuevos para ti.
+    }
+narrative-top-game = { $kind ->
+        [dominant] { $game } dominó el año, con el { $share } % de tu tiempo de juego ({ $hours } horas).
+       *[leading] { $game } fue el primero con { $hours } horas, el { $share } % de tu tiempo de juego.
+    }
+narrative-biggest-month = Tu mejor mes fue { $month } ({ $hours } horas), sobre todo gracias a { $game }.
+narrative-quietest-month = { $kind ->
+        [off] En { $month } no jugaste nada.
+       *[quiet] El mes más tranquilo fue { $month }, con { $hours } horas.
+    }
+narrative-streak = { $kind ->
+        [all-year] Jugaste a { $game } todos los meses.
+       *[run] Volviste a { $game } durante { $months } meses seguidos.
+    }
+report-tags = Horas por etiqueta
+report-tags-none = Sin tiempo de juego para los juegos etiquetados
+report-tag-games = { $games ->
+        [one] 1 juego
+       *[other] { $games } juegos
+    }
+
+## Profile section
+
+profile-title = Perfil
+profile-level = Nivel: { $level }
+profile-xp = PX: { $xp }
+profile-badges-earned = Insignias obtenidas en { $year }: { $count }
+profile-unknown-badge = Insignia desconocida
+
+## game
+
+game-heading = { $name } (app { $app_id })
+game-all-years = Todos los años: { $hours } horas, { $share } % del tiempo total
+game-hours-per-month = Horas por mes
+game-lifetime = Tiempo total (importado): { $hours } horas
+game-tags = Etiquetas: { $tags }
+game-notes = Notas: { $notes }
+
+## heatmap
+
+heatmap-empty = No hay tiempo de juego mensual en los datos
+heatmap-title = Horas por mes
+heatmap-no-months = sin desglose mensual
+heatmap-legend = { $none } nada  { $low } poco  { $high } mucho  (más oscuro = { $max } h)
+heatmap-saved = Mapa de calor guardado en: { $path }
+
+## compare-global
+
+compare-title = Comparación con las cifras globales de Steam
+compare-baseline = Fuente: { $source }
+compare-no-figures = { $year }: no hay cifras globales en { $path }
+compare-games-played = Juegos jugados:
+compare-new-release-playtime = Tiempo en novedades:
+compare-global-average = (media global { $value })
+compare-global-share = (global { $value } %)
+compare-no-release-dates = (sin fechas de lanzamiento en caché; ejecuta 'details')
+compare-sessions-by-platform = Sesiones por plataforma
+compare-top-games-played = Juegos más jugados del mundo que jugaste: { $played }/{ $total }
+
+## goals
+
+goals-title = Objetivos de { $year }
+goals-elapsed = Ha pasado el { $percent } % del año
+goals-status-met = cumplido
+goals-status-on-track = en camino
+goals-status-behind = atrasado
+goals-status-missed = no cumplido
+goals-status-over-pace = por encima del ritmo
+goals-bound = { $bound ->
+        [min] al menos
+       *[max] como máximo
+    }
+goals-default-name = { $bound ->
+        [min] Al menos
+       *[max] Como máximo
+    } { $target } { $metric ->
+        [played] en total
+        [new] jugados por primera vez este año
+        [new-releases] en novedades
+        [back-catalog] en juegos de catálogo
+       *[listed] en los juegos indicados
+    }
+goals-games = { $count ->
+        [one] { $count } juego
+       *[other] { $count } juegos
+    }
+goals-summary = { $total ->
+        [one] { $met } de { $total } objetivo cumplido
+       *[other] { $met } de { $total } objetivos cumplidos
+    }
+
+## family report
+
+family-title = Informe de la Familia Steam
+family-none = No se encontró tiempo de juego
+family-total = Familia
+family-top-games = Juegos principales ({ $shared } jugados por más de un miembro)
+family-borrowed = (prestado)
+family-owned-by = propiedad de { $owners }
+family-saved = Tiempo de juego familiar guardado en: { $path }
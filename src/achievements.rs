@@ -0,0 +1,137 @@
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+
+pub const COMPLETION_FILENAME: &str = "achievement_completion.csv";
+pub const UNLOCKS_FILENAME: &str = "achievement_unlocks.csv";
+
+struct Achievement {
+    api_name: String,
+    achieved: bool,
+    unlock_time: i64,
+}
+
+pub fn fetch_achievements(json_files: &[String], api_key: &str, subset: Option<crate::trial::Subset>) -> Result<()> {
+    println!("Fetching achievements for {} JSON file(s)...", json_files.len());
+
+    // (steam_id, app_id) pairs for every game with playtime
+    let mut targets = BTreeSet::new();
+
+    for json_file in json_files {
+        println!("Reading: {}", json_file);
+
+        let file_content = fs::read_to_string(json_file)
+            .with_context(|| format!("Failed to read {}", json_file))?;
+
+        let data: Value = serde_json::from_str(&file_content)
+            .with_context(|| format!("Failed to parse {}", json_file))?;
+
+        let Some(steam_id) = data
+            .get("url")
+            .and_then(|v| v.as_str())
+            .and_then(crate::extract_steam_id)
+        else {
+            println!("  Warning: No Steam ID found in {}, skipping", json_file);
+            crate::summary::failure(json_file.as_str(), "No Steam ID found")?;
+            continue;
+        };
+
+        let playtime_data = crate::replay::extract_playtime(&data, json_file, false)?;
+        println!("  Found {} playtime entries", playtime_data.len());
+
+        for (app_id, _, _) in playtime_data {
+            targets.insert((steam_id.to_string(), app_id));
+        }
+    }
+
+    let mut targets: Vec<(String, String)> = targets.into_iter().collect();
+    if let Some(subset) = subset {
+        targets = subset.apply(targets);
+    }
+
+    let mut csv_content = String::from("steam_id,app_id,achieved,total,achievement_completion\n");
+    let mut unlocks_content = String::from("steam_id,app_id,achievement,unlock_time\n");
+    let total = targets.len();
+
+    crate::progress::phase("achievements", total);
+    for (index, (steam_id, app_id)) in targets.iter().enumerate() {
+        crate::progress::item(app_id, index);
+        println!("[{}/{}] Fetching achievements for app ID: {}", index + 1, total, app_id);
+
+        match fetch_player_achievements(api_key, steam_id, app_id) {
+            Ok(list) if !list.is_empty() => {
+                let achieved = list.iter().filter(|a| a.achieved).count();
+                let completion = achieved as f64 / list.len() as f64 * 100.0;
+                csv_content.push_str(&format!(
+                    "{},{},{},{},{:.1}\n",
+                    steam_id, app_id, achieved, list.len(), completion
+                ));
+
+                for achievement in list.iter().filter(|a| a.achieved && a.unlock_time > 0) {
+                    unlocks_content.push_str(&format!(
+                        "{},{},{},{}\n",
+                        steam_id, app_id, achievement.api_name, achievement.unlock_time
+                    ));
+                }
+            }
+            Ok(_) => {
+                println!("  No achievements for app ID {}", app_id);
+            }
+            Err(e) => {
+                println!("  Error fetching app ID {}: {}", app_id, e);
+                crate::summary::failure(app_id.as_str(), format!("{:#}", e))?;
+            }
+        }
+    }
+
+    fs::write(COMPLETION_FILENAME, csv_content)
+        .context("Failed to write achievements file")?;
+
+    fs::write(UNLOCKS_FILENAME, unlocks_content)
+        .context("Failed to write achievement unlocks file")?;
+
+    crate::summary::file_written(COMPLETION_FILENAME);
+    crate::summary::file_written(UNLOCKS_FILENAME);
+
+    println!("\nAchievement completion saved to: {}", COMPLETION_FILENAME);
+    println!("Achievement unlock times saved to: {}", UNLOCKS_FILENAME);
+
+    Ok(())
+}
+
+/// Returns the player's achievement list; empty when the game has no achievements.
+fn fetch_player_achievements(api_key: &str, steam_id: &str, app_id: &str) -> Result<Vec<Achievement>> {
+    let url = format!(
+        "https://api.steampowered.com/ISteamUserStats/GetPlayerAchievements/v1/?appid={}&steamid={}&key={}",
+        app_id, steam_id, api_key
+    );
+
+    let response = crate::http::get(&url)
+        .context("Failed to fetch achievements")?;
+
+    if response.status() == reqwest::StatusCode::FORBIDDEN {
+        bail!("API key rejected or profile game details are private");
+    }
+
+    let data: Value = response.json()
+        .context("Failed to parse response")?;
+
+    // Games without stats answer with { "playerstats": { "error": ..., "success": false } }
+    let Some(achievements) = data
+        .get("playerstats")
+        .and_then(|stats| stats.get("achievements"))
+        .and_then(|a| a.as_array())
+    else {
+        return Ok(Vec::new());
+    };
+
+    Ok(achievements
+        .iter()
+        .map(|a| Achievement {
+            api_name: a.get("apiname").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            achieved: a.get("achieved").and_then(|v| v.as_u64()) == Some(1),
+            unlock_time: a.get("unlocktime").and_then(|v| v.as_i64()).unwrap_or(0),
+        })
+        .collect())
+}
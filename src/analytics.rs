@@ -0,0 +1,219 @@
+//! Aggregations over the tidy dataset shared by the reports, exports and
+//! servers: per-game summaries, each year's totals, and monthly breakdowns
+//! per game or summed over games. They are single passes into ordered maps;
+//! even a family's multi-year dataset is a few thousand rows.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::dataset::{self, PlaytimeRow};
+
+/// Per-game figures across every year.
+#[derive(Clone)]
+pub struct GameSummary {
+    pub app_id: String,
+    pub name: String,
+    /// Lifetime playtime from local/community imports, else the replay
+    /// years' playtime summed
+    pub playtime_seconds: u64,
+    /// (year, playtime) for each replay year the game was played in
+    pub years: Vec<(String, u64)>,
+}
+
+type YearTally<'a> = BTreeMap<&'a str, (Option<u64>, u64)>;
+
+/// One summary per game, in app ID order.
+pub fn summarize(rows: &[PlaytimeRow], mapping: &HashMap<String, String>) -> Vec<GameSummary> {
+    // app_id -> (year -> (yearly total, sum of months), largest lifetime figure)
+    let mut by_game: BTreeMap<&str, (YearTally, Option<u64>)> = BTreeMap::new();
+
+    for row in rows {
+        let (years, lifetime) = by_game.entry(row.app_id.as_str()).or_default();
+        match row.month.as_str() {
+            "lifetime" => *lifetime = (*lifetime).max(Some(row.playtime_seconds)),
+            "total" => years.entry(row.year.as_str()).or_default().0 = Some(row.playtime_seconds),
+            _ => years.entry(row.year.as_str()).or_default().1 += row.playtime_seconds,
+        }
+    }
+
+    by_game
+        .into_iter()
+        .map(|(app_id, (years, lifetime))| {
+            // The replay's yearly list only covers top games; others are
+            // only in the monthly lists
+            let years: Vec<(String, u64)> = years
+                .into_iter()
+                .map(|(year, (total, months))| (year.to_string(), total.unwrap_or(months)))
+                .filter(|(_, seconds)| *seconds > 0)
+                .collect();
+            let replay_seconds = years.iter().map(|(_, seconds)| seconds).sum();
+            GameSummary {
+                app_id: app_id.to_string(),
+                name: mapping.get(app_id).cloned().unwrap_or_default(),
+                playtime_seconds: lifetime.unwrap_or(replay_seconds),
+                years,
+            }
+        })
+        .collect()
+}
+
+/// Each year's "total" rows, most played first. Yearly figures come from
+/// these; summing the monthly rows as well would double count.
+pub fn yearly_totals(rows: &[PlaytimeRow]) -> BTreeMap<&str, Vec<&PlaytimeRow>> {
+    let mut by_year: BTreeMap<&str, Vec<&PlaytimeRow>> = BTreeMap::new();
+    for row in rows.iter().filter(|row| row.month == "total") {
+        by_year.entry(row.year.as_str()).or_default().push(row);
+    }
+    for games in by_year.values_mut() {
+        games.sort_by_key(|row| std::cmp::Reverse(row.playtime_seconds));
+    }
+    by_year
+}
+
+/// year -> app_id -> seconds per month, January first. When inputs overlap
+/// the last row for a month wins.
+pub fn monthly_by_game(rows: &[PlaytimeRow]) -> BTreeMap<&str, BTreeMap<&str, [u64; 12]>> {
+    let mut by_year: BTreeMap<&str, BTreeMap<&str, [u64; 12]>> = BTreeMap::new();
+    for row in rows {
+        if let index @ 1..=12 = dataset::month_order(&row.month) {
+            by_year
+                .entry(row.year.as_str())
+                .or_default()
+                .entry(row.app_id.as_str())
+                .or_default()[index - 1] = row.playtime_seconds;
+        }
+    }
+    by_year
+}
+
+/// year -> hours for each month, summed over games. Years without a
+/// monthly breakdown are kept as empty rows.
+pub fn monthly_hours(rows: &[PlaytimeRow]) -> BTreeMap<String, [f64; 12]> {
+    let mut hours: BTreeMap<String, [f64; 12]> = BTreeMap::new();
+
+    for row in rows {
+        match dataset::month_order(&row.month) {
+            index @ 1..=12 => hours.entry(row.year.clone()).or_default()[index - 1] += row.hours(),
+            0 => {
+                hours.entry(row.year.clone()).or_default();
+            }
+            _ => {}
+        }
+    }
+
+    hours
+}
+
+/// Playtime summed per game over `rows`, most played first (ties by app ID).
+pub fn rank_games<'a>(rows: impl IntoIterator<Item = &'a PlaytimeRow>) -> Vec<(&'a str, u64)> {
+    let mut by_game: BTreeMap<&str, u64> = BTreeMap::new();
+    for row in rows {
+        *by_game.entry(row.app_id.as_str()).or_default() += row.playtime_seconds;
+    }
+
+    let mut ranked: Vec<(&str, u64)> = by_game.into_iter().collect();
+    ranked.sort_by_key(|&(app_id, seconds)| (std::cmp::Reverse(seconds), app_id));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(app_id: &str, year: &str, month: &str, playtime_seconds: u64) -> PlaytimeRow {
+        PlaytimeRow {
+            app_id: app_id.to_string(),
+            playtime_seconds,
+            year: year.to_string(),
+            month: month.to_string(),
+        }
+    }
+
+    #[test]
+    fn summarizes_totals_only_years() {
+        let rows = [row("10", "2021", "total", 3600), row("20", "2021", "total", 1800)];
+        let mapping = HashMap::from([("10".to_string(), "Portal".to_string())]);
+
+        let games = summarize(&rows, &mapping);
+        assert_eq!(games.len(), 2);
+        assert_eq!((games[0].app_id.as_str(), games[0].name.as_str()), ("10", "Portal"));
+        assert_eq!(games[0].years, [("2021".to_string(), 3600)]);
+        assert_eq!(games[0].playtime_seconds, 3600);
+        assert_eq!(games[1].name, "");
+    }
+
+    #[test]
+    fn summarize_prefers_the_year_total_over_its_months() {
+        let rows = [
+            row("10", "2022", "January", 600),
+            row("10", "2022", "February", 900),
+            row("10", "2022", "total", 2000),
+            row("20", "2022", "March", 300),
+            row("20", "2022", "April", 400),
+        ];
+
+        let games = summarize(&rows, &HashMap::new());
+        assert_eq!(games[0].years, [("2022".to_string(), 2000)]);
+        assert_eq!(games[1].years, [("2022".to_string(), 700)]);
+    }
+
+    #[test]
+    fn summarize_prefers_lifetime_imports_over_replay_years() {
+        let rows = [
+            row("10", "2022", "total", 2000),
+            row("10", "2023", "total", 1000),
+            row("10", "2024", "lifetime", 5000),
+            row("20", "2022", "total", 2000),
+            row("20", "2023", "May", 1000),
+        ];
+
+        let games = summarize(&rows, &HashMap::new());
+        assert_eq!(games[0].playtime_seconds, 5000);
+        assert_eq!(games[0].years.len(), 2);
+        assert_eq!(games[1].playtime_seconds, 3000);
+    }
+
+    #[test]
+    fn yearly_totals_keep_only_total_rows_most_played_first() {
+        let rows = [
+            row("10", "2022", "total", 1000),
+            row("10", "2022", "January", 1000),
+            row("20", "2022", "total", 3000),
+            row("30", "2023", "February", 500),
+        ];
+
+        let totals = yearly_totals(&rows);
+        assert_eq!(totals.keys().copied().collect::<Vec<_>>(), ["2022"]);
+        let games: Vec<(&str, u64)> = totals["2022"].iter().map(|row| (row.app_id.as_str(), row.playtime_seconds)).collect();
+        assert_eq!(games, [("20", 3000), ("10", 1000)]);
+    }
+
+    #[test]
+    fn monthly_by_game_fills_calendar_slots_and_skips_totals() {
+        let rows = [
+            row("10", "2022", "January", 100),
+            row("10", "2022", "December", 200),
+            row("10", "2022", "total", 300),
+            // Overlapping inputs: the last row wins
+            row("10", "2022", "January", 150),
+        ];
+
+        let by_game = monthly_by_game(&rows);
+        let months = by_game["2022"]["10"];
+        assert_eq!(months[0], 150);
+        assert_eq!(months[11], 200);
+        assert_eq!(months.iter().sum::<u64>(), 350);
+    }
+
+    #[test]
+    fn rank_games_breaks_ties_by_app_id() {
+        let rows = [
+            row("30", "2022", "January", 500),
+            row("20", "2022", "January", 500),
+            row("10", "2022", "January", 200),
+            row("10", "2022", "February", 300),
+            row("40", "2022", "March", 900),
+        ];
+
+        assert_eq!(rank_games(&rows), [("40", 900), ("10", 500), ("20", 500), ("30", 500)]);
+    }
+}
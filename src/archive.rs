@@ -0,0 +1,62 @@
+//! Wayback Machine fallback for `scrape --allow-archive`: when Valve has
+//! taken a replay page down, the Internet Archive's closest snapshot of the
+//! same URL is scraped instead.
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+/// The URL of the archived copy closest to now, or None when the page was
+/// never archived.
+pub fn find_snapshot(url: &str) -> Result<Option<String>> {
+    let query = format!("https://archive.org/wayback/available?url={}", url);
+
+    let data: Value = crate::http::get(&query)
+        .and_then(|response| Ok(response.error_for_status()?))
+        .context("Failed to query the Wayback Machine")?
+        .json()
+        .context("Failed to parse the Wayback Machine response")?;
+
+    let Some(closest) = data.pointer("/archived_snapshots/closest") else {
+        return Ok(None);
+    };
+    if closest.get("available").and_then(|v| v.as_bool()) != Some(true)
+        || closest.get("status").and_then(|v| v.as_str()).is_some_and(|status| status != "200")
+    {
+        return Ok(None);
+    }
+
+    Ok(closest.get("url").and_then(|v| v.as_str()).map(raw_snapshot_url))
+}
+
+/// Snapshot URLs look like `https://web.archive.org/web/<timestamp>/<url>`;
+/// `<timestamp>id_` serves the page as captured, without the archive's
+/// toolbar and rewritten links.
+fn raw_snapshot_url(snapshot: &str) -> String {
+    let Some((prefix, rest)) = snapshot.split_once("/web/") else {
+        return snapshot.to_string();
+    };
+    match rest.split_once('/') {
+        Some((timestamp, original)) if !timestamp.ends_with("id_") => {
+            format!("{}/web/{}id_/{}", prefix, timestamp, original)
+        }
+        _ => snapshot.to_string(),
+    }
+}
+
+/// The capture time from a snapshot URL, as YYYY-MM-DD.
+pub fn snapshot_date(snapshot: &str) -> Option<String> {
+    let timestamp = snapshot.split("/web/").nth(1)?.get(..8)?;
+    chrono::NaiveDate::parse_from_str(timestamp, "%Y%m%d")
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+pub fn fetch_snapshot(snapshot: &str) -> Result<String> {
+    let response = crate::http::get(snapshot)
+        .context("Failed to fetch the archived page")?;
+    if !response.status().is_success() {
+        bail!("The Wayback Machine answered {} for {}", response.status(), snapshot);
+    }
+
+    response.text().context("Failed to read the archived page")
+}
@@ -0,0 +1,90 @@
+//! `audit-mapping`: app IDs in the dataset that the mapping has no name for,
+//! most played first, with what the appdetails cache knows about each. The
+//! list is written as CSV with `app_id` first, which `details --from FILE`
+//! reads to fetch them.
+
+use anyhow::{Context, Result};
+use std::fs;
+
+use crate::dataset::{self, escape_csv_field};
+use crate::details;
+
+pub const UNMAPPED_FILENAME: &str = "unmapped_appids.csv";
+
+pub struct AuditOptions {
+    pub mapping_path: String,
+    pub output: String,
+}
+
+pub fn audit_mapping(inputs: &[String], options: &AuditOptions) -> Result<()> {
+    let rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+
+    let games = crate::analytics::summarize(&rows, &mapping);
+    let mut unmapped: Vec<_> = games.iter().filter(|game| game.name.is_empty()).collect();
+    unmapped.sort_by_key(|game| (std::cmp::Reverse(game.playtime_seconds), game.app_id.clone()));
+
+    let total_seconds: u64 = games.iter().map(|game| game.playtime_seconds).sum();
+    let unmapped_seconds: u64 = unmapped.iter().map(|game| game.playtime_seconds).sum();
+
+    println!(
+        "{} of {} games in the dataset have no name in {}",
+        unmapped.len(),
+        games.len(),
+        options.mapping_path
+    );
+    if unmapped.is_empty() {
+        return Ok(());
+    }
+    println!(
+        "They account for {:.1} of {:.1} hours ({:.1}%)\n",
+        unmapped_seconds as f64 / 3600.0,
+        total_seconds as f64 / 3600.0,
+        unmapped_seconds as f64 / total_seconds.max(1) as f64 * 100.0
+    );
+
+    let mut content = String::from("app_id,playtime_in_seconds,years,status\n");
+    println!("{:<12} {:>9} {:<20} Status", "App ID", "Hours", "Years");
+    for game in &unmapped {
+        let status = cache_status(&game.app_id);
+        let years: Vec<&str> = game.years.iter().map(|(year, _)| year.as_str()).collect();
+        println!(
+            "{:<12} {:>9.1} {:<20} {}",
+            game.app_id,
+            game.playtime_seconds as f64 / 3600.0,
+            years.join(" "),
+            status
+        );
+        content.push_str(&format!(
+            "{},{},{},{}\n",
+            escape_csv_field(&game.app_id),
+            game.playtime_seconds,
+            years.join(" "),
+            escape_csv_field(&status)
+        ));
+    }
+
+    fs::write(&options.output, content)
+        .with_context(|| format!("Failed to write {}", options.output))?;
+    crate::summary::file_written(&options.output);
+
+    println!("\nUnmapped app IDs saved to: {}", options.output);
+    println!("Fetch them with 'details --from {}', then rerun map-games", options.output);
+
+    Ok(())
+}
+
+/// What the appdetails cache says about a game the mapping lacks.
+fn cache_status(app_id: &str) -> String {
+    if app_id.contains(':') {
+        return "imported without a name".to_string();
+    }
+    match details::cached(app_id) {
+        Ok(Some(entry)) => match details::data(&entry).and_then(|d| d.get("name")).and_then(|n| n.as_str()) {
+            Some(name) => format!("cached as '{}'; rerun map-games", name),
+            None => "no store data (delisted or region-locked)".to_string(),
+        },
+        Ok(None) => "not fetched yet".to_string(),
+        Err(e) => format!("unreadable cache entry: {:#}", e),
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde_json::Value;
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config;
+use crate::http;
+use crate::session::{self, Session};
+
+const KEYRING_SERVICE: &str = "steamreplay";
+const KEYRING_API_KEY_USER: &str = "steam-web-api-key";
+
+/// Stores the Web API key in the OS keyring. When no key is given on the
+/// command line it is read from stdin so it stays out of shell history.
+pub fn set_key(key: Option<&str>) -> Result<()> {
+    let key = match key {
+        Some(key) => key.trim().to_string(),
+        None => {
+            print!("Steam Web API key: ");
+            io::stdout().flush()?;
+            let mut line = String::new();
+            io::stdin().lock().read_line(&mut line)
+                .context("Failed to read key from stdin")?;
+            line.trim().to_string()
+        }
+    };
+
+    if key.len() != 32 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("That doesn't look like a Steam Web API key (expected 32 hex characters)");
+    }
+
+    api_key_entry()?.set_password(&key).with_context(|| {
+        format!(
+            "Failed to store the key in the OS keyring; set STEAM_API_KEY or api_key in {} instead",
+            config::config_path().map(|p| p.display().to_string()).unwrap_or_default()
+        )
+    })?;
+
+    println!("API key stored in the OS keyring");
+
+    Ok(())
+}
+
+/// Resolves the Web API key: explicit flag, then STEAM_API_KEY, then the OS
+/// keyring, then `api_key` in config.toml.
+pub fn resolve_api_key(flag: Option<String>) -> Result<Option<String>> {
+    if let Some(key) = flag {
+        return Ok(Some(key));
+    }
+
+    if let Ok(key) = env::var("STEAM_API_KEY")
+        && !key.is_empty()
+    {
+        return Ok(Some(key));
+    }
+
+    match api_key_entry()?.get_password() {
+        Ok(key) => return Ok(Some(key)),
+        // No keyring service at all (e.g. headless Linux): fall through quietly
+        Err(keyring::Error::NoEntry | keyring::Error::PlatformFailure(_)) => {}
+        Err(e) => println!("Warning: Could not read the OS keyring: {}", e),
+    }
+
+    Ok(config::load()?.api_key)
+}
+
+fn api_key_entry() -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_API_KEY_USER)
+        .context("Failed to open the OS keyring")
+}
+
+/// Resolves the `steamLoginSecure` cookie: STEAM_LOGIN_SECURE, then the
+/// saved session (refreshed when close to expiry), then config.toml.
+pub fn resolve_login_cookie() -> Result<Option<String>> {
+    if let Ok(cookie) = env::var("STEAM_LOGIN_SECURE")
+        && !cookie.is_empty()
+    {
+        return Ok(Some(cookie));
+    }
+
+    if let Some(mut session) = session::load()? {
+        let expiring = cookie_expiry(&session.steam_login_secure)
+            .is_some_and(|expires| expires < chrono::Utc::now() + chrono::Duration::minutes(10));
+        if !expiring {
+            return Ok(Some(session.steam_login_secure));
+        }
+
+        // An expired cookie is still returned: requests just run logged out
+        let Some(refresh_token) = session.refresh_token.clone() else {
+            println!("Warning: The saved Steam session has expired; run 'auth login' or 'auth import-cookies'");
+            return Ok(Some(session.steam_login_secure));
+        };
+
+        match refresh_access_token(&refresh_token, &session.steam_id) {
+            Ok(access_token) => {
+                session.steam_login_secure = format!("{}%7C%7C{}", session.steam_id, access_token);
+                session.saved_at = chrono::Utc::now().to_rfc3339();
+                session::save(&session)?;
+                println!("Refreshed the saved Steam session");
+                return Ok(Some(session.steam_login_secure));
+            }
+            Err(e) => {
+                println!("Warning: Could not refresh the Steam session ({}); run 'auth login'", e);
+                return Ok(Some(session.steam_login_secure));
+            }
+        }
+    }
+
+    Ok(config::load()?.steam_login_secure)
+}
+
+fn refresh_access_token(refresh_token: &str, steam_id: &str) -> Result<String> {
+    let request = http::client()
+        .post(format!("{}/GenerateAccessTokenForApp/v1/", AUTH_API))
+        .form(&[("refresh_token", refresh_token), ("steamid", steam_id)]);
+
+    let response: Value = http::send(request)
+        .context("Failed to reach api.steampowered.com")?
+        .error_for_status()
+        .context("Refresh token rejected")?
+        .json()
+        .context("Failed to parse refresh response")?;
+
+    response
+        .get("response")
+        .and_then(|r| json_string(r, "access_token"))
+        .context("Refresh response has no access token")
+}
+
+const AUTH_API: &str = "https://api.steampowered.com/IAuthenticationService";
+
+/// Logs in by QR code: the Steam mobile app scans the code and approves the
+/// session. (Steam's OpenID only proves identity and yields no session
+/// cookies, so it can't unlock private replay pages.)
+pub fn login() -> Result<()> {
+    let request = http::client()
+        .post(format!("{}/BeginAuthSessionViaQR/v1/", AUTH_API))
+        .form(&[("device_friendly_name", "steamreplay"), ("platform_type", "2")]);
+
+    let begin: Value = http::send(request)
+        .context("Failed to start a login session")?
+        .json()
+        .context("Failed to parse login session response")?;
+
+    let begin = begin.get("response").context("Malformed login session response")?;
+    let mut client_id = json_string(begin, "client_id")
+        .context("Login session has no client_id")?;
+    let request_id = json_string(begin, "request_id")
+        .context("Login session has no request_id")?;
+    let challenge_url = json_string(begin, "challenge_url")
+        .context("Login session has no challenge_url")?;
+    let interval = begin.get("interval").and_then(|v| v.as_f64()).unwrap_or(5.0);
+
+    println!("Scan this code with the Steam mobile app (Steam Guard > scan QR code):\n");
+    print_qr_code(&challenge_url)?;
+
+    let deadline = Instant::now() + Duration::from_secs(300);
+
+    let (access_token, refresh_token, account_name) = loop {
+        if Instant::now() > deadline {
+            bail!("Timed out waiting for the login to be approved");
+        }
+        thread::sleep(Duration::from_secs_f64(interval));
+
+        let request = http::client()
+            .post(format!("{}/PollAuthSessionStatus/v1/", AUTH_API))
+            .form(&[("client_id", client_id.as_str()), ("request_id", request_id.as_str())]);
+
+        let poll: Value = http::send(request)
+            .context("Failed to poll login status")?
+            .error_for_status()
+            .context("Login session expired or was denied")?
+            .json()
+            .context("Failed to parse login status")?;
+
+        let Some(poll) = poll.get("response") else {
+            continue;
+        };
+
+        // The QR challenge rotates while waiting; show the fresh one
+        if let Some(new_url) = json_string(poll, "new_challenge_url") {
+            if let Some(new_client_id) = json_string(poll, "new_client_id") {
+                client_id = new_client_id;
+            }
+            println!("\nThe code was refreshed, scan this one instead:\n");
+            print_qr_code(&new_url)?;
+        }
+
+        if let Some(access_token) = json_string(poll, "access_token") {
+            break (
+                access_token,
+                json_string(poll, "refresh_token"),
+                json_string(poll, "account_name"),
+            );
+        }
+    };
+
+    let steam_id = jwt_claims(&access_token)
+        .and_then(|claims| claims.get("sub").and_then(|v| v.as_str()).map(|s| s.to_string()))
+        .context("Access token has no Steam ID")?;
+
+    let session = Session {
+        steam_login_secure: format!("{}%7C%7C{}", steam_id, access_token),
+        steam_id,
+        account_name,
+        refresh_token,
+        saved_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let path = session::save(&session)?;
+
+    println!(
+        "\nLogged in as {} ({})",
+        session.account_name.as_deref().unwrap_or("unknown"),
+        session.steam_id
+    );
+    println!("Session saved to: {}", path.display());
+
+    Ok(())
+}
+
+fn print_qr_code(data: &str) -> Result<()> {
+    let code = qrcode::QrCode::new(data.as_bytes())
+        .context("Failed to encode QR code")?;
+    let rendered = code
+        .render::<qrcode::render::unicode::Dense1x2>()
+        .quiet_zone(true)
+        .build();
+    println!("{}", rendered);
+    println!("\n{}", data);
+    Ok(())
+}
+
+fn json_string(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+/// Validates the configured credentials. Returns an error (non-zero exit)
+/// when anything configured is invalid, so scheduled jobs fail fast.
+pub fn check() -> Result<()> {
+    let mut failures = Vec::new();
+
+    match resolve_api_key(None)? {
+        Some(key) => match check_api_key(&key) {
+            Ok(true) => println!("API key: OK"),
+            Ok(false) => {
+                println!("API key: INVALID");
+                failures.push("API key rejected; run 'auth set-key' with a new key");
+            }
+            Err(e) => {
+                println!("API key: could not verify ({})", e);
+                failures.push("Could not reach the Web API to verify the API key");
+            }
+        },
+        None => println!("API key: not configured"),
+    }
+
+    match resolve_login_cookie()? {
+        Some(cookie) => {
+            let expiry = cookie_expiry(&cookie);
+            // A token past its expiry can't be valid; skip the round trip
+            let status = match expiry {
+                Some(expires) if expires < chrono::Utc::now() => Ok(false),
+                _ => check_login_cookie(&cookie),
+            };
+            match status {
+                Ok(true) => {
+                    println!("Session cookie: OK");
+                    if let Some(expires) = expiry {
+                        let days_left = (expires - chrono::Utc::now()).num_days();
+                        println!(
+                            "  Expires: {} (in {} days)",
+                            expires.format("%Y-%m-%d %H:%M UTC"),
+                            days_left
+                        );
+                    }
+                }
+                Ok(false) => {
+                    println!("Session cookie: EXPIRED or invalid");
+                    if let Some(expires) = expiry {
+                        println!("  Expired: {}", expires.format("%Y-%m-%d %H:%M UTC"));
+                    }
+                    failures.push("Session cookie no longer accepted; re-login needed");
+                }
+                Err(e) => {
+                    println!("Session cookie: could not verify ({})", e);
+                    failures.push("Could not reach the store to verify the session cookie");
+                }
+            }
+        }
+        None => println!("Session cookie: not configured"),
+    }
+
+    if !failures.is_empty() {
+        bail!(failures.join("\n"));
+    }
+
+    Ok(())
+}
+
+fn check_api_key(key: &str) -> Result<bool> {
+    // Any cheap keyed call works; Steam answers 403 for unknown keys
+    let url = format!(
+        "https://api.steampowered.com/ISteamUser/GetPlayerSummaries/v2/?key={}&steamids=76561197960435530",
+        key
+    );
+
+    let response = http::get(&url)
+        .context("Failed to reach api.steampowered.com")?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::UNAUTHORIZED {
+        return Ok(false);
+    }
+    response
+        .error_for_status()
+        .map_err(http::redact_error)
+        .context("Unexpected API response")?;
+
+    Ok(true)
+}
+
+fn check_login_cookie(cookie: &str) -> Result<bool> {
+    // Only answers with a web API token when the session is logged in
+    let request = http::client()
+        .get("https://store.steampowered.com/pointssummary/ajaxgetasyncconfig")
+        .header(reqwest::header::COOKIE, format!("steamLoginSecure={}", cookie));
+
+    let response = http::send(request)
+        .context("Failed to reach store.steampowered.com")?;
+
+    let data: Value = response.json()
+        .context("Failed to parse response")?;
+
+    Ok(data.get("success").and_then(|v| v.as_i64()) == Some(1)
+        && data
+            .get("data")
+            .and_then(|d| d.get("webapi_token"))
+            .and_then(|t| t.as_str())
+            .is_some_and(|t| !t.is_empty()))
+}
+
+/// Reads the expiry out of the JWT embedded in `steamLoginSecure`
+/// ("<steamid>%7C%7C<jwt>").
+fn cookie_expiry(cookie: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let token = cookie.replace("%7C", "|");
+    let claims = jwt_claims(token.rsplit("||").next()?)?;
+    chrono::DateTime::from_timestamp(claims.get("exp")?.as_i64()?, 0)
+}
+
+/// Decodes (without verifying) the payload of a Steam JWT.
+fn jwt_claims(jwt: &str) -> Option<Value> {
+    let payload = URL_SAFE_NO_PAD.decode(jwt.split('.').nth(1)?).ok()?;
+    serde_json::from_slice(&payload).ok()
+}
@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Value, json};
+use std::fs;
+
+use crate::community;
+use crate::i18n::t;
+
+pub fn scrape_profile_badges(profile: &str, year: Option<&str>) -> Result<()> {
+    let profile_url = community::build_profile_url(profile);
+    let year = year
+        .map(|y| y.to_string())
+        .unwrap_or_else(|| chrono::Utc::now().year().to_string());
+
+    println!("Fetching badges for: {} ({})", profile_url, year);
+
+    let html_content = crate::http::get(&format!("{}/badges/", profile_url))
+        .context("Failed to fetch the badges page")?
+        .text()
+        .context("Failed to read response body")?;
+
+    let document = Html::parse_document(&html_content);
+
+    let level = select_text(&document, ".profile_xp_block .friendPlayerLevelNum")
+        .and_then(|text| text.parse::<u64>().ok());
+    // "XP 12,345" / "12,345 XP"
+    let xp = select_text(&document, ".profile_xp_block_xp")
+        .map(|text| text.chars().filter(|c| c.is_ascii_digit()).collect::<String>())
+        .and_then(|digits| digits.parse::<u64>().ok());
+
+    println!("  Level: {}", level.map(|l| l.to_string()).unwrap_or_else(|| "unknown".to_string()));
+    println!("  XP: {}", xp.map(|x| x.to_string()).unwrap_or_else(|| "unknown".to_string()));
+
+    let row_selector = Selector::parse(".badge_row").expect("Failed to create selector");
+    let mut badges = Vec::new();
+
+    for row in document.select(&row_selector) {
+        let Some(unlocked) = element_text(&row, ".badge_info_unlocked") else {
+            continue;
+        };
+        let Some(date) = parse_unlock_date(&unlocked) else {
+            continue;
+        };
+        if date.year().to_string() != year {
+            continue;
+        }
+
+        let name = element_text(&row, ".badge_info_title")
+            .or_else(|| element_text(&row, ".badge_title"))
+            .unwrap_or_else(|| "Unknown badge".to_string());
+        let badge_xp = element_text(&row, ".badge_info_description")
+            .and_then(|text| parse_badge_xp(&text));
+
+        badges.push(json!({
+            "name": name,
+            "unlocked": date.to_string(),
+            "xp": badge_xp,
+        }));
+    }
+
+    println!("  Found {} badges earned in {}", badges.len(), year);
+
+    let profile_id = profile_url.rsplit('/').next().unwrap_or("unknown");
+    let output_filename = format!("steam_profile_{}_{}.json", profile_id, year);
+    let output = json!({
+        "profile_url": profile_url,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "year": year,
+        "level": level,
+        "xp": xp,
+        "badges": badges,
+    });
+
+    fs::write(&output_filename, serde_json::to_string_pretty(&output)?)
+        .context("Failed to write profile file")?;
+
+    crate::summary::file_written(&output_filename);
+    println!("\nProfile data saved to: {}", output_filename);
+
+    Ok(())
+}
+
+/// Prints the profile section of the report from a file written by `profile-badges`.
+pub fn print_profile_section(path: &str) -> Result<()> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+    let data: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path))?;
+
+    let year = data.get("year").and_then(|v| v.as_str()).unwrap_or("unknown");
+
+    crate::report::print_heading(&t!("profile-title"));
+
+    if let Some(level) = data.get("level").and_then(|v| v.as_u64()) {
+        println!("  {}", t!("profile-level", level = level));
+    }
+    if let Some(xp) = data.get("xp").and_then(|v| v.as_u64()) {
+        println!("  {}", t!("profile-xp", xp = xp));
+    }
+
+    let badges = data.get("badges").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    println!("  {}", t!("profile-badges-earned", year = year, count = badges.len()));
+
+    for badge in &badges {
+        let name = badge
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| t!("profile-unknown-badge"));
+        let unlocked = badge.get("unlocked").and_then(|v| v.as_str()).unwrap_or("");
+        match badge.get("xp").and_then(|v| v.as_u64()) {
+            Some(xp) => println!("    {} {:<40} {:>6} XP", unlocked, name, xp),
+            None => println!("    {} {}", unlocked, name),
+        }
+    }
+
+    Ok(())
+}
+
+fn select_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).expect("Failed to create selector");
+    let text = document.select(&selector).next()?.text().collect::<String>();
+    Some(text.trim().to_string())
+}
+
+fn element_text(element: &ElementRef, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).expect("Failed to create selector");
+    let text = element.select(&selector).next()?.text().collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+fn parse_unlock_date(text: &str) -> Option<NaiveDate> {
+    // "Unlocked Mar 5, 2023 @ 3:27pm", or "Unlocked Mar 5 @ 3:27pm" for the current year
+    let date = text.strip_prefix("Unlocked ")?.split(" @").next()?.trim();
+    NaiveDate::parse_from_str(date, "%b %d, %Y").ok().or_else(|| {
+        let with_year = format!("{}, {}", date, chrono::Utc::now().year());
+        NaiveDate::parse_from_str(&with_year, "%b %d, %Y").ok()
+    })
+}
+
+fn parse_badge_xp(text: &str) -> Option<u64> {
+    // "Level 5, 1,200 XP"
+    let before_xp = text.split(" XP").next()?;
+    before_xp.split_whitespace().last()?.replace(',', "").parse().ok()
+}
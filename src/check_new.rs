@@ -0,0 +1,104 @@
+//! `check-new`: checks whether an account's replay for a year (by default
+//! the current one) has been published, for running from cron around
+//! Valve's December launch:
+//!
+//! ```sh
+//! steamreplay check-new 76561198069815823 --webhook https://discord.com/api/webhooks/... --scrape
+//! ```
+//!
+//! The run exits with `EXIT_NOT_PUBLISHED` until the replay is out, so
+//! `check-new ... && ...` chains work too. Once it's out the webhook gets
+//! one JSON POST (with both Slack's `text` and Discord's `content`);
+//! later runs see that in the data directory and don't post again.
+
+use anyhow::{Context, Result, bail};
+use chrono::Datelike;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config;
+
+/// Exit status while the replay isn't published yet
+pub const EXIT_NOT_PUBLISHED: i32 = 3;
+/// `<steamid>/<year>` of the replays a webhook was already sent for
+const NOTIFIED_FILENAME: &str = "check_new_notified.json";
+
+pub struct CheckOptions {
+    /// Defaults to the current year
+    pub year: Option<i32>,
+    pub webhook: Option<String>,
+    /// Scrape the replay as soon as it's published
+    pub scrape: bool,
+}
+
+pub fn check_new(steam_id: &str, options: &CheckOptions) -> Result<()> {
+    if !steam_id.chars().all(|c| c.is_ascii_digit()) {
+        bail!("'{}' is not a SteamID64; pass the numeric ID or a replay URL", steam_id);
+    }
+    let year = options.year.unwrap_or_else(|| chrono::Local::now().year());
+    let url = format!("{}/{}/{}", crate::REPLAY_URL_BASE, steam_id, year);
+
+    println!("Checking for the {} replay of {}...", year, steam_id);
+    let cookie = crate::auth::resolve_login_cookie()?;
+    if !crate::replay_published(steam_id, year, cookie.as_deref())? {
+        println!("Not published yet: {}", url);
+        crate::summary::set_exit_status(EXIT_NOT_PUBLISHED);
+        return Ok(());
+    }
+    println!("Published: {}", url);
+
+    if let Some(webhook) = &options.webhook {
+        let key = format!("{}/{}", steam_id, year);
+        let mut notified = load_notified()?;
+        if notified.contains(&key) {
+            println!("Webhook already notified for this replay");
+        } else {
+            let message = format!("The {} Steam Replay for {} is out: {}", year, steam_id, url);
+            let body = json!({
+                "text": message,
+                "content": message,
+                "steamid": steam_id,
+                "year": year,
+                "url": url,
+            });
+            crate::http::send(crate::http::client().post(webhook).json(&body))
+                .and_then(|response| Ok(response.error_for_status()?))
+                .context("Failed to call the webhook")?;
+            println!("Webhook notified");
+
+            notified.insert(key);
+            save_notified(&notified)?;
+        }
+    }
+
+    if options.scrape {
+        println!();
+        crate::scrape_replay(&url, &crate::ScrapeOptions::default())?;
+    }
+
+    Ok(())
+}
+
+fn notified_path() -> Result<PathBuf> {
+    Ok(config::data_dir()?.join(NOTIFIED_FILENAME))
+}
+
+fn load_notified() -> Result<BTreeSet<String>> {
+    let path = notified_path()?;
+    if !path.exists() {
+        return Ok(BTreeSet::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn save_notified(notified: &BTreeSet<String>) -> Result<()> {
+    let path = notified_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(notified)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
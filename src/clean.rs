@@ -0,0 +1,105 @@
+//! `clean`: frees the space used by the managed directories. Caches can
+//! always be refetched; the config directory (credentials, session,
+//! config.toml) is never touched.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+pub struct CleanOptions {
+    /// Cached appdetails and anything else in the cache except artwork
+    pub cache: bool,
+    /// Downloaded artwork
+    pub art: bool,
+    /// The whole cache and data directories
+    pub all: bool,
+}
+
+pub fn clean(options: &CleanOptions) -> Result<()> {
+    let cache_dir = config::cache_dir()?;
+    let artwork_dir = config::artwork_dir()?;
+    let data_dir = config::data_dir()?;
+
+    if !(options.cache || options.art || options.all) {
+        println!("Managed directories:");
+        print_usage("Cache", &cache_dir);
+        print_usage("  artwork", &artwork_dir);
+        print_usage("Data", &data_dir);
+        print_usage("Config", &config::config_dir()?);
+        println!("\nPass --cache, --art or --all to remove them (config is never removed)");
+        return Ok(());
+    }
+
+    let mut targets: Vec<PathBuf> = Vec::new();
+    if options.all {
+        targets.push(cache_dir);
+        targets.push(data_dir);
+    } else {
+        if options.cache && cache_dir.exists() {
+            for entry in fs::read_dir(&cache_dir)
+                .with_context(|| format!("Failed to read {}", cache_dir.display()))?
+            {
+                let path = entry?.path();
+                if path != artwork_dir {
+                    targets.push(path);
+                }
+            }
+        }
+        if options.art {
+            targets.push(artwork_dir);
+        }
+    }
+
+    let mut freed = 0;
+    for path in targets.iter().filter(|path| path.exists()) {
+        let size = disk_usage(path);
+        remove(path)?;
+        println!("Removed {} ({})", path.display(), format_size(size));
+        freed += size;
+    }
+
+    println!("Freed {}", format_size(freed));
+
+    Ok(())
+}
+
+fn print_usage(label: &str, path: &Path) {
+    if path.exists() {
+        println!("  {:<10} {} ({})", label, path.display(), format_size(disk_usage(path)));
+    } else {
+        println!("  {:<10} {} (empty)", label, path.display());
+    }
+}
+
+fn remove(path: &Path) -> Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+    .with_context(|| format!("Failed to remove {}", path.display()))
+}
+
+/// Total size of the files under `path`, in bytes.
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| disk_usage(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
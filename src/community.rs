@@ -0,0 +1,165 @@
+use anyhow::{Context, Result, bail};
+use scraper::{Html, Selector};
+use serde_json::Value;
+use std::fs;
+
+pub fn scrape_community_games(profile: &str, name_template: Option<&str>) -> Result<()> {
+    let profile_url = build_profile_url(profile);
+    println!("Fetching community games list for: {}", profile_url);
+
+    // The HTML page embeds the full list as JSON; fall back to the legacy XML feed
+    let games = match fetch_games_json(&profile_url) {
+        Ok(games) if !games.is_empty() => games,
+        Ok(_) => {
+            println!("  No games in page data, trying XML variant...");
+            fetch_games_xml(&profile_url)?
+        }
+        Err(e) => {
+            println!("  Page data unavailable ({}), trying XML variant...", e);
+            fetch_games_xml(&profile_url)?
+        }
+    };
+
+    let mut csv_rows: Vec<(String, u64, String)> = games
+        .into_iter()
+        .filter(|game| game.playtime_seconds > 0)
+        .map(|game| {
+            let year = game
+                .last_played
+                .filter(|&ts| ts > 0)
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                .map(|dt| dt.format("%Y").to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            (game.app_id, game.playtime_seconds, year)
+        })
+        .collect();
+
+    println!("  Found {} games with recorded playtime", csv_rows.len());
+
+    csv_rows.sort_by(|a, b| a.2.cmp(&b.2).then(a.0.cmp(&b.0)));
+
+    // Same schema as steam_replay_data.csv; community playtime is lifetime, not per-month
+    let profile_id = profile_url.trim_end_matches('/').rsplit('/').next().unwrap_or("unknown");
+    let csv_filename = crate::template::render(
+        name_template.unwrap_or("steam_community_data_{steamid}.csv"),
+        &[("steamid", profile_id)],
+    )?;
+    crate::template::create_parent_dir(std::path::Path::new(&csv_filename))?;
+    let mut csv_content = String::from("app_id,playtime_in_seconds,year,month\n");
+
+    for (app_id, playtime_seconds, year) in csv_rows {
+        csv_content.push_str(&format!("{},{},{},lifetime\n", app_id, playtime_seconds, year));
+    }
+
+    fs::write(&csv_filename, csv_content)
+        .context("Failed to write CSV file")?;
+
+    crate::summary::file_written(&csv_filename);
+    println!("\nCommunity playtime data saved to: {}", csv_filename);
+
+    Ok(())
+}
+
+struct CommunityGame {
+    app_id: String,
+    playtime_seconds: u64,
+    last_played: Option<i64>,
+}
+
+pub fn build_profile_url(profile: &str) -> String {
+    // Accepts a full profile URL, a SteamID64 or a vanity name
+    if profile.starts_with("http") {
+        let base = profile.split('?').next().unwrap_or(profile).trim_end_matches('/');
+        base.trim_end_matches("/games").to_string()
+    } else if profile.len() == 17 && profile.chars().all(|c| c.is_ascii_digit()) {
+        format!("https://steamcommunity.com/profiles/{}", profile)
+    } else {
+        format!("https://steamcommunity.com/id/{}", profile)
+    }
+}
+
+fn fetch_games_json(profile_url: &str) -> Result<Vec<CommunityGame>> {
+    let url = format!("{}/games/?tab=all", profile_url);
+
+    let html_content = crate::http::get(&url)
+        .context("Failed to fetch the games page")?
+        .text()
+        .context("Failed to read response body")?;
+
+    let document = Html::parse_document(&html_content);
+    let selector = Selector::parse("[data-profile-gameslist]")
+        .expect("Failed to create selector");
+
+    let config = document
+        .select(&selector)
+        .next()
+        .and_then(|element| element.value().attr("data-profile-gameslist"))
+        .context("No data-profile-gameslist attribute (profile may be private)")?;
+
+    let data: Value = serde_json::from_str(config)
+        .context("Failed to parse games list JSON")?;
+
+    let games = data
+        .get("rgGames")
+        .and_then(|v| v.as_array())
+        .context("Games list JSON has no rgGames array")?;
+
+    Ok(games
+        .iter()
+        .filter_map(|game| {
+            let app_id = game.get("appid")?.as_u64()?;
+            // playtime_forever is in minutes
+            let minutes = game.get("playtime_forever").and_then(|v| v.as_u64()).unwrap_or(0);
+            Some(CommunityGame {
+                app_id: app_id.to_string(),
+                playtime_seconds: minutes * 60,
+                last_played: game.get("rtime_last_played").and_then(|v| v.as_i64()),
+            })
+        })
+        .collect())
+}
+
+fn fetch_games_xml(profile_url: &str) -> Result<Vec<CommunityGame>> {
+    let url = format!("{}/games/?tab=all&xml=1", profile_url);
+
+    let xml = crate::http::get(&url)
+        .context("Failed to fetch the XML games list")?
+        .text()
+        .context("Failed to read response body")?;
+
+    if let Some(error) = extract_tag(&xml, "error") {
+        bail!("Steam returned an error: {}", error);
+    }
+
+    let mut games = Vec::new();
+
+    for block in xml.split("<game>").skip(1) {
+        let Some(app_id) = extract_tag(block, "appID") else {
+            continue;
+        };
+        // hoursOnRecord looks like "1,234.5" and is absent for unplayed games
+        let hours = extract_tag(block, "hoursOnRecord")
+            .and_then(|h| h.replace(',', "").parse::<f64>().ok())
+            .unwrap_or(0.0);
+        games.push(CommunityGame {
+            app_id,
+            playtime_seconds: (hours * 3600.0).round() as u64,
+            last_played: None,
+        });
+    }
+
+    Ok(games)
+}
+
+fn extract_tag(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    let value = xml[start..end].trim();
+    let value = value
+        .strip_prefix("<![CDATA[")
+        .and_then(|v| v.strip_suffix("]]>"))
+        .unwrap_or(value);
+    Some(value.to_string())
+}
@@ -0,0 +1,212 @@
+//! `compare-global`: places the dataset next to Valve's published global
+//! replay figures. The figures aren't available from an API, so they are
+//! read from a baseline file the user fills in from Valve's announcements:
+//!
+//! ```json
+//! {
+//!   "source": "https://store.steampowered.com/news/...",
+//!   "years": {
+//!     "2024": {
+//!       "average_games_played": 4.0,
+//!       "new_release_share": 15.0,
+//!       "platform_share": { "deck": 5.0, "linux": 2.0 },
+//!       "top_games": ["730", "570"]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Every field is optional; shares are percentages.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use crate::dataset::{self, PlaytimeRow};
+use crate::i18n::t;
+use crate::report::game_name;
+
+pub const BASELINE_FILENAME: &str = "global_replay_baseline.json";
+
+#[derive(Debug, Deserialize)]
+struct Baseline {
+    source: Option<String>,
+    years: BTreeMap<String, YearBaseline>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YearBaseline {
+    average_games_played: Option<f64>,
+    new_release_share: Option<f64>,
+    #[serde(default)]
+    platform_share: BTreeMap<String, f64>,
+    #[serde(default)]
+    top_games: Vec<String>,
+}
+
+pub fn compare_global(inputs: &[String], baseline_path: &str, mapping_path: &str) -> Result<()> {
+    if !Path::new(baseline_path).exists() {
+        bail!(
+            "No baseline file at {}; create one from Valve's published replay figures \
+             ({{\"source\": ..., \"years\": {{\"2024\": {{\"average_games_played\", \"new_release_share\", \
+             \"platform_share\", \"top_games\"}}}}}}) or pass --baseline FILE",
+            baseline_path
+        );
+    }
+
+    let content = fs::read_to_string(baseline_path)
+        .with_context(|| format!("Failed to read {}", baseline_path))?;
+    let baseline: Baseline = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", baseline_path))?;
+
+    let rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(mapping_path)?;
+    let platform_sessions = load_platform_sessions(inputs)?;
+
+    let totals_by_year = crate::analytics::yearly_totals(&rows);
+
+    let title = t!("compare-title");
+    println!("{}", title);
+    println!("{}", "=".repeat(title.chars().count()));
+    if let Some(source) = &baseline.source {
+        println!("{}", t!("compare-baseline", source = source.as_str()));
+    }
+
+    let games_label = t!("compare-games-played");
+    let new_release_label = t!("compare-new-release-playtime");
+    let label_width = games_label.chars().count().max(new_release_label.chars().count()) + 1;
+
+    for (year, games) in totals_by_year {
+        let Some(global) = baseline.years.get(year) else {
+            println!("\n{}", t!("compare-no-figures", year = year, path = baseline_path));
+            continue;
+        };
+
+        println!("\n{}", year);
+
+        if let Some(average) = global.average_games_played {
+            println!(
+                "  {:<width$}{:>6}  {}",
+                games_label,
+                games.len(),
+                t!("compare-global-average", value = format!("{:.1}", average)),
+                width = label_width
+            );
+        }
+
+        if let Some(global_share) = global.new_release_share {
+            match new_release_share(year, &games) {
+                Some(share) => println!(
+                    "  {:<width$}{:>5.1}%  {}",
+                    new_release_label,
+                    share,
+                    t!("compare-global-share", value = format!("{:.1}", global_share)),
+                    width = label_width
+                ),
+                None => println!(
+                    "  {:<width$}{:>6}  {}",
+                    new_release_label,
+                    "-",
+                    t!("compare-no-release-dates"),
+                    width = label_width
+                ),
+            }
+        }
+
+        if !global.platform_share.is_empty() {
+            // Platforms overlap (a Deck session is also a Linux one), so
+            // shares are taken against total_sessions rather than their sum
+            let sessions = platform_sessions.get(year);
+            let total = sessions.and_then(|s| s.get("total")).copied().unwrap_or(0);
+            println!("  {}", t!("compare-sessions-by-platform"));
+            for (platform, global_share) in &global.platform_share {
+                let mine = sessions
+                    .and_then(|s| s.get(platform))
+                    .filter(|_| total > 0)
+                    .map(|&count| format!("{:.1}%", count as f64 / total as f64 * 100.0))
+                    .unwrap_or_else(|| "-".to_string());
+                println!(
+                    "    {:<18} {:>6}  {}",
+                    platform,
+                    mine,
+                    t!("compare-global-share", value = format!("{:.1}", global_share))
+                );
+            }
+        }
+
+        if !global.top_games.is_empty() {
+            let hours: HashMap<&str, f64> = games.iter().map(|row| (row.app_id.as_str(), row.hours())).collect();
+            let played = global.top_games.iter().filter(|id| hours.contains_key(id.as_str())).count();
+            println!("  {}", t!("compare-top-games-played", played = played, total = global.top_games.len()));
+            for (rank, app_id) in global.top_games.iter().enumerate() {
+                let mine = hours
+                    .get(app_id.as_str())
+                    .map(|h| format!("{:.1}h", h))
+                    .unwrap_or_else(|| "-".to_string());
+                println!("    {:>2}. {:<40} {:>8}", rank + 1, game_name(&mapping, app_id), mine);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Share of the year's playtime spent on games released that year, using
+/// cached appdetails only. None when no release dates are known.
+fn new_release_share(year: &str, games: &[&PlaytimeRow]) -> Option<f64> {
+    let mut known_seconds = 0;
+    let mut new_seconds = 0;
+
+    for row in games {
+        let Ok(Some(entry)) = crate::details::cached(&row.app_id) else {
+            continue;
+        };
+        let Some(released) = crate::details::release_year(&entry) else {
+            continue;
+        };
+        known_seconds += row.playtime_seconds;
+        if released.to_string() == year {
+            new_seconds += row.playtime_seconds;
+        }
+    }
+
+    (known_seconds > 0).then(|| new_seconds as f64 / known_seconds as f64 * 100.0)
+}
+
+/// year -> platform -> session count ("total" included), from the
+/// `*_sessions` counters in the replay's `playtime_stats.total_stats` when
+/// the scraped page has them.
+fn load_platform_sessions(inputs: &[String]) -> Result<HashMap<String, BTreeMap<String, u64>>> {
+    let mut result = HashMap::new();
+
+    for input in inputs.iter().filter(|input| input.ends_with(".json")) {
+        let content = fs::read_to_string(input)
+            .with_context(|| format!("Failed to read {}", input))?;
+        let data: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", input))?;
+
+        let Some(stats) = data
+            .pointer("/data/data-yearinreview/playtime_stats/total_stats")
+            .and_then(|v| v.as_object())
+        else {
+            continue;
+        };
+
+        let sessions: BTreeMap<String, u64> = stats
+            .iter()
+            .filter_map(|(key, value)| {
+                let platform = key.strip_suffix("_sessions")?;
+                Some((platform.to_string(), value.as_u64().unwrap_or(0)))
+            })
+            .collect();
+
+        if !sessions.is_empty() {
+            result.insert(crate::extract_year_from_data(&data, input), sessions);
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,109 @@
+//! User configuration, read from `config.toml` in the platform config
+//! directory (e.g. ~/.config/steamreplay on Linux).
+//!
+//! ```toml
+//! api_key = "..."
+//! # Left out of every dataset, report and lookup
+//! exclude_appids = [431960]
+//! # When set, only these apps are kept
+//! include_appids = []
+//! ```
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    pub api_key: Option<String>,
+    /// Value of the store's `steamLoginSecure` cookie, for private replay pages
+    pub steam_login_secure: Option<String>,
+    /// Apps that aren't games (Wallpaper Engine, streaming tools, ...)
+    #[serde(default)]
+    pub exclude_appids: Vec<u64>,
+    /// When non-empty, every other app is dropped
+    #[serde(default)]
+    pub include_appids: Vec<u64>,
+    /// Steam Family members, name -> SteamID64, for `family`
+    #[serde(default)]
+    pub family: BTreeMap<String, String>,
+    /// Home Assistant instance for `push-hass`
+    pub hass: Option<crate::hass::HassConfig>,
+    /// Yearly targets for `goals`
+    #[serde(default)]
+    pub goals: Vec<crate::goals::Goal>,
+}
+
+/// The config's `include_appids` / `exclude_appids` lists.
+pub struct AppFilter {
+    include: HashSet<String>,
+    exclude: HashSet<String>,
+}
+
+impl AppFilter {
+    pub fn allows(&self, app_id: &str) -> bool {
+        (self.include.is_empty() || self.include.contains(app_id)) && !self.exclude.contains(app_id)
+    }
+}
+
+/// The app filter from the config file, loaded once per run.
+pub fn app_filter() -> Result<&'static AppFilter> {
+    static FILTER: OnceLock<AppFilter> = OnceLock::new();
+    if let Some(filter) = FILTER.get() {
+        return Ok(filter);
+    }
+
+    let config = load()?;
+    let ids = |ids: Vec<u64>| ids.into_iter().map(|id| id.to_string()).collect();
+    Ok(FILTER.get_or_init(|| AppFilter {
+        include: ids(config.include_appids),
+        exclude: ids(config.exclude_appids),
+    }))
+}
+
+fn project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from("", "", "steamreplay")
+        .context("Could not determine the user's home directory")
+}
+
+pub fn config_dir() -> Result<PathBuf> {
+    Ok(project_dirs()?.config_dir().to_path_buf())
+}
+
+/// Platform cache directory (e.g. ~/.cache/steamreplay on Linux). Everything
+/// in it can be refetched; `clean` empties it.
+pub fn cache_dir() -> Result<PathBuf> {
+    Ok(project_dirs()?.cache_dir().to_path_buf())
+}
+
+/// Downloaded store artwork, shared by every output directory.
+pub fn artwork_dir() -> Result<PathBuf> {
+    Ok(cache_dir()?.join("artwork"))
+}
+
+/// Platform data directory (e.g. ~/.local/share/steamreplay on Linux), for
+/// state the tool keeps between runs.
+pub fn data_dir() -> Result<PathBuf> {
+    Ok(project_dirs()?.data_dir().to_path_buf())
+}
+
+pub fn config_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("config.toml"))
+}
+
+/// Loads the config file; a missing file yields the defaults.
+pub fn load() -> Result<Config> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
@@ -0,0 +1,194 @@
+//! Folding of demos, playtests and beta branches into their base game.
+//! Steam tracks their playtime under separate app IDs, which splits a
+//! game's hours across several rows of the dataset and the reports.
+//!
+//! The base game comes from the cached appdetails' `fullgame` link (set on
+//! demos), else from the mapped name: "Foo Demo", "Foo Playtest" or
+//! "Foo (Beta)" fold into the one app named "Foo". Only the appdetails cache
+//! is read, so run `map-games` (which fills it) first; apps it doesn't
+//! cover fall back to the name heuristics. `to-csv` and `report` fold only
+//! with `--fold-demos`, so by default both keep Steam's own rows.
+
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use crate::dataset::PlaytimeRow;
+use crate::details;
+
+/// Rewrites the rows of demos, playtests and betas to their base game's app
+/// ID, summing rows that then share an app, year and month.
+pub fn fold_related_apps(rows: Vec<PlaytimeRow>, mapping: &HashMap<String, String>) -> Vec<PlaytimeRow> {
+    let mut app_ids: Vec<&str> = rows.iter().map(|row| row.app_id.as_str()).collect();
+    app_ids.sort_unstable();
+    app_ids.dedup();
+    let bases = base_games(&app_ids, mapping);
+    if bases.is_empty() {
+        return rows;
+    }
+
+    // Only worth a line when the base game has rows of its own to merge with
+    for (app_id, base) in bases.iter().filter(|(_, base)| app_ids.binary_search(&base.as_str()).is_ok()) {
+        println!(
+            "  Folding {} into {}",
+            mapping.get(app_id).map(|name| format!("{} ({})", name, app_id)).unwrap_or_else(|| app_id.clone()),
+            mapping.get(base).map(|name| format!("{} ({})", name, base)).unwrap_or_else(|| base.clone()),
+        );
+    }
+    crate::summary::count("apps_folded", bases.len() as u64);
+
+    // Keeps the order rows first appeared in
+    let mut folded: Vec<PlaytimeRow> = Vec::with_capacity(rows.len());
+    let mut positions: HashMap<(String, String, String), usize> = HashMap::new();
+    for mut row in rows {
+        if let Some(base) = bases.get(&row.app_id) {
+            row.app_id = base.clone();
+        }
+        let key = (row.app_id.clone(), row.year.clone(), row.month.clone());
+        match positions.get(&key) {
+            Some(&index) => folded[index].playtime_seconds += row.playtime_seconds,
+            None => {
+                positions.insert(key, folded.len());
+                folded.push(row);
+            }
+        }
+    }
+    folded
+}
+
+/// app_id -> base game's app ID, for the apps in `app_ids` that are a
+/// demo, playtest or beta of another game.
+fn base_games(app_ids: &[&str], mapping: &HashMap<String, String>) -> BTreeMap<String, String> {
+    let by_name = unique_names(mapping);
+
+    let mut bases = BTreeMap::new();
+    for &app_id in app_ids {
+        let entry = details::cached(app_id).ok().flatten();
+        if let Some(base) = base_game(app_id, entry.as_ref(), mapping, &by_name) {
+            bases.insert(app_id.to_string(), base);
+        }
+    }
+    bases
+}
+
+/// Lowercased name -> app ID, for names only one app has.
+fn unique_names(mapping: &HashMap<String, String>) -> HashMap<String, Option<&str>> {
+    let mut by_name: HashMap<String, Option<&str>> = HashMap::new();
+    for (app_id, name) in mapping {
+        by_name
+            .entry(name.trim().to_lowercase())
+            .and_modify(|found| *found = None)
+            .or_insert(Some(app_id.as_str()));
+    }
+    by_name
+}
+
+/// The game `app_id` is a demo, playtest or beta of: the appdetails
+/// `fullgame` link, else the one app named like it without the suffix.
+fn base_game(
+    app_id: &str,
+    entry: Option<&Value>,
+    mapping: &HashMap<String, String>,
+    by_name: &HashMap<String, Option<&str>>,
+) -> Option<String> {
+    let linked = entry.and_then(|entry| {
+        let fullgame = details::data(entry)?.get("fullgame")?.get("appid")?;
+        // appdetails gives the ID as a string
+        fullgame.as_str().map(String::from).or_else(|| fullgame.as_u64().map(|id| id.to_string()))
+    });
+    let named = || {
+        let name = mapping.get(app_id)?;
+        let base = suffix_pattern().captures(name)?.get(1)?.as_str().trim().to_lowercase();
+        by_name.get(&base).copied().flatten().map(String::from)
+    };
+
+    linked.or_else(named).filter(|base| base != app_id)
+}
+
+/// "<base> Demo", "<base> - Playtest", "<base> (Public Beta)" and the like.
+fn suffix_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)^(.+?)(?:[\s:\-–]+[(\[]?|[(\[])\s*(?:free\s+)?(?:demo|playtest|public\s+test(?:\s+server)?|(?:open\s+|public\s+)?beta)\s*[)\]]?$")
+            .expect("the suffix pattern is valid")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_name(name: &str) -> Option<&str> {
+        suffix_pattern().captures(name).and_then(|captures| captures.get(1)).map(|base| base.as_str())
+    }
+
+    fn mapping(names: &[(&str, &str)]) -> HashMap<String, String> {
+        names.iter().map(|(app_id, name)| (app_id.to_string(), name.to_string())).collect()
+    }
+
+    #[test]
+    fn strips_demo_playtest_and_beta_suffixes() {
+        assert_eq!(base_name("Foo Demo"), Some("Foo"));
+        assert_eq!(base_name("Foo Playtest"), Some("Foo"));
+        assert_eq!(base_name("Foo (Beta)"), Some("Foo"));
+        assert_eq!(base_name("Foo - Public Test Server"), Some("Foo"));
+        assert_eq!(base_name("Foo: Free Demo"), Some("Foo"));
+    }
+
+    #[test]
+    fn leaves_names_without_a_separate_suffix() {
+        assert_eq!(base_name("Foo"), None);
+        assert_eq!(base_name("Pandemo"), None);
+        assert_eq!(base_name("Alphabeta"), None);
+        assert_eq!(base_name("Demo"), None);
+    }
+
+    #[test]
+    fn finds_the_base_game_by_name() {
+        let mapping = mapping(&[("1", "Foo"), ("2", "Foo Demo"), ("3", "Foo Playtest"), ("4", "Foo (Beta)")]);
+        let by_name = unique_names(&mapping);
+
+        for app_id in ["2", "3", "4"] {
+            assert_eq!(base_game(app_id, None, &mapping, &by_name).as_deref(), Some("1"), "app {}", app_id);
+        }
+        assert_eq!(base_game("1", None, &mapping, &by_name), None);
+    }
+
+    #[test]
+    fn keeps_a_game_whose_own_name_ends_in_demo() {
+        // No "Wrecking" app to fold into, so this is a game of its own
+        let mapping = mapping(&[("1", "Wrecking Demo"), ("2", "Foo")]);
+        let by_name = unique_names(&mapping);
+
+        assert_eq!(base_game("1", None, &mapping, &by_name), None);
+    }
+
+    #[test]
+    fn skips_names_several_apps_share() {
+        let mapping = mapping(&[("1", "Foo"), ("2", "foo"), ("3", "Foo Demo")]);
+        let by_name = unique_names(&mapping);
+
+        assert_eq!(base_game("3", None, &mapping, &by_name), None);
+    }
+
+    #[test]
+    fn prefers_the_fullgame_link() {
+        let mapping = mapping(&[("1", "Foo"), ("2", "Foo Demo"), ("9", "Foo Remastered")]);
+        let by_name = unique_names(&mapping);
+        let entry = serde_json::json!({ "success": true, "data": { "fullgame": { "appid": "9", "name": "Foo Remastered" } } });
+
+        assert_eq!(base_game("2", Some(&entry), &mapping, &by_name).as_deref(), Some("9"));
+    }
+
+    #[test]
+    fn falls_back_to_the_name_without_a_fullgame_link() {
+        let mapping = mapping(&[("1", "Foo"), ("2", "Foo Demo")]);
+        let by_name = unique_names(&mapping);
+        let entry = serde_json::json!({ "success": true, "data": { "name": "Foo Demo" } });
+        let failed = serde_json::json!({ "success": false });
+
+        assert_eq!(base_game("2", Some(&entry), &mapping, &by_name).as_deref(), Some("1"));
+        assert_eq!(base_game("2", Some(&failed), &mapping, &by_name).as_deref(), Some("1"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Reads the Steam store session cookie straight out of a local browser's
+//! cookie database.
+
+use aes::cipher::{BlockDecryptMut, KeyIvInit, block_padding::Pkcs7};
+use anyhow::{Context, Result, bail};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::session::{self, Session};
+
+const STORE_HOST: &str = "store.steampowered.com";
+const COOKIE_NAME: &str = "steamLoginSecure";
+
+pub fn import_cookies(browser: &str, profile: Option<&str>) -> Result<()> {
+    let database = match profile {
+        Some(path) => PathBuf::from(path),
+        None => find_cookie_database(browser)?,
+    };
+
+    println!("Reading {} cookies from: {}", browser, database.display());
+
+    // Browsers keep the database locked while running; work on a copy,
+    // removed again however this returns
+    let temp_dir = PrivateTempDir::create()?;
+    let copy = temp_dir.path.join("cookies.sqlite");
+    fs::copy(&database, &copy)
+        .with_context(|| format!("Failed to copy {}", database.display()))?;
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = PathBuf::from(format!("{}{}", database.display(), suffix));
+        if sidecar.exists() {
+            fs::copy(&sidecar, temp_dir.path.join(format!("cookies.sqlite{}", suffix)))
+                .with_context(|| format!("Failed to copy {}", sidecar.display()))?;
+        }
+    }
+
+    let cookie = match browser {
+        "firefox" => read_firefox_cookie(&copy),
+        "chrome" | "chromium" => read_chrome_cookie(&copy),
+        other => bail!("Unsupported browser '{}' (expected firefox or chrome)", other),
+    };
+    drop(temp_dir);
+
+    let Some(cookie) = cookie? else {
+        bail!(
+            "No {} cookie for {} found; log in to the store in {} first",
+            COOKIE_NAME,
+            STORE_HOST,
+            browser
+        );
+    };
+
+    // "<steamid>%7C%7C<jwt>"
+    let steam_id = cookie
+        .split("%7C")
+        .next()
+        .filter(|id| id.chars().all(|c| c.is_ascii_digit()))
+        .context("Cookie does not look like a steamLoginSecure value")?
+        .to_string();
+
+    let session = Session {
+        steam_id,
+        account_name: None,
+        refresh_token: None,
+        steam_login_secure: cookie,
+        saved_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let path = session::save(&session)?;
+
+    println!("Imported session for Steam ID {}", session.steam_id);
+    println!("Session saved to: {}", path.display());
+
+    Ok(())
+}
+
+/// A directory in the system temp dir only the current user can open,
+/// deleted with everything in it when dropped.
+struct PrivateTempDir {
+    path: PathBuf,
+}
+
+impl PrivateTempDir {
+    fn create() -> Result<PrivateTempDir> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos())
+            .unwrap_or_default();
+        let path = env::temp_dir().join(format!("steamreplay-cookies-{}-{:08x}", std::process::id(), nanos));
+
+        let mut builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        // Fails rather than reuse a directory someone else created there
+        builder
+            .create(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+
+        Ok(PrivateTempDir { path })
+    }
+}
+
+impl Drop for PrivateTempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn find_cookie_database(browser: &str) -> Result<PathBuf> {
+    let home = env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .map(PathBuf::from)
+        .context("Could not determine the home directory")?;
+    let app_data = env::var("APPDATA").map(PathBuf::from).unwrap_or_default();
+    let local_app_data = env::var("LOCALAPPDATA").map(PathBuf::from).unwrap_or_default();
+
+    let candidates: Vec<PathBuf> = match browser {
+        "firefox" => {
+            let profile_roots = [
+                home.join(".mozilla/firefox"),
+                home.join("snap/firefox/common/.mozilla/firefox"),
+                home.join("Library/Application Support/Firefox/Profiles"),
+                app_data.join("Mozilla/Firefox/Profiles"),
+            ];
+            profile_roots
+                .iter()
+                .filter_map(|root| fs::read_dir(root).ok())
+                .flat_map(|entries| entries.flatten())
+                .map(|entry| entry.path().join("cookies.sqlite"))
+                .collect()
+        }
+        "chrome" | "chromium" => {
+            let profile_dirs = [
+                home.join(".config/google-chrome/Default"),
+                home.join(".config/chromium/Default"),
+                home.join("Library/Application Support/Google/Chrome/Default"),
+                local_app_data.join("Google/Chrome/User Data/Default"),
+            ];
+            profile_dirs
+                .iter()
+                .flat_map(|dir| [dir.join("Network/Cookies"), dir.join("Cookies")])
+                .collect()
+        }
+        other => bail!("Unsupported browser '{}' (expected firefox or chrome)", other),
+    };
+
+    // Most recently used profile wins
+    candidates
+        .into_iter()
+        .filter(|path| path.is_file())
+        .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .with_context(|| format!("No {} cookie database found; pass --profile <path>", browser))
+}
+
+fn read_firefox_cookie(database: &Path) -> Result<Option<String>> {
+    let connection = rusqlite::Connection::open(database)?;
+
+    let mut statement = connection.prepare(
+        "SELECT value FROM moz_cookies WHERE host LIKE ?1 AND name = ?2 ORDER BY expiry DESC LIMIT 1",
+    )?;
+    let mut rows = statement.query((format!("%{}", STORE_HOST), COOKIE_NAME))?;
+
+    match rows.next()? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+fn read_chrome_cookie(database: &Path) -> Result<Option<String>> {
+    let connection = rusqlite::Connection::open(database)?;
+
+    let mut statement = connection.prepare(
+        "SELECT value, encrypted_value FROM cookies WHERE host_key LIKE ?1 AND name = ?2 \
+         ORDER BY expires_utc DESC LIMIT 1",
+    )?;
+    let mut rows = statement.query((format!("%{}", STORE_HOST), COOKIE_NAME))?;
+
+    let Some(row) = rows.next()? else {
+        return Ok(None);
+    };
+
+    let value: String = row.get(0)?;
+    if !value.is_empty() {
+        return Ok(Some(value));
+    }
+
+    let encrypted: Vec<u8> = row.get(1)?;
+
+    // Database version 24+ prefixes the plaintext with a SHA-256 of the host
+    let version: i64 = connection
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    decrypt_chrome_value(&encrypted, version >= 24).map(Some)
+}
+
+/// Chrome on Linux ("v10", fixed password) and macOS (Keychain password)
+/// encrypt cookies with AES-128-CBC. Windows DPAPI / app-bound encryption
+/// and the Linux keyring-backed "v11" scheme are not supported.
+fn decrypt_chrome_value(encrypted: &[u8], has_host_hash: bool) -> Result<String> {
+    let Some(ciphertext) = encrypted.strip_prefix(b"v10") else {
+        bail!(
+            "This Chrome cookie uses an encryption scheme that can't be read here; \
+             copy steamLoginSecure from the browser into STEAM_LOGIN_SECURE instead"
+        );
+    };
+
+    let (password, iterations) = if cfg!(target_os = "macos") {
+        let password = keyring::Entry::new("Chrome Safe Storage", "Chrome")
+            .and_then(|entry| entry.get_password())
+            .context("Failed to read Chrome's key from the Keychain")?;
+        (password, 1003)
+    } else {
+        ("peanuts".to_string(), 1)
+    };
+
+    let mut key = [0u8; 16];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password.as_bytes(), b"saltysalt", iterations, &mut key);
+
+    let mut buffer = ciphertext.to_vec();
+    let plaintext = cbc::Decryptor::<aes::Aes128>::new(&key.into(), &[b' '; 16].into())
+        .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+        .map_err(|_| anyhow::anyhow!("Failed to decrypt the Chrome cookie"))?;
+
+    let plaintext = if has_host_hash && plaintext.len() > 32 {
+        &plaintext[32..]
+    } else {
+        plaintext
+    };
+
+    String::from_utf8(plaintext.to_vec()).context("Decrypted cookie is not valid text")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_os = "macos"))]
+    use aes::cipher::BlockEncryptMut;
+
+    /// Encrypts like Chrome on Linux: "v10", then AES-128-CBC with the
+    /// "peanuts" key. macOS takes its key from the Keychain instead.
+    #[cfg(not(target_os = "macos"))]
+    fn encrypt_v10(plaintext: &[u8]) -> Vec<u8> {
+        let mut key = [0u8; 16];
+        pbkdf2::pbkdf2_hmac::<sha1::Sha1>(b"peanuts", b"saltysalt", 1, &mut key);
+        let ciphertext = cbc::Encryptor::<aes::Aes128>::new(&key.into(), &[b' '; 16].into())
+            .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+        [b"v10".as_slice(), &ciphertext].concat()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn decrypts_v10_cookies() {
+        let encrypted = encrypt_v10(b"76561198000000000%7C%7Ctoken");
+
+        assert_eq!(decrypt_chrome_value(&encrypted, false).unwrap(), "76561198000000000%7C%7Ctoken");
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn strips_the_host_hash_from_newer_databases() {
+        let mut plaintext = vec![0xab; 32];
+        plaintext.extend_from_slice(b"76561198000000000%7C%7Ctoken");
+
+        assert_eq!(
+            decrypt_chrome_value(&encrypt_v10(&plaintext), true).unwrap(),
+            "76561198000000000%7C%7Ctoken"
+        );
+    }
+
+    #[test]
+    fn refuses_other_encryption_schemes() {
+        let error = decrypt_chrome_value(b"v11\x01\x02\x03", false).unwrap_err().to_string();
+
+        assert!(error.contains("STEAM_LOGIN_SECURE"), "{}", error);
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn fails_on_corrupt_ciphertext() {
+        let mut encrypted = encrypt_v10(b"token");
+        encrypted.truncate(encrypted.len() - 1);
+
+        assert!(decrypt_chrome_value(&encrypted, false).is_err());
+    }
+}
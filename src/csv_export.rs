@@ -0,0 +1,477 @@
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::dataset::{self, PlaytimeRow};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Field {
+    AppId,
+    GameName,
+    PlaytimeSeconds,
+    PlaytimeMinutes,
+    Hours,
+    Year,
+    Month,
+    /// From `overrides.toml`, separated by semicolons
+    Tags,
+    Notes,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Field> {
+        match name {
+            "app_id" => Some(Field::AppId),
+            "game_name" | "game" => Some(Field::GameName),
+            "playtime_in_seconds" | "seconds" => Some(Field::PlaytimeSeconds),
+            "minutes" => Some(Field::PlaytimeMinutes),
+            "hours" => Some(Field::Hours),
+            "year" => Some(Field::Year),
+            "month" => Some(Field::Month),
+            "tags" => Some(Field::Tags),
+            "notes" => Some(Field::Notes),
+            _ => None,
+        }
+    }
+
+    fn value(&self, row: &PlaytimeRow, mapping: &HashMap<String, String>, decimal_comma: bool) -> String {
+        let value = match self {
+            Field::AppId => row.app_id.clone(),
+            Field::GameName => mapping.get(&row.app_id).cloned().unwrap_or_default(),
+            Field::PlaytimeSeconds => row.playtime_seconds.to_string(),
+            Field::PlaytimeMinutes => format!("{:.0}", row.playtime_seconds as f64 / 60.0),
+            Field::Hours => format!("{:.2}", row.hours()),
+            Field::Year => row.year.clone(),
+            Field::Month => row.month.clone(),
+            Field::Tags => crate::overrides::tags(&row.app_id).join(";"),
+            Field::Notes => crate::overrides::get(&row.app_id).and_then(|entry| entry.notes.clone()).unwrap_or_default(),
+        };
+
+        match self {
+            Field::PlaytimeMinutes | Field::Hours if decimal_comma => value.replace('.', ","),
+            _ => value,
+        }
+    }
+}
+
+pub struct Column {
+    pub field: Field,
+    pub header: String,
+}
+
+pub fn default_columns() -> Vec<Column> {
+    parse_columns("app_id,playtime_in_seconds,year,month").expect("default columns are valid")
+}
+
+/// Parses `--columns app_id,game_name=Game,hours`: columns in output order,
+/// each optionally renamed with `=Header`.
+pub fn parse_columns(spec: &str) -> Result<Vec<Column>> {
+    let mut columns = Vec::new();
+
+    for part in spec.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let (name, header) = match part.split_once('=') {
+            Some((name, header)) => (name.trim(), header.trim()),
+            None => (part, part),
+        };
+        let Some(field) = Field::parse(name) else {
+            bail!(
+                "Unknown column '{}' (available: app_id, game_name, playtime_in_seconds, minutes, hours, year, month, tags, notes)",
+                name
+            );
+        };
+        columns.push(Column {
+            field,
+            header: header.to_string(),
+        });
+    }
+
+    if columns.is_empty() {
+        bail!("--columns needs at least one column");
+    }
+
+    Ok(columns)
+}
+
+pub struct CsvOptions {
+    /// Also write one CSV per input, next to that input
+    pub split_per_file: bool,
+    /// Write the combined steam_replay_data.csv
+    pub combined: bool,
+    pub columns: Vec<Column>,
+    /// app_id -> name mapping used by the game_name column
+    pub mapping_path: String,
+    pub format: CsvFormat,
+    /// Merge into an existing combined CSV instead of overwriting it
+    pub merge: bool,
+    /// Fail on payloads that don't match the typed replay schema (set by the
+    /// global `--strict`)
+    pub strict: bool,
+    /// Roll monthly rows up into quarters or seasons
+    pub group_by: Option<dataset::Grouping>,
+    /// Name of the combined CSV; only `{date}` applies
+    pub name_template: String,
+    /// Name of each `--split-per-file` CSV, next to its input
+    pub split_name_template: String,
+    /// Only these months, with yearly totals recomputed from them
+    pub months: Option<dataset::MonthSet>,
+    /// Only games whose mapped names match
+    pub name_filter: Option<dataset::NameFilter>,
+    /// Fold demos, playtests and betas into their base game
+    pub fold_demos: bool,
+    /// Row order within each year
+    pub sort_by: SortKey,
+    pub descending: bool,
+}
+
+/// `--sort-by`: rows are ordered by year, then by this key.
+#[derive(Debug, Clone, Copy)]
+pub enum SortKey {
+    Playtime,
+    Name,
+    Month,
+    AppId,
+}
+
+impl SortKey {
+    pub fn parse(name: &str) -> Result<SortKey> {
+        match name {
+            "playtime" | "hours" => Ok(SortKey::Playtime),
+            "name" | "game_name" => Ok(SortKey::Name),
+            "month" => Ok(SortKey::Month),
+            "app_id" => Ok(SortKey::AppId),
+            other => bail!("Unknown sort key '{}' (expected playtime, name, month or app_id)", other),
+        }
+    }
+}
+
+pub const NAME_TEMPLATE: &str = "steam_replay_data.csv";
+pub const SPLIT_NAME_TEMPLATE: &str = "steam_replay_data_{steamid}_{year}.csv";
+
+/// Output dialect. `excel` adds a BOM and CRLF line endings so Excel detects
+/// UTF-8, and follows the locale's decimal separator (switching the field
+/// separator to ';' where the decimal separator is ',').
+pub struct CsvFormat {
+    pub delimiter: char,
+    pub decimal_comma: bool,
+    pub line_ending: &'static str,
+    pub bom: bool,
+}
+
+impl CsvFormat {
+    pub fn standard() -> CsvFormat {
+        CsvFormat {
+            delimiter: ',',
+            decimal_comma: false,
+            line_ending: "\n",
+            bom: false,
+        }
+    }
+
+    pub fn excel(locale: Option<&str>) -> CsvFormat {
+        let decimal_comma = locale
+            .map(|l| l.to_string())
+            .or_else(system_locale)
+            .is_some_and(|l| uses_decimal_comma(&l));
+
+        CsvFormat {
+            delimiter: if decimal_comma { ';' } else { ',' },
+            decimal_comma,
+            line_ending: "\r\n",
+            bom: true,
+        }
+    }
+
+    fn escape(&self, field: &str) -> String {
+        if field.contains([self.delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+fn uses_decimal_comma(locale: &str) -> bool {
+    // "de_DE.UTF-8", "pt-BR", "fr" -> language code
+    let language = locale
+        .split(['_', '-', '.'])
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    matches!(
+        language.as_str(),
+        "de" | "fr" | "es" | "it" | "pt" | "nl" | "ru" | "pl" | "cs" | "sk" | "sv" | "da" | "nb"
+            | "nn" | "no" | "fi" | "tr" | "id" | "uk" | "el" | "hu" | "ro" | "sl" | "hr" | "bg"
+            | "lt" | "lv" | "et" | "ca" | "vi"
+    )
+}
+
+pub fn convert_to_csv(json_files: &[String], options: &CsvOptions) -> Result<()> {
+    println!("Converting {} JSON file(s) to CSV...", json_files.len());
+
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+    // Rendered up front so a bad template fails before any work
+    let csv_filename = crate::template::render(&options.name_template, &[])?;
+
+    let mut csv_rows: Vec<PlaytimeRow> = Vec::new();
+    // (file, error) for inputs that couldn't be converted
+    let mut failures: Vec<(String, String)> = Vec::new();
+
+    crate::progress::phase("convert", json_files.len());
+    for (index, json_file) in json_files.iter().enumerate() {
+        crate::progress::item(json_file, index);
+        println!("Processing: {}", json_file);
+
+        match convert_file(json_file, options, &mapping) {
+            Ok(file_rows) => csv_rows.extend(file_rows),
+            Err(e) => {
+                println!("  Error: {:#}", e);
+                crate::summary::failure(json_file.as_str(), format!("{:#}", e))?;
+                failures.push((json_file.clone(), format!("{:#}", e)));
+            }
+        }
+    }
+
+    crate::summary::write_failure_report(&failures, json_files.len())?;
+    if !json_files.is_empty() && failures.len() == json_files.len() {
+        bail!("None of the {} input file(s) could be converted", json_files.len());
+    }
+
+    if options.columns.iter().any(|column| matches!(column.field, Field::GameName)) {
+        let mut unmapped: Vec<&str> = csv_rows
+            .iter()
+            .map(|row| row.app_id.as_str())
+            .filter(|app_id| !mapping.contains_key(*app_id))
+            .collect();
+        unmapped.sort();
+        unmapped.dedup();
+        if !unmapped.is_empty() {
+            println!("  Warning: {} games missing from the mapping; run map-games first", unmapped.len());
+        }
+        for app_id in unmapped {
+            crate::summary::failure(app_id, "Not in the mapping")?;
+        }
+    }
+
+    if options.combined {
+        let csv_filename = Path::new(&csv_filename);
+        crate::template::create_parent_dir(csv_filename)?;
+        if options.merge && csv_filename.exists() {
+            // Group first so keys line up with an already grouped file
+            if let Some(grouping) = options.group_by {
+                csv_rows = dataset::group_rows(csv_rows, grouping);
+            }
+            csv_rows = merge_with_existing(csv_filename, csv_rows, &options.format)?;
+        }
+        write_playtime_csv(csv_filename, csv_rows, options, &mapping)?;
+        println!("\nCSV data saved to: {}", csv_filename.display());
+    }
+
+    Ok(())
+}
+
+/// Rows from one replay or master file, writing its own CSV with
+/// `--split-per-file`.
+fn convert_file(json_file: &str, options: &CsvOptions, mapping: &HashMap<String, String>) -> Result<Vec<PlaytimeRow>> {
+    let file_content = fs::read_to_string(json_file)
+        .with_context(|| format!("Failed to read {}", json_file))?;
+
+    let data: Value = serde_json::from_str(&file_content)
+        .with_context(|| format!("Failed to parse {}", json_file))?;
+
+    if crate::master::is_master(&data) {
+        let master = crate::master::from_value(data, json_file)?;
+        let mut rows = Vec::new();
+        for (steam_id, year, file_rows) in master.player_years() {
+            let file_rows = select_rows(file_rows, options, mapping)?;
+            println!("  {} {}: {} playtime entries", steam_id, year, file_rows.len());
+            if options.split_per_file {
+                let file_csv = split_csv_path(json_file, options, steam_id, year)?;
+                write_playtime_csv(&file_csv, file_rows.clone(), options, mapping)?;
+                println!("  CSV data saved to: {}", file_csv.display());
+            }
+            rows.extend(file_rows);
+        }
+        return Ok(rows);
+    }
+
+    // Extract year from filename or data
+    let year = crate::extract_year_from_data(&data, json_file);
+    println!("  Year: {}", year);
+
+    // Extract playtime data with section tracking
+    let playtime_data = crate::replay::extract_playtime(&data, json_file, options.strict)?;
+    println!("  Found {} playtime entries", playtime_data.len());
+    crate::summary::count("files_processed", 1);
+    crate::summary::count("playtime_entries", playtime_data.len() as u64);
+
+    let file_rows: Vec<PlaytimeRow> = playtime_data
+        .into_iter()
+        .map(|(app_id, playtime_seconds, section)| PlaytimeRow {
+            app_id,
+            playtime_seconds,
+            year: year.clone(),
+            // Convert section to readable month name
+            month: crate::convert_section_to_month(&section),
+        })
+        .collect();
+    let file_rows = select_rows(file_rows, options, mapping)?;
+
+    if options.split_per_file {
+        let steam_id = data
+            .get("url")
+            .and_then(|v| v.as_str())
+            .and_then(crate::extract_steam_id)
+            .unwrap_or("unknown");
+        let file_csv = split_csv_path(json_file, options, steam_id, &year)?;
+        write_playtime_csv(&file_csv, file_rows.clone(), options, mapping)?;
+        println!("  CSV data saved to: {}", file_csv.display());
+    }
+
+    Ok(file_rows)
+}
+
+/// Applies the config's app filter, demo folding, `--filter-name` and
+/// `--months`.
+fn select_rows(rows: Vec<PlaytimeRow>, options: &CsvOptions, mapping: &HashMap<String, String>) -> Result<Vec<PlaytimeRow>> {
+    let mut rows = dataset::filter_apps(rows)?;
+    if options.fold_demos {
+        rows = crate::consolidate::fold_related_apps(rows, mapping);
+    }
+    if let Some(name_filter) = &options.name_filter {
+        rows = name_filter.apply(rows, mapping);
+    }
+    Ok(match &options.months {
+        Some(months) => dataset::filter_months(rows, months),
+        None => rows,
+    })
+}
+
+fn split_csv_path(json_file: &str, options: &CsvOptions, steam_id: &str, year: &str) -> Result<PathBuf> {
+    let name = crate::template::render(&options.split_name_template, &[("steamid", steam_id), ("year", year)])?;
+    let path = Path::new(json_file).with_file_name(name);
+    crate::template::create_parent_dir(&path)?;
+    Ok(path)
+}
+
+fn write_playtime_csv(
+    csv_filename: &Path,
+    mut csv_rows: Vec<PlaytimeRow>,
+    options: &CsvOptions,
+    mapping: &HashMap<String, String>,
+) -> Result<()> {
+    let format = &options.format;
+    let delimiter = format.delimiter.to_string();
+
+    let headers: Vec<String> = options.columns.iter().map(|c| format.escape(&c.header)).collect();
+    let mut csv_content = if format.bom { String::from("\u{feff}") } else { String::new() };
+    csv_content.push_str(&headers.join(&delimiter));
+    csv_content.push_str(format.line_ending);
+
+    if let Some(grouping) = options.group_by {
+        csv_rows = dataset::group_rows(csv_rows, grouping);
+    }
+
+    let name = |row: &PlaytimeRow| mapping.get(&row.app_id).map(|n| n.to_lowercase()).unwrap_or_default();
+    let period = |row: &PlaytimeRow| dataset::period_order(&row.month);
+    csv_rows.sort_by(|a, b| {
+        let order = match options.sort_by {
+            SortKey::Playtime => a.playtime_seconds.cmp(&b.playtime_seconds),
+            SortKey::Name => name(a).cmp(&name(b)).then(a.app_id.cmp(&b.app_id)),
+            SortKey::Month => period(a).cmp(&period(b)),
+            SortKey::AppId => a.app_id.cmp(&b.app_id).then(period(a).cmp(&period(b))),
+        };
+        let order = if options.descending { order.reverse() } else { order };
+        // Years stay in order; ties fall back to app_id, then month or
+        // period (total first)
+        a.year
+            .cmp(&b.year)
+            .then(order)
+            .then(a.app_id.cmp(&b.app_id))
+            .then(period(a).cmp(&period(b)))
+    });
+
+    for row in csv_rows {
+        let fields: Vec<String> = options
+            .columns
+            .iter()
+            .map(|column| format.escape(&column.field.value(&row, mapping, format.decimal_comma)))
+            .collect();
+        csv_content.push_str(&fields.join(&delimiter));
+        csv_content.push_str(format.line_ending);
+    }
+
+    fs::write(csv_filename, csv_content)
+        .with_context(|| format!("Failed to write {}", csv_filename.display()))?;
+    crate::summary::file_written(csv_filename);
+
+    Ok(())
+}
+
+/// Merges freshly converted rows into an existing CSV, keyed on
+/// (app_id, year, month): new values win, rows not being re-converted stay.
+fn merge_with_existing(path: &Path, new_rows: Vec<PlaytimeRow>, format: &CsvFormat) -> Result<Vec<PlaytimeRow>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut lines = content.trim_start_matches('\u{feff}').lines();
+
+    let header = dataset::split_csv_line(lines.next().unwrap_or(""), format.delimiter);
+    let position = |name: &str| header.iter().position(|h| h == name);
+    let (Some(app_id_col), Some(playtime_col), Some(year_col), Some(month_col)) = (
+        position("app_id"),
+        position("playtime_in_seconds"),
+        position("year"),
+        position("month"),
+    ) else {
+        bail!(
+            "--merge needs {} to have app_id, playtime_in_seconds, year and month columns",
+            path.display()
+        );
+    };
+
+    let mut merged: BTreeMap<(String, String, String), PlaytimeRow> = BTreeMap::new();
+    for line in lines.filter(|l| !l.trim().is_empty()) {
+        let fields = dataset::split_csv_line(line, format.delimiter);
+        let field = |index: usize| fields.get(index).cloned().unwrap_or_default();
+        let row = PlaytimeRow {
+            app_id: field(app_id_col),
+            playtime_seconds: field(playtime_col)
+                .parse()
+                .with_context(|| format!("Invalid playtime in {}: {}", path.display(), line))?,
+            year: field(year_col),
+            month: field(month_col),
+        };
+        merged.insert((row.app_id.clone(), row.year.clone(), row.month.clone()), row);
+    }
+
+    let existing = merged.len();
+    let (mut added, mut updated) = (0, 0);
+
+    for row in new_rows {
+        let key = (row.app_id.clone(), row.year.clone(), row.month.clone());
+        match merged.insert(key, row.clone()) {
+            None => added += 1,
+            Some(old) if old.playtime_seconds != row.playtime_seconds => updated += 1,
+            Some(_) => {}
+        }
+    }
+
+    println!(
+        "\nMerged into {}: {} existing rows, {} added, {} updated",
+        path.display(),
+        existing,
+        added,
+        updated
+    );
+
+    Ok(merged.into_values().collect())
+}
@@ -0,0 +1,535 @@
+//! Loading of the tidy playtime dataset (app_id, playtime_in_seconds, year, month)
+//! and the app ID -> name mapping, from CSV outputs, scraped JSON or a merged
+//! master file.
+
+use anyhow::{Context, Result, bail};
+use schemars::JsonSchema;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// One row of the tidy dataset written by `to-csv`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PlaytimeRow {
+    /// Steam app ID
+    pub app_id: String,
+    #[serde(rename = "playtime_in_seconds")]
+    pub playtime_seconds: u64,
+    pub year: String,
+    /// January..December, "total" for the whole year, or "lifetime" for
+    /// local and community imports
+    pub month: String,
+}
+
+impl PlaytimeRow {
+    pub fn hours(&self) -> f64 {
+        self.playtime_seconds as f64 / 3600.0
+    }
+}
+
+/// Calendar position of a month label: "total" first, then January..December.
+pub fn month_order(month: &str) -> usize {
+    if month == "total" {
+        return 0;
+    }
+    (0..12)
+        .find(|&index| crate::get_month_name(index) == month)
+        .map(|index| index + 1)
+        .unwrap_or(13)
+}
+
+/// Coarser periods that monthly rows can be rolled up into (`--group-by`).
+#[derive(Debug, Clone, Copy)]
+pub enum Grouping {
+    Quarter,
+    Season { southern: bool },
+}
+
+const QUARTERS: [&str; 4] = ["Q1", "Q2", "Q3", "Q4"];
+const SEASONS: [&str; 4] = ["Winter", "Spring", "Summer", "Fall"];
+
+impl Grouping {
+    pub fn parse(group_by: &str, hemisphere: Option<&str>) -> Result<Grouping> {
+        let southern = match hemisphere.unwrap_or("north") {
+            "north" => false,
+            "south" => true,
+            other => bail!("Unknown hemisphere '{}' (expected north or south)", other),
+        };
+
+        match group_by {
+            "quarter" => Ok(Grouping::Quarter),
+            "season" => Ok(Grouping::Season { southern }),
+            other => bail!("Unknown grouping '{}' (expected quarter or season)", other),
+        }
+    }
+
+    /// Label of the period a month (0 = January) falls into. Seasons are
+    /// meteorological: December belongs to the same year's winter (or summer
+    /// in the southern hemisphere).
+    pub fn label(&self, month_index: usize) -> &'static str {
+        match self {
+            Grouping::Quarter => QUARTERS[month_index / 3],
+            Grouping::Season { southern } => {
+                let index = ((month_index + 1) % 12) / 3;
+                SEASONS[if *southern { (index + 2) % 4 } else { index }]
+            }
+        }
+    }
+
+    pub fn labels(&self) -> [&'static str; 4] {
+        match self {
+            Grouping::Quarter => QUARTERS,
+            Grouping::Season { southern: false } => SEASONS,
+            Grouping::Season { southern: true } => ["Summer", "Fall", "Winter", "Spring"],
+        }
+    }
+}
+
+/// Rolls monthly rows up into the grouping's periods, summing per game and
+/// year. "total" and "lifetime" rows are kept as they are.
+pub fn group_rows(rows: Vec<PlaytimeRow>, grouping: Grouping) -> Vec<PlaytimeRow> {
+    let mut grouped: Vec<PlaytimeRow> = Vec::new();
+    let mut positions: HashMap<(String, String, &'static str), usize> = HashMap::new();
+
+    for row in rows {
+        let index = month_order(&row.month);
+        if !(1..=12).contains(&index) {
+            grouped.push(row);
+            continue;
+        }
+
+        let label = grouping.label(index - 1);
+        let key = (row.app_id.clone(), row.year.clone(), label);
+        match positions.get(&key) {
+            Some(&position) => grouped[position].playtime_seconds += row.playtime_seconds,
+            None => {
+                positions.insert(key, grouped.len());
+                grouped.push(PlaytimeRow {
+                    month: label.to_string(),
+                    ..row
+                });
+            }
+        }
+    }
+
+    grouped
+}
+
+/// Months selected with `--months` (0 = January).
+pub type MonthSet = [bool; 12];
+
+/// Parses `--months`: a comma-separated list of months or `from..to`
+/// ranges, by number or English name (`6..9`, `jun..sep`, `12,1,2`). A range
+/// may wrap around the new year (`11..2`).
+pub fn parse_months(spec: &str) -> Result<MonthSet> {
+    let month = |token: &str| -> Result<usize> {
+        let token = token.trim().to_lowercase();
+        if let Ok(number) = token.parse::<usize>() {
+            if (1..=12).contains(&number) {
+                return Ok(number - 1);
+            }
+            bail!("Month {} is out of range (1-12)", number);
+        }
+        (0..12)
+            .find(|&index| token.len() >= 3 && crate::get_month_name(index).to_lowercase().starts_with(&token))
+            .with_context(|| format!("Unknown month '{}' in --months", token))
+    };
+
+    let mut months = [false; 12];
+    for part in spec.split(',').filter(|part| !part.trim().is_empty()) {
+        match part.split_once("..") {
+            Some((from, to)) => {
+                let (from, to) = (month(from)?, month(to)?);
+                let length = (to + 12 - from) % 12 + 1;
+                for offset in 0..length {
+                    months[(from + offset) % 12] = true;
+                }
+            }
+            None => months[month(part)?] = true,
+        }
+    }
+
+    if !months.contains(&true) {
+        bail!("--months needs at least one month");
+    }
+
+    Ok(months)
+}
+
+/// Keeps the selected months' rows and replaces each year's "total" rows
+/// with the sum of the kept months, so yearly figures cover only the slice.
+/// Rows without a month ("lifetime") are dropped.
+pub fn filter_months(rows: Vec<PlaytimeRow>, months: &MonthSet) -> Vec<PlaytimeRow> {
+    let mut kept: Vec<PlaytimeRow> = rows
+        .into_iter()
+        .filter(|row| matches!(month_order(&row.month), index @ 1..=12 if months[index - 1]))
+        .collect();
+
+    let mut totals: HashMap<(String, String), u64> = HashMap::new();
+    for row in &kept {
+        *totals.entry((row.app_id.clone(), row.year.clone())).or_default() += row.playtime_seconds;
+    }
+    kept.extend(
+        totals
+            .into_iter()
+            .filter(|(_, seconds)| *seconds > 0)
+            .map(|((app_id, year), playtime_seconds)| PlaytimeRow {
+                app_id,
+                playtime_seconds,
+                year,
+                month: "total".to_string(),
+            }),
+    );
+
+    kept
+}
+
+/// "June..September" style description of a month selection.
+pub fn describe_months(months: &MonthSet) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < 12 {
+        if !months[index] {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index + 1 < 12 && months[index + 1] {
+            index += 1;
+        }
+        parts.push(if start == index {
+            crate::i18n::month_name(start)
+        } else {
+            format!("{}..{}", crate::i18n::month_name(start), crate::i18n::month_name(index))
+        });
+        index += 1;
+    }
+    parts.join(", ")
+}
+
+/// Sort position of a month or period label, for ordering output rows.
+pub fn period_order(label: &str) -> usize {
+    match month_order(label) {
+        13 => QUARTERS
+            .iter()
+            .chain(SEASONS.iter())
+            .position(|&l| l == label)
+            .map(|index| 13 + index % 4)
+            .unwrap_or(17),
+        index => index,
+    }
+}
+
+/// Loads rows from a mix of dataset CSVs, scraped replay JSON and master files.
+pub fn load_rows(inputs: &[String]) -> Result<Vec<PlaytimeRow>> {
+    let mut rows = Vec::new();
+
+    for input in inputs {
+        if input.ends_with(".json") {
+            let file_content = fs::read_to_string(input)
+                .with_context(|| format!("Failed to read {}", input))?;
+
+            let data: Value = serde_json::from_str(&file_content)
+                .with_context(|| format!("Failed to parse {}", input))?;
+
+            if crate::master::is_master(&data) {
+                rows.extend(crate::master::from_value(data, input)?.rows());
+                continue;
+            }
+
+            let year = crate::extract_year_from_data(&data, input);
+
+            for (app_id, playtime_seconds, section) in crate::replay::extract_playtime(&data, input, false)? {
+                rows.push(PlaytimeRow {
+                    app_id,
+                    playtime_seconds,
+                    year: year.clone(),
+                    month: crate::convert_section_to_month(&section),
+                });
+            }
+        } else {
+            rows.extend(load_csv_rows(input)?);
+        }
+    }
+
+    filter_apps(rows)
+}
+
+/// `--filter-name` / `--exclude-name`: regexes matched against the mapped
+/// game name. Games missing from the mapping have no name, so they only
+/// pass when there is no `--filter-name`.
+pub struct NameFilter {
+    include: Option<Regex>,
+    exclude: Option<Regex>,
+}
+
+impl NameFilter {
+    pub fn parse(include: Option<&str>, exclude: Option<&str>) -> Result<Option<NameFilter>> {
+        let compile = |pattern: &str, flag: &str| {
+            Regex::new(pattern).with_context(|| format!("Invalid {} pattern '{}'", flag, pattern))
+        };
+        if include.is_none() && exclude.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(NameFilter {
+            include: include.map(|pattern| compile(pattern, "--filter-name")).transpose()?,
+            exclude: exclude.map(|pattern| compile(pattern, "--exclude-name")).transpose()?,
+        }))
+    }
+
+    pub fn allows(&self, name: Option<&str>) -> bool {
+        let included = match (&self.include, name) {
+            (Some(include), Some(name)) => include.is_match(name),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let excluded = matches!((&self.exclude, name), (Some(exclude), Some(name)) if exclude.is_match(name));
+        included && !excluded
+    }
+
+    pub fn apply(&self, mut rows: Vec<PlaytimeRow>, mapping: &HashMap<String, String>) -> Vec<PlaytimeRow> {
+        rows.retain(|row| self.allows(mapping.get(&row.app_id).map(|name| name.as_str())));
+        rows
+    }
+}
+
+/// Drops the apps the config's `exclude_appids` / `include_appids` leave out.
+pub fn filter_apps(mut rows: Vec<PlaytimeRow>) -> Result<Vec<PlaytimeRow>> {
+    let filter = crate::config::app_filter()?;
+    rows.retain(|row| filter.allows(&row.app_id));
+    Ok(rows)
+}
+
+fn load_csv_rows(path: &str) -> Result<Vec<PlaytimeRow>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+
+    let mut rows = Vec::new();
+
+    for (line_number, line) in content.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = parse_csv_line(line);
+        if fields.len() < 4 {
+            anyhow::bail!("{}:{}: expected 4 columns", path, line_number + 1);
+        }
+        let playtime_seconds = fields[1]
+            .parse()
+            .with_context(|| format!("{}:{}: invalid playtime", path, line_number + 1))?;
+        rows.push(PlaytimeRow {
+            app_id: fields[0].clone(),
+            playtime_seconds,
+            year: fields[2].clone(),
+            month: fields[3].clone(),
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Loads an `app_id,game` mapping CSV, or a JSON/TOML map keyed by app ID
+/// (by extension), with the names from `overrides.toml` applied. A missing
+/// file yields an empty mapping.
+pub fn load_mapping(path: &str) -> Result<HashMap<String, String>> {
+    let mut mapping = load_mapping_with_fields(path)?.0;
+    crate::overrides::apply_names(&mut mapping)?;
+    Ok(mapping)
+}
+
+/// Extra mapping columns from `map-games --fields`: their names, and each
+/// game's values.
+#[derive(Default)]
+pub struct MappingFields {
+    pub names: Vec<String>,
+    pub values: HashMap<String, serde_json::Map<String, Value>>,
+}
+
+/// The mapping with any extra columns. In JSON/TOML, a game is either its
+/// name or an object with `game` and the extra fields.
+pub fn load_mapping_with_fields(path: &str) -> Result<(HashMap<String, String>, MappingFields)> {
+    let mut mapping = HashMap::new();
+    let mut fields = MappingFields::default();
+    if !Path::new(path).exists() {
+        return Ok((mapping, fields));
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+
+    if path.ends_with(".json") || path.ends_with(".toml") {
+        let entries: BTreeMap<String, Value> = if path.ends_with(".json") {
+            serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path))?
+        } else {
+            toml::from_str(&content).with_context(|| format!("Failed to parse {}", path))?
+        };
+        for (app_id, entry) in entries {
+            match entry {
+                Value::String(name) => {
+                    mapping.insert(app_id, name);
+                }
+                Value::Object(mut object) => {
+                    if let Some(Value::String(name)) = object.remove("game") {
+                        mapping.insert(app_id.clone(), name);
+                    }
+                    for key in object.keys() {
+                        if !fields.names.contains(key) {
+                            fields.names.push(key.clone());
+                        }
+                    }
+                    fields.values.insert(app_id, object);
+                }
+                _ => bail!("{}: unexpected entry for app ID {}", path, app_id),
+            }
+        }
+        return Ok((mapping, fields));
+    }
+
+    let mut lines = content.lines();
+    fields.names = lines.next().map(parse_csv_line).unwrap_or_default().into_iter().skip(2).collect();
+    for line in lines {
+        let mut columns = parse_csv_line(line).into_iter();
+        let (Some(app_id), Some(name)) = (columns.next(), columns.next()) else {
+            continue;
+        };
+        if !fields.names.is_empty() {
+            let values = fields
+                .names
+                .iter()
+                .zip(columns)
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(field, value)| (field.clone(), Value::String(value)))
+                .collect();
+            fields.values.insert(app_id.clone(), values);
+        }
+        mapping.insert(app_id, name);
+    }
+
+    Ok((mapping, fields))
+}
+
+/// Writes an `app_id,game` mapping CSV, sorted by app ID, with any extra
+/// columns after the name; `.json` and `.toml` paths get a map keyed by app
+/// ID instead.
+pub fn write_mapping(path: &str, mapping: &HashMap<String, String>, fields: &MappingFields) -> Result<()> {
+    let sorted: BTreeMap<&String, &String> = mapping.iter().collect();
+
+    if path.ends_with(".json") || path.ends_with(".toml") {
+        let entries: BTreeMap<&String, Value> = sorted
+            .into_iter()
+            .map(|(app_id, name)| {
+                let entry = match fields.values.get(app_id).filter(|values| !values.is_empty()) {
+                    Some(values) => {
+                        let mut object = serde_json::Map::new();
+                        object.insert("game".to_string(), Value::String(name.clone()));
+                        object.extend(values.iter().filter(|(_, v)| !v.is_null()).map(|(k, v)| (k.clone(), v.clone())));
+                        Value::Object(object)
+                    }
+                    None => Value::String(name.clone()),
+                };
+                (app_id, entry)
+            })
+            .collect();
+        let content = if path.ends_with(".json") {
+            serde_json::to_string_pretty(&entries)? + "\n"
+        } else {
+            toml::to_string(&entries)?
+        };
+        return fs::write(path, content).with_context(|| format!("Failed to write {}", path));
+    }
+
+    let mut csv_content = String::from("app_id,game");
+    for field in &fields.names {
+        csv_content.push(',');
+        csv_content.push_str(&escape_csv_field(field));
+    }
+    csv_content.push('\n');
+
+    for (app_id, game_name) in sorted {
+        // Escape commas and quotes in game names
+        csv_content.push_str(&format!("{},{}", escape_csv_field(app_id), escape_csv_field(game_name)));
+        for field in &fields.names {
+            let value = match fields.values.get(app_id).and_then(|values| values.get(field)) {
+                Some(Value::String(text)) => text.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            };
+            csv_content.push(',');
+            csv_content.push_str(&escape_csv_field(&value));
+        }
+        csv_content.push('\n');
+    }
+
+    fs::write(path, csv_content)
+        .with_context(|| format!("Failed to write {}", path))
+}
+
+/// Splits one CSV line, honouring quoted fields with doubled quotes.
+pub fn parse_csv_line(line: &str) -> Vec<String> {
+    split_csv_line(line, ',')
+}
+
+/// Like `parse_csv_line`, for files using another delimiter (e.g. ';').
+pub fn split_csv_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+/// Quotes a field when it contains commas, quotes or line breaks.
+pub fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selected(months: MonthSet) -> Vec<usize> {
+        (1..=12).filter(|&month| months[month - 1]).collect()
+    }
+
+    #[test]
+    fn parses_numbers_names_and_lists() {
+        assert_eq!(selected(parse_months("12,1,2").unwrap()), [1, 2, 12]);
+        assert_eq!(selected(parse_months("jun..sep").unwrap()), [6, 7, 8, 9]);
+        assert_eq!(selected(parse_months("March, Sept").unwrap()), [3, 9]);
+    }
+
+    #[test]
+    fn ranges_wrap_around_the_new_year() {
+        assert_eq!(selected(parse_months("11..2").unwrap()), [1, 2, 11, 12]);
+        assert_eq!(selected(parse_months("6..6").unwrap()), [6]);
+    }
+
+    #[test]
+    fn rejects_unknown_and_out_of_range_months() {
+        for spec in ["13", "0", "ju", "smarch", "1..13", "", " , "] {
+            assert!(parse_months(spec).is_err(), "{:?} should not parse", spec);
+        }
+    }
+}
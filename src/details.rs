@@ -0,0 +1,168 @@
+//! Store `appdetails` payloads, cached per game under the user cache
+//! directory so enrichers and reports can reuse them without refetching.
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config;
+
+fn cache_path(app_id: &str) -> Result<PathBuf> {
+    Ok(config::cache_dir()?.join("appdetails").join(format!("{}.json", app_id)))
+}
+
+/// The cached `{ "success": ..., "data": ... }` entry for a game, if any.
+pub fn cached(app_id: &str) -> Result<Option<Value>> {
+    let path = cache_path(app_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let entry = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    Ok(Some(entry))
+}
+
+/// Returns the appdetails entry for a game, from the cache unless `refresh`
+/// is set. Unsuccessful lookups (delisted or region-locked apps) are cached
+/// too, so they aren't retried on every run. With `--offline`, a game that
+/// isn't cached is an error rather than a fetch.
+pub fn load(app_id: &str, refresh: bool) -> Result<Value> {
+    if !refresh && let Some(entry) = cached(app_id)? {
+        return Ok(entry);
+    }
+    if crate::http::offline() {
+        bail!("No cached store details for app ID {} (--offline)", app_id);
+    }
+
+    let url = format!("https://store.steampowered.com/api/appdetails?appids={}", app_id);
+
+    let response = crate::http::get(&url)
+        .and_then(|response| Ok(response.error_for_status()?))
+        .context("Failed to fetch game details")?;
+
+    let data: Value = response.json()
+        .context("Failed to parse response")?;
+
+    // Steam API returns: { "appid": { "success": true/false, "data": {...} } }
+    let entry = data
+        .get(app_id)
+        .cloned()
+        .with_context(|| format!("No entry for app ID {} in the response", app_id))?;
+
+    let path = cache_path(app_id)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&entry)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(entry)
+}
+
+/// The `data` object of a successful entry.
+pub fn data(entry: &Value) -> Option<&Value> {
+    if entry.get("success").and_then(|v| v.as_bool()) == Some(true) {
+        entry.get("data")
+    } else {
+        None
+    }
+}
+
+/// Release year from `release_date.date`, whose format follows the store
+/// language ("18 Apr, 2011", "Apr 18, 2011", ...). None for unreleased games.
+pub fn release_year(entry: &Value) -> Option<i32> {
+    let date = data(entry)?.get("release_date")?.get("date")?.as_str()?;
+    date.split(|c: char| !c.is_ascii_digit())
+        .rfind(|part| part.len() == 4)?
+        .parse()
+        .ok()
+}
+
+/// Fields `map-games --fields` can add to the mapping.
+pub const MAPPING_FIELDS: &[&str] = &["type", "is_free", "release_year", "header_image"];
+
+/// A `MAPPING_FIELDS` value of an entry; null when the store doesn't have it.
+pub fn mapping_field(entry: &Value, field: &str) -> Value {
+    match field {
+        "release_year" => release_year(entry).map(Value::from).unwrap_or(Value::Null),
+        field => data(entry).and_then(|data| data.get(field)).cloned().unwrap_or(Value::Null),
+    }
+}
+
+/// A list field of the store data: `genres` and `categories` hold
+/// `{ "description": ... }` objects, `developers` and `publishers` strings.
+pub fn list_field(entry: &Value, key: &str) -> Vec<String> {
+    data(entry)
+        .and_then(|data| data.get(key))
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().or_else(|| item.get("description")?.as_str()))
+                .map(|item| item.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// App IDs from the first column of a CSV with a header, such as
+/// `audit-mapping`'s output. Imported keys (`<source>:<id>`) are skipped.
+pub fn read_app_ids(path: &str) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+
+    Ok(content
+        .lines()
+        .skip(1)
+        .filter_map(|line| crate::dataset::parse_csv_line(line).into_iter().next())
+        .filter(|app_id| !app_id.is_empty() && !app_id.contains(':'))
+        .collect())
+}
+
+pub fn dump_details(app_ids: &[String], refresh: bool) -> Result<()> {
+    let total = app_ids.len();
+
+    crate::progress::phase("details", total);
+    for (index, app_id) in app_ids.iter().enumerate() {
+        crate::progress::item(app_id, index);
+        println!("[{}/{}] App ID {}", index + 1, total, app_id);
+
+        let entry = match load(app_id, refresh) {
+            Ok(entry) => entry,
+            Err(e) => {
+                println!("  Error fetching app ID {}: {:#}", app_id, e);
+                crate::summary::failure(app_id.as_str(), format!("{:#}", e))?;
+                continue;
+            }
+        };
+
+        let Some(data) = data(&entry) else {
+            println!("  Warning: No data available for app ID {}", app_id);
+            continue;
+        };
+
+        let text = |key: &str| data.get(key).and_then(|v| v.as_str()).unwrap_or("-").to_string();
+        let genres = list_field(&entry, "genres");
+        let release_date = data
+            .get("release_date")
+            .and_then(|d| d.get("date"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("-");
+
+        println!("  Name:     {}", text("name"));
+        println!("  Type:     {}", text("type"));
+        println!("  Released: {}", release_date);
+        println!("  Genres:   {}", if genres.is_empty() { "-".to_string() } else { genres.join(", ") });
+    }
+
+    println!("\nDetails cached in: {}", config::cache_dir()?.join("appdetails").display());
+
+    Ok(())
+}
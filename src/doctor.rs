@@ -0,0 +1,190 @@
+//! `doctor`: checks the things a scrape or enrichment run depends on —
+//! network access to Steam, credentials, writable config and cache
+//! directories and the replay page layout — and prints a fix for each
+//! problem found.
+
+use anyhow::{Context, Result, bail};
+use chrono::Datelike;
+use scraper::{Html, Selector};
+use std::fs;
+use std::path::Path;
+
+use crate::{auth, config, http, session};
+
+/// Public profile used to probe the page layout when no URL is given
+const PROBE_STEAM_ID: &str = "76561198069815823";
+
+const HOSTS: &[(&str, &str)] = &[
+    ("store.steampowered.com", "https://store.steampowered.com/api/appdetails?appids=730&filters=basic"),
+    ("api.steampowered.com", "https://api.steampowered.com/ISteamWebAPIUtil/GetServerInfo/v1/"),
+    ("steamcommunity.com", "https://steamcommunity.com/"),
+];
+
+pub fn run_doctor(replay_url: Option<&str>) -> Result<()> {
+    // Fixes for every problem found, printed at the end
+    let mut fixes: Vec<String> = Vec::new();
+
+    println!("Network");
+    let mut store_reachable = false;
+    let hosts = if http::offline() {
+        println!("  Skipped: --offline");
+        &[][..]
+    } else {
+        HOSTS
+    };
+    for (host, url) in hosts {
+        match http::get(url).and_then(|response| Ok(response.error_for_status()?)) {
+            Ok(_) => {
+                println!("  {}: OK", host);
+                store_reachable |= *host == "store.steampowered.com";
+            }
+            Err(e) => {
+                println!("  {}: FAILED ({})", host, e);
+                let fix = match e.downcast_ref::<reqwest::Error>().and_then(|e| e.status()) {
+                    Some(status) if status.as_u16() == 429 => {
+                        format!("{} is rate limiting this IP; wait a few minutes before long runs", host)
+                    }
+                    Some(status) => format!("{} answered {}; Steam may be down for maintenance (usually Tuesdays)", host, status),
+                    None => format!(
+                        "Can't reach {}; check the network connection, DNS and HTTPS_PROXY settings",
+                        host
+                    ),
+                };
+                fixes.push(fix);
+            }
+        }
+    }
+
+    println!("\nCredentials");
+    if let Err(e) = auth::check() {
+        fixes.extend(format!("{:#}", e).lines().map(|line| line.to_string()));
+    }
+
+    println!("\nDirectories");
+    for (label, dir) in [
+        ("Config", config::config_dir()?),
+        ("Cache", config::cache_dir()?),
+        ("Data", config::data_dir()?),
+    ] {
+        match check_writable(&dir) {
+            Ok(()) => println!("  {}: {} (writable)", label, dir.display()),
+            Err(e) => {
+                println!("  {}: {} (NOT writable: {:#})", label, dir.display(), e);
+                fixes.push(format!("Make {} writable, e.g. chmod u+rwx {}", dir.display(), dir.display()));
+            }
+        }
+    }
+    for path in [session::session_path()?, config::config_dir()?.join("session.key")] {
+        if let Some(mode) = loose_permissions(&path) {
+            println!("  {} is readable by other users (mode {:o})", path.display(), mode);
+            fixes.push(format!("Restrict {} to your user: chmod 600 {}", path.display(), path.display()));
+        }
+    }
+
+    println!("\nReplay page");
+    if store_reachable {
+        let url = replay_url.map(|url| url.to_string()).unwrap_or_else(|| {
+            format!(
+                "https://store.steampowered.com/replay/{}/{}",
+                PROBE_STEAM_ID,
+                chrono::Utc::now().year() - 1
+            )
+        });
+        match probe_replay_page(&url) {
+            Ok(attributes) if attributes.iter().any(|a| a == "data-yearinreview") => {
+                println!("  {}: OK (#application_config with {} data attributes)", url, attributes.len());
+            }
+            Ok(attributes) => {
+                println!(
+                    "  {}: #application_config found but without data-yearinreview ({})",
+                    url,
+                    attributes.join(", ")
+                );
+                fixes.push(
+                    "The replay data is missing from the page: the profile may be private (run 'auth login') \
+                     or Steam changed the page; scrapes will fail until the scraper is updated"
+                        .to_string(),
+                );
+            }
+            Err(e) => {
+                println!("  {}: FAILED ({:#})", url, e);
+                fixes.push(
+                    "The replay page layout changed or the page didn't load; 'scrape' looks for #application_config"
+                        .to_string(),
+                );
+            }
+        }
+    } else if http::offline() {
+        println!("  Skipped: --offline");
+    } else {
+        println!("  Skipped: the store isn't reachable");
+    }
+
+    if fixes.is_empty() {
+        println!("\nEverything looks good");
+        return Ok(());
+    }
+
+    println!("\nTo fix:");
+    for fix in &fixes {
+        println!("  - {}", fix);
+    }
+
+    bail!("doctor found {} problem(s)", fixes.len());
+}
+
+fn check_writable(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let probe = dir.join(".doctor-probe");
+    fs::write(&probe, b"ok")
+        .with_context(|| format!("Failed to write {}", probe.display()))?;
+    fs::remove_file(&probe)
+        .with_context(|| format!("Failed to remove {}", probe.display()))?;
+
+    Ok(())
+}
+
+/// The file's mode when group or others can read it.
+#[cfg(unix)]
+fn loose_permissions(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path).ok()?.permissions().mode() & 0o777;
+    (mode & 0o077 != 0).then_some(mode)
+}
+
+#[cfg(not(unix))]
+fn loose_permissions(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Names of the `data-*` attributes on the page's `#application_config`.
+fn probe_replay_page(url: &str) -> Result<Vec<String>> {
+    let mut request = http::client().get(url);
+    if let Some(cookie) = auth::resolve_login_cookie()? {
+        request = request.header(reqwest::header::COOKIE, format!("steamLoginSecure={}", cookie));
+    }
+
+    let html = http::send(request)
+        .context("Failed to fetch the replay page")?
+        .error_for_status()
+        .context("The replay page returned an error")?
+        .text()
+        .context("Failed to read response body")?;
+
+    let document = Html::parse_document(&html);
+    let selector = Selector::parse("#application_config").expect("Failed to create selector");
+    let element = document
+        .select(&selector)
+        .next()
+        .context("No #application_config element on the page")?;
+
+    Ok(element
+        .value()
+        .attrs()
+        .map(|(name, _)| name)
+        .filter(|name| name.starts_with("data-"))
+        .map(|name| name.to_string())
+        .collect())
+}
@@ -0,0 +1,180 @@
+//! Page schema drift detection for `scrape`. The shape of a scraped page's
+//! `data-*` attributes (every JSON path with its value type, numeric keys
+//! folded together) is compared with the shape the last good scrape of that
+//! year had, or of the newest year when there's none yet. Losing paths,
+//! values changing type, or a payload the replay model can't read means
+//! Valve changed the page: the scrape is tagged `"schema_status":
+//! "unverified"` in its provenance and reported as a failure instead of
+//! quietly joining the archive. New paths alone are fine.
+//!
+//! The expected shapes live in the data directory; `scrape
+//! --accept-schema` records the current page's shape after a change has
+//! been checked.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+const BASELINES_FILENAME: &str = "page_schema.json";
+
+/// Expected shape per replay year
+#[derive(Default, Serialize, Deserialize)]
+struct Baselines(BTreeMap<String, Baseline>);
+
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    fingerprint: String,
+    paths: BTreeSet<String>,
+    updated_at: String,
+}
+
+pub struct Check {
+    pub fingerprint: String,
+    paths: BTreeSet<String>,
+    /// The baseline compared with, as (year, fingerprint)
+    pub baseline: Option<(String, String)>,
+    /// Expected paths the page no longer has (or has with another type)
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+    /// Why the replay model couldn't read the payload
+    pub unreadable: Option<String>,
+}
+
+impl Check {
+    pub fn verified(&self) -> bool {
+        self.removed.is_empty() && self.unreadable.is_none()
+    }
+}
+
+/// Compares a scraped page's attributes (the `data` object of the output)
+/// with the expected shape for `year`.
+pub fn check(data: &Map<String, Value>, year: &str) -> Result<Check> {
+    let mut paths = BTreeSet::new();
+    for (name, value) in data {
+        collect_paths(value, name, &mut paths);
+    }
+    let fingerprint = fingerprint(&paths);
+
+    let baselines = load()?;
+    let baseline = baselines
+        .0
+        .get_key_value(year)
+        .or_else(|| baselines.0.iter().next_back());
+    let (removed, added) = match baseline {
+        Some((_, baseline)) => (
+            baseline.paths.difference(&paths).cloned().collect(),
+            paths.difference(&baseline.paths).cloned().collect(),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let unreadable = crate::replay::ReplayFile::deserialize(&serde_json::json!({
+        "url": "",
+        "timestamp": "",
+        "data": data,
+    }))
+        .err()
+        .map(|e| e.to_string());
+
+    Ok(Check {
+        fingerprint,
+        paths,
+        baseline: baseline.map(|(year, baseline)| (year.clone(), baseline.fingerprint.clone())),
+        removed,
+        added,
+        unreadable,
+    })
+}
+
+/// Makes the checked page's shape the expected one for `year`.
+pub fn accept(check: &Check, year: &str) -> Result<()> {
+    let mut baselines = load()?;
+    if baselines.0.get(year).is_some_and(|baseline| baseline.fingerprint == check.fingerprint) {
+        return Ok(());
+    }
+    baselines.0.insert(
+        year.to_string(),
+        Baseline {
+            fingerprint: check.fingerprint.clone(),
+            paths: check.paths.clone(),
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+
+    let path = baselines_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&baselines)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Prints what changed, for an unverified check.
+pub fn print_changes(check: &Check) {
+    println!("\nWarning: The replay page's structure has changed; the output is tagged as an unverified schema");
+    if let Some((year, fingerprint)) = &check.baseline {
+        println!("  Schema fingerprint {} (expected {} from {})", check.fingerprint, fingerprint, year);
+    }
+    if let Some(reason) = &check.unreadable {
+        println!("  The replay payload can't be read: {}", reason);
+    }
+    for path in &check.removed {
+        println!("  - {}", path);
+    }
+    for path in &check.added {
+        println!("  + {}", path);
+    }
+    println!("  Check the extracted data, then scrape again with --accept-schema to expect this structure");
+}
+
+/// `path:type` for every value under `value`. Array elements share a
+/// `[]` segment and all-digit object keys (app IDs and the like) a `{id}`
+/// one, so the shape doesn't depend on the account. Nulls and empty
+/// containers are skipped as optional values.
+fn collect_paths(value: &Value, path: &str, paths: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let key = if !key.is_empty() && key.chars().all(|c| c.is_ascii_digit()) { "{id}" } else { key };
+                collect_paths(child, &format!("{}.{}", path, key), paths);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_paths(item, &format!("{}[]", path), paths);
+            }
+        }
+        Value::Null => {}
+        Value::Bool(_) => {
+            paths.insert(format!("{}:bool", path));
+        }
+        Value::Number(_) => {
+            paths.insert(format!("{}:number", path));
+        }
+        Value::String(_) => {
+            paths.insert(format!("{}:string", path));
+        }
+    }
+}
+
+/// Short hash of the path set, stable across accounts with the same layout.
+fn fingerprint(paths: &BTreeSet<String>) -> String {
+    let joined = paths.iter().map(String::as_str).collect::<Vec<_>>().join("\n");
+    crate::provenance::sha256_hex(joined.as_bytes())[..16].to_string()
+}
+
+fn baselines_path() -> Result<PathBuf> {
+    Ok(crate::config::data_dir()?.join(BASELINES_FILENAME))
+}
+
+fn load() -> Result<Baselines> {
+    let path = baselines_path()?;
+    if !path.exists() {
+        return Ok(Baselines::default());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
@@ -0,0 +1,325 @@
+//! `export --format <profile>`: the dataset reshaped into the layouts other
+//! tools import, one row per game. `--format arrow` instead writes the tidy
+//! dataset itself as an Arrow IPC (Feather v2) file with typed columns.
+//! `--split-by genre` writes one file per group, named after the output
+//! with the group appended.
+
+use anyhow::{Context, Result, bail};
+use arrow_array::{ArrayRef, Int32Array, RecordBatch, StringArray, UInt8Array, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::analytics::{self, GameSummary};
+use crate::dataset::{self, PlaytimeRow, escape_csv_field};
+
+pub struct ExportOptions {
+    pub format: String,
+    pub output: Option<String>,
+    pub mapping_path: String,
+    /// Only export this replay year
+    pub year: Option<String>,
+    /// Write one file per group instead of one file
+    pub split_by: Option<SplitBy>,
+}
+
+/// `--split-by`: a list field of the store's appdetails. Games are written
+/// to every group they belong to; games without details go to "Unknown".
+#[derive(Debug, Clone, Copy)]
+pub enum SplitBy {
+    Genre,
+    Category,
+    Developer,
+    Publisher,
+}
+
+impl SplitBy {
+    pub fn parse(name: &str) -> Result<SplitBy> {
+        match name {
+            "genre" => Ok(SplitBy::Genre),
+            "category" => Ok(SplitBy::Category),
+            "developer" => Ok(SplitBy::Developer),
+            "publisher" => Ok(SplitBy::Publisher),
+            "tag" | "franchise" => bail!(
+                "Store {}s aren't part of the appdetails data; split by genre, category, developer or publisher",
+                name
+            ),
+            other => bail!("Unknown --split-by '{}' (expected genre, category, developer or publisher)", other),
+        }
+    }
+
+    fn field(self) -> &'static str {
+        match self {
+            SplitBy::Genre => "genres",
+            SplitBy::Category => "categories",
+            SplitBy::Developer => "developers",
+            SplitBy::Publisher => "publishers",
+        }
+    }
+}
+
+pub fn export(inputs: &[String], options: &ExportOptions) -> Result<()> {
+    let rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+
+    let rows: Vec<PlaytimeRow> = rows
+        .into_iter()
+        .filter(|row| options.year.as_ref().is_none_or(|year| &row.year == year))
+        .collect();
+    let games = analytics::summarize(&rows, &mapping);
+
+    let extension = match options.format.as_str() {
+        "steamdb" | "backloggd" | "hltb" => "csv",
+        "playnite" => "json",
+        "arrow" => "arrow",
+        other => bail!("Unknown export format '{}' (expected steamdb, backloggd, hltb, playnite or arrow)", other),
+    };
+
+    let output = options
+        .output
+        .clone()
+        .unwrap_or_else(|| format!("steam_replay_{}.{}", options.format, extension));
+
+    match options.split_by {
+        Some(split_by) => {
+            let groups = group_games(&games, split_by)?;
+            println!("Splitting {} games into {} groups", games.len(), groups.len());
+            for (group, group_games) in &groups {
+                let app_ids: HashSet<&str> = group_games.iter().map(|game| game.app_id.as_str()).collect();
+                let group_rows: Vec<PlaytimeRow> = rows
+                    .iter()
+                    .filter(|row| app_ids.contains(row.app_id.as_str()))
+                    .cloned()
+                    .collect();
+                write_export(&options.format, group_games, &group_rows, &mapping, &group_path(&output, group))?;
+            }
+        }
+        None => write_export(&options.format, &games, &rows, &mapping, &output)?,
+    }
+
+    // Title-matched formats can't use games without a name
+    let unnamed = games.iter().filter(|game| game.name.is_empty()).count();
+    if unnamed > 0 && matches!(options.format.as_str(), "backloggd" | "hltb") {
+        println!("  Warning: Skipped {} games missing from the mapping; run map-games first", unnamed);
+        for game in games.iter().filter(|game| game.name.is_empty()) {
+            crate::summary::failure(game.app_id.as_str(), "Not in the mapping")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_export(
+    format: &str,
+    games: &[GameSummary],
+    rows: &[PlaytimeRow],
+    mapping: &HashMap<String, String>,
+    output: &str,
+) -> Result<()> {
+    if format == "arrow" {
+        return write_arrow(rows, mapping, output);
+    }
+
+    let content = match format {
+        "steamdb" => steamdb_csv(games),
+        "backloggd" => backloggd_csv(games),
+        "hltb" => hltb_csv(games),
+        _ => playnite_json(games)?,
+    };
+
+    fs::write(output, content)
+        .with_context(|| format!("Failed to write {}", output))?;
+    crate::summary::file_written(output);
+
+    // Title-matched formats leave out games without a name
+    let exported = match format {
+        "backloggd" | "hltb" => games.iter().filter(|game| !game.name.is_empty()).count(),
+        _ => games.len(),
+    };
+    println!("Exported {} games to: {}", exported, output);
+
+    Ok(())
+}
+
+/// The tidy dataset as an Arrow IPC file. Unlike the CSV, the types survive:
+/// playtime is UInt64, the year Int32 (null for "unknown"), `month_number`
+/// 1..12 (null for totals, lifetime rows and quarters), and games missing
+/// from the mapping have a null name rather than an empty one.
+fn write_arrow(rows: &[PlaytimeRow], mapping: &HashMap<String, String>, output: &str) -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("app_id", DataType::Utf8, false),
+        Field::new("game_name", DataType::Utf8, true),
+        Field::new("playtime_in_seconds", DataType::UInt64, false),
+        Field::new("year", DataType::Int32, true),
+        Field::new("month", DataType::Utf8, false),
+        Field::new("month_number", DataType::UInt8, true),
+    ]));
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|row| row.app_id.as_str()))),
+        Arc::new(rows.iter().map(|row| mapping.get(&row.app_id).map(|name| name.as_str())).collect::<StringArray>()),
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|row| row.playtime_seconds))),
+        Arc::new(rows.iter().map(|row| row.year.parse::<i32>().ok()).collect::<Int32Array>()),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|row| row.month.as_str()))),
+        Arc::new(
+            rows.iter()
+                .map(|row| match dataset::month_order(&row.month) {
+                    index @ 1..=12 => Some(index as u8),
+                    _ => None,
+                })
+                .collect::<UInt8Array>(),
+        ),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns).context("Failed to build the Arrow table")?;
+
+    let file = File::create(output).with_context(|| format!("Failed to create {}", output))?;
+    let mut writer = arrow_ipc::writer::FileWriter::try_new(file, &schema)
+        .with_context(|| format!("Failed to write {}", output))?;
+    writer.write(&batch).with_context(|| format!("Failed to write {}", output))?;
+    writer.finish().with_context(|| format!("Failed to write {}", output))?;
+    crate::summary::file_written(output);
+
+    println!("Exported {} rows to: {}", rows.len(), output);
+
+    Ok(())
+}
+
+/// Games by group, from the appdetails cache, fetching what's missing.
+fn group_games(games: &[GameSummary], split_by: SplitBy) -> Result<BTreeMap<String, Vec<GameSummary>>> {
+    let mut groups: BTreeMap<String, Vec<GameSummary>> = BTreeMap::new();
+
+    for game in games {
+        let names = match crate::details::load(&game.app_id, false) {
+            Ok(entry) => crate::details::list_field(&entry, split_by.field()),
+            Err(e) => {
+                println!("  Warning: No store details for app ID {}: {:#}", game.app_id, e);
+                crate::summary::failure(game.app_id.as_str(), format!("{:#}", e))?;
+                Vec::new()
+            }
+        };
+        if names.is_empty() {
+            groups.entry("Unknown".to_string()).or_default().push(game.clone());
+        }
+        for name in names {
+            groups.entry(name).or_default().push(game.clone());
+        }
+    }
+
+    Ok(groups)
+}
+
+/// `steam_replay_hltb.csv` + "Massively Multiplayer" ->
+/// `steam_replay_hltb_massively-multiplayer.csv`
+fn group_path(output: &str, group: &str) -> String {
+    let mut slug = String::new();
+    for c in group.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    let path = Path::new(output);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("export");
+    let name = match path.extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}_{}.{}", stem, slug, extension),
+        None => format!("{}_{}", stem, slug),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// Field names of the Web API's GetOwnedGames response (`appid`, `name`,
+/// `playtime_forever` in minutes), which SteamDB's calculator and most
+/// community library tools read.
+fn steamdb_csv(games: &[GameSummary]) -> String {
+    let mut content = String::from("appid,name,playtime_forever\n");
+
+    for game in games {
+        content.push_str(&format!(
+            "{},{},{}\n",
+            game.app_id,
+            escape_csv_field(&game.name),
+            game.playtime_seconds / 60
+        ));
+    }
+
+    content
+}
+
+/// Backloggd's importer matches games by title and platform; one row per
+/// game and year played, so each year can be logged as its own play.
+fn backloggd_csv(games: &[GameSummary]) -> String {
+    let mut content = String::from("Title,Platform,Status,Year Played,Hours Played,Steam App ID\n");
+
+    for game in games.iter().filter(|game| !game.name.is_empty()) {
+        for (year, seconds) in &game.years {
+            content.push_str(&format!(
+                "{},PC,Played,{},{:.1},{}\n",
+                escape_csv_field(&game.name),
+                year,
+                *seconds as f64 / 3600.0,
+                game.app_id
+            ));
+        }
+    }
+
+    content
+}
+
+/// HowLongToBeat's list import: title, platform and storefront, with
+/// progress as hours and minutes.
+fn hltb_csv(games: &[GameSummary]) -> String {
+    let mut content = String::from("Title,Platform,Storefront,Progress,Year,Steam App ID\n");
+
+    for game in games.iter().filter(|game| !game.name.is_empty()) {
+        for (year, seconds) in &game.years {
+            content.push_str(&format!(
+                "{},PC,Steam,{}:{:02},{},{}\n",
+                escape_csv_field(&game.name),
+                seconds / 3600,
+                seconds % 3600 / 60,
+                year,
+                game.app_id
+            ));
+        }
+    }
+
+    content
+}
+
+/// Playnite's Steam library plugin; games carrying it are matched to the
+/// entries Playnite's own Steam import created.
+const PLAYNITE_STEAM_PLUGIN_ID: &str = "cb91dfc9-b977-43bf-8e70-55f46e410fab";
+
+/// A game in Playnite's library schema, limited to the fields a playtime
+/// merge needs.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlayniteGame<'a> {
+    name: &'a str,
+    game_id: &'a str,
+    plugin_id: &'a str,
+    source: &'a str,
+    /// Seconds, as Playnite stores it
+    playtime: u64,
+}
+
+fn playnite_json(games: &[GameSummary]) -> Result<String> {
+    let entries: Vec<PlayniteGame> = games
+        .iter()
+        .map(|game| PlayniteGame {
+            name: &game.name,
+            game_id: &game.app_id,
+            plugin_id: PLAYNITE_STEAM_PLUGIN_ID,
+            source: "Steam",
+            playtime: game.playtime_seconds,
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&entries)?)
+}
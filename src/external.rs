@@ -0,0 +1,232 @@
+//! `import --format playnite|csv`: playtime from other launchers, written in
+//! the dataset schema so reports cover games outside Steam too.
+//!
+//! Steam games keep their app ID. Everything else gets a `<source>:<id>`
+//! key (e.g. `epic:fn-2c4f`), whose name is added to the mapping file so
+//! reports can label it.
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::fs;
+
+use crate::dataset::{self, escape_csv_field};
+
+pub struct ImportOptions {
+    pub format: String,
+    pub mapping_path: String,
+    /// Only keep entries from this year
+    pub year: Option<String>,
+    /// Source label for CSV rows without a source column
+    pub source: Option<String>,
+}
+
+/// Playnite's Steam library plugin
+const PLAYNITE_STEAM_PLUGIN_ID: &str = "cb91dfc9-b977-43bf-8e70-55f46e410fab";
+
+struct ImportedRow {
+    key: String,
+    name: String,
+    playtime_seconds: u64,
+    year: String,
+    month: String,
+}
+
+pub fn import_external(path: &str, options: &ImportOptions) -> Result<()> {
+    println!("Importing {} playtime from: {}", options.format, path);
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+
+    let mut rows = match options.format.as_str() {
+        "playnite" => parse_playnite(&content, path)?,
+        "csv" => parse_csv(&content, path, options)?,
+        other => bail!("Unknown import format '{}' (expected playnite or csv)", other),
+    };
+
+    rows.retain(|row| row.playtime_seconds > 0);
+    if let Some(year) = &options.year {
+        rows.retain(|row| &row.year == year);
+    }
+    rows.sort_by(|a, b| a.year.cmp(&b.year).then(a.key.cmp(&b.key)));
+
+    println!("  Found {} entries with playtime", rows.len());
+
+    let csv_filename = format!("imported_{}_data.csv", options.format);
+    let mut csv_content = String::from("app_id,playtime_in_seconds,year,month\n");
+    for row in &rows {
+        csv_content.push_str(&format!(
+            "{},{},{},{}\n",
+            escape_csv_field(&row.key),
+            row.playtime_seconds,
+            row.year,
+            row.month
+        ));
+    }
+
+    fs::write(&csv_filename, csv_content)
+        .with_context(|| format!("Failed to write {}", csv_filename))?;
+    crate::summary::file_written(&csv_filename);
+
+    let added = add_names_to_mapping(&options.mapping_path, &rows)?;
+
+    println!("\nImported playtime data saved to: {}", csv_filename);
+    if added > 0 {
+        println!("Added {} game names to: {}", added, options.mapping_path);
+    }
+
+    Ok(())
+}
+
+/// A Playnite library export: a JSON array of games with `Name`, `GameId`,
+/// `PluginId`, `Source` (a name or `{ "Name": ... }`), `Playtime` in seconds
+/// and `LastActivity`.
+fn parse_playnite(content: &str, path: &str) -> Result<Vec<ImportedRow>> {
+    let games: Vec<Value> = serde_json::from_str(content)
+        .with_context(|| format!("Failed to parse {} as a Playnite game list", path))?;
+
+    let mut rows = Vec::new();
+
+    for game in games {
+        let text = |key: &str| game.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let name = text("Name");
+        let game_id = text("GameId");
+        let source = game
+            .get("Source")
+            .and_then(|s| s.as_str().or_else(|| s.get("Name")?.as_str()))
+            .unwrap_or("playnite");
+
+        let is_steam = text("PluginId").eq_ignore_ascii_case(PLAYNITE_STEAM_PLUGIN_ID)
+            || source.eq_ignore_ascii_case("steam");
+        let key = if is_steam && !game_id.is_empty() {
+            game_id
+        } else {
+            external_key(source, if game_id.is_empty() { &name } else { &game_id })
+        };
+
+        // Playtime is lifetime; file it under the year it was last played
+        let year = game
+            .get("LastActivity")
+            .and_then(|v| v.as_str())
+            .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.format("%Y").to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        rows.push(ImportedRow {
+            key,
+            name,
+            playtime_seconds: game.get("Playtime").and_then(|v| v.as_u64()).unwrap_or(0),
+            year,
+            month: "lifetime".to_string(),
+        });
+    }
+
+    Ok(rows)
+}
+
+/// A CSV with a header row. Recognised columns: `name`/`title`, `app_id`,
+/// `source`/`platform`, one of `playtime_in_seconds`/`seconds`, `minutes`
+/// or `hours`, `year` and `month`. Without a month column each row is taken
+/// as the year's total, so it counts in yearly reports like replay totals.
+fn parse_csv(content: &str, path: &str, options: &ImportOptions) -> Result<Vec<ImportedRow>> {
+    let mut lines = content.trim_start_matches('\u{feff}').lines();
+    let header: Vec<String> = dataset::parse_csv_line(lines.next().unwrap_or(""))
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+
+    let name_col = column(&["name", "title", "game"]);
+    let app_id_col = column(&["app_id", "appid"]);
+    let source_col = column(&["source", "platform", "launcher"]);
+    let year_col = column(&["year"]);
+    let month_col = column(&["month"]);
+    let (playtime_col, seconds_per_unit) = match (
+        column(&["playtime_in_seconds", "seconds"]),
+        column(&["minutes"]),
+        column(&["hours"]),
+    ) {
+        (Some(col), _, _) => (col, 1.0),
+        (_, Some(col), _) => (col, 60.0),
+        (_, _, Some(col)) => (col, 3600.0),
+        _ => bail!("{} has no playtime column (seconds, minutes or hours)", path),
+    };
+    if name_col.is_none() && app_id_col.is_none() {
+        bail!("{} needs a name or app_id column", path);
+    }
+    if year_col.is_none() && options.year.is_none() {
+        bail!("{} has no year column; pass --year", path);
+    }
+
+    let mut rows = Vec::new();
+
+    for (line_number, line) in lines.enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let fields = dataset::parse_csv_line(line);
+        let field = |col: Option<usize>| col.and_then(|c| fields.get(c)).map(|v| v.trim().to_string()).unwrap_or_default();
+
+        let playtime: f64 = field(Some(playtime_col))
+            .replace(',', ".")
+            .parse()
+            .with_context(|| format!("{}:{}: invalid playtime", path, line_number + 2))?;
+
+        let name = field(name_col);
+        let source = Some(field(source_col))
+            .filter(|s| !s.is_empty())
+            .or_else(|| options.source.clone())
+            .unwrap_or_else(|| "csv".to_string());
+        let app_id = field(app_id_col);
+
+        let key = if !app_id.is_empty() && app_id.chars().all(|c| c.is_ascii_digit()) && source.eq_ignore_ascii_case("steam") {
+            app_id
+        } else {
+            external_key(&source, if app_id.is_empty() { &name } else { &app_id })
+        };
+
+        rows.push(ImportedRow {
+            key,
+            name,
+            playtime_seconds: (playtime * seconds_per_unit).round() as u64,
+            year: Some(field(year_col))
+                .filter(|y| !y.is_empty())
+                .or_else(|| options.year.clone())
+                .unwrap_or_default(),
+            month: Some(field(month_col))
+                .filter(|m| !m.is_empty())
+                .unwrap_or_else(|| "total".to_string()),
+        });
+    }
+
+    Ok(rows)
+}
+
+/// `<source>:<id>` with both parts lowercased and reduced to [a-z0-9-].
+fn external_key(source: &str, id: &str) -> String {
+    let slug = |text: &str| {
+        let slug: String = text
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-")
+    };
+    format!("{}:{}", slug(source), slug(id))
+}
+
+/// Adds names for imported keys the mapping doesn't know yet; returns how
+/// many were added.
+fn add_names_to_mapping(path: &str, rows: &[ImportedRow]) -> Result<usize> {
+    // Extra columns from `map-games --fields` are written back unchanged
+    let (mut mapping, fields) = dataset::load_mapping_with_fields(path)?;
+    let before = mapping.len();
+
+    for row in rows.iter().filter(|row| !row.name.is_empty()) {
+        mapping.entry(row.key.clone()).or_insert_with(|| row.name.clone());
+    }
+
+    if mapping.len() == before {
+        return Ok(0);
+    }
+
+    dataset::write_mapping(path, &mapping, &fields)?;
+
+    Ok(mapping.len() - before)
+}
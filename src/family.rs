@@ -0,0 +1,325 @@
+//! `family`: Steam Family setups. Members are listed in config.toml:
+//!
+//! ```toml
+//! [family]
+//! Alex = "76561198000000001"
+//! Sam = "76561198000000002"
+//! ```
+//!
+//! `family scrape` fetches every member's replay for a year; `family report`
+//! combines them with per-member and per-game totals. Each replay already
+//! counts a borrowed game for whoever played it, so games are attributed to
+//! the member who played them; with a Web API key, the owners of each game
+//! are looked up too, so borrowed games from the shared library are marked.
+//! The report follows `--report-locale`.
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+
+use crate::analytics;
+use crate::dataset::{self, PlaytimeRow, escape_csv_field};
+use crate::i18n::t;
+use crate::report::{game_name, print_heading};
+use crate::{config, summary};
+
+pub struct FamilyReportOptions {
+    pub mapping_path: String,
+    /// Only this replay year
+    pub year: Option<String>,
+    pub top_n: usize,
+    /// Tidy per-member CSV (year, member, game, playtime)
+    pub output: Option<String>,
+    /// Web API key for the ownership lookup; without one, owners are unknown
+    pub api_key: Option<String>,
+}
+
+/// Family members from the config, as (name, SteamID64).
+fn members() -> Result<Vec<(String, String)>> {
+    let family = config::load()?.family;
+    if family.is_empty() {
+        bail!(
+            "No family members configured; add a [family] table (name = \"SteamID64\") to {}",
+            config::config_path()?.display()
+        );
+    }
+    Ok(family.into_iter().collect())
+}
+
+/// Scrapes each member's replay for `year`, continuing past members whose
+/// page can't be fetched (private profiles, members who joined later).
+pub fn scrape_family(year: &str, name_template: Option<&str>) -> Result<()> {
+    let members = members()?;
+    let options = crate::ScrapeOptions {
+        name_template: name_template.map(|t| t.to_string()),
+        ..Default::default()
+    };
+
+    let mut failed = 0;
+    crate::progress::phase("family_scrape", members.len());
+    for (index, (name, steam_id)) in members.iter().enumerate() {
+        crate::progress::item(name, index);
+        println!("[{}/{}] {} ({})", index + 1, members.len(), name, steam_id);
+        let url = format!("https://store.steampowered.com/replay/{}/{}", steam_id, year);
+        if let Err(e) = crate::scrape_replay(&url, &options) {
+            println!("  Error scraping {}'s replay: {:#}", name, e);
+            summary::failure(name.as_str(), format!("{:#}", e))?;
+            failed += 1;
+        }
+        println!();
+    }
+
+    if failed == members.len() {
+        bail!("None of the {} family members' replays could be scraped", members.len());
+    }
+    println!("Scraped {} of {} family members' replays for {}", members.len() - failed, members.len(), year);
+
+    Ok(())
+}
+
+/// Rows per Steam ID, from replay scrapes (ID taken from the URL) and
+/// master files. CSVs don't record whose playtime they hold, so they're
+/// rejected.
+fn load_player_rows(inputs: &[String]) -> Result<BTreeMap<String, Vec<PlaytimeRow>>> {
+    let mut players: BTreeMap<String, Vec<PlaytimeRow>> = BTreeMap::new();
+
+    for input in inputs {
+        if !input.ends_with(".json") {
+            bail!("{}: family reports need replay JSON or master files, which record the Steam ID", input);
+        }
+
+        let content = fs::read_to_string(input)
+            .with_context(|| format!("Failed to read {}", input))?;
+        let data: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", input))?;
+
+        if crate::master::is_master(&data) {
+            for (steam_id, _, rows) in crate::master::from_value(data, input)?.player_years() {
+                players.entry(steam_id.to_string()).or_default().extend(rows);
+            }
+            continue;
+        }
+
+        let Some(steam_id) = data.get("url").and_then(|v| v.as_str()).and_then(crate::extract_steam_id) else {
+            bail!("No Steam ID found in {}", input);
+        };
+        let year = crate::extract_year_from_data(&data, input);
+        let rows = players.entry(steam_id.to_string()).or_default();
+        for (app_id, playtime_seconds, section) in crate::replay::extract_playtime(&data, input, false)? {
+            rows.push(PlaytimeRow {
+                app_id,
+                playtime_seconds,
+                year: year.clone(),
+                month: crate::convert_section_to_month(&section),
+            });
+        }
+    }
+
+    for rows in players.values_mut() {
+        *rows = dataset::filter_apps(std::mem::take(rows))?;
+    }
+
+    Ok(players)
+}
+
+/// year -> app_id -> seconds, by the per-game yearly figures of
+/// `analytics::summarize`.
+fn yearly_seconds(rows: &[PlaytimeRow]) -> BTreeMap<String, HashMap<String, u64>> {
+    let mut years: BTreeMap<String, HashMap<String, u64>> = BTreeMap::new();
+    for game in analytics::summarize(rows, &HashMap::new()) {
+        for (year, seconds) in game.years {
+            years.entry(year).or_default().insert(game.app_id.clone(), seconds);
+        }
+    }
+    years
+}
+
+/// App IDs in the member's library (GetOwnedGames, including free games).
+fn fetch_owned_games(api_key: &str, steam_id: &str) -> Result<HashSet<String>> {
+    let url = format!(
+        "https://api.steampowered.com/IPlayerService/GetOwnedGames/v1/?key={}&steamid={}&include_played_free_games=1",
+        api_key, steam_id
+    );
+
+    let response = crate::http::get(&url)
+        .context("Failed to fetch owned games")?;
+
+    if response.status() == reqwest::StatusCode::FORBIDDEN {
+        bail!("API key rejected");
+    }
+
+    let data: Value = response.json()
+        .context("Failed to parse response")?;
+
+    // Private game details answer with an empty response object
+    let Some(games) = data.get("response").and_then(|r| r.get("games")).and_then(|g| g.as_array()) else {
+        bail!("Game details are private");
+    };
+
+    Ok(games
+        .iter()
+        .filter_map(|game| game.get("appid")?.as_u64())
+        .map(|app_id| app_id.to_string())
+        .collect())
+}
+
+pub fn print_family_report(inputs: &[String], options: &FamilyReportOptions) -> Result<()> {
+    let names: HashMap<String, String> = config::load()?
+        .family
+        .into_iter()
+        .map(|(name, steam_id)| (steam_id, name))
+        .collect();
+    let member_name = |steam_id: &str| names.get(steam_id).cloned().unwrap_or_else(|| steam_id.to_string());
+
+    let players = load_player_rows(inputs)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+
+    // steam_id -> year -> app_id -> seconds
+    let mut by_player: BTreeMap<&str, BTreeMap<String, HashMap<String, u64>>> = players
+        .iter()
+        .map(|(steam_id, rows)| (steam_id.as_str(), yearly_seconds(rows)))
+        .collect();
+    if let Some(year) = &options.year {
+        for years in by_player.values_mut() {
+            years.retain(|y, _| y == year);
+        }
+    }
+
+    // app_id -> owners' names, when a key is available
+    let mut owners: Option<HashMap<String, BTreeSet<String>>> = None;
+    if let Some(api_key) = &options.api_key {
+        let mut owned: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let mut complete = true;
+        for steam_id in by_player.keys() {
+            match fetch_owned_games(api_key, steam_id) {
+                Ok(app_ids) => {
+                    for app_id in app_ids {
+                        owned.entry(app_id).or_default().insert(member_name(steam_id));
+                    }
+                }
+                Err(e) => {
+                    println!("Warning: Could not look up {}'s library: {:#}", member_name(steam_id), e);
+                    summary::failure(*steam_id, format!("{:#}", e))?;
+                    complete = false;
+                }
+            }
+        }
+        // A partial lookup would mark games from the missing libraries as borrowed
+        if complete {
+            owners = Some(owned);
+        }
+    }
+
+    let title = t!("family-title");
+    println!("{}", title);
+    println!("{}", "=".repeat(title.chars().count()));
+
+    let years: BTreeSet<&String> = by_player.values().flat_map(|years| years.keys()).collect();
+    if years.is_empty() {
+        println!("\n{}", t!("family-none"));
+    }
+
+    for year in years {
+        print_heading(year);
+
+        // Per member
+        let family_seconds: u64 = by_player
+            .values()
+            .filter_map(|years| years.get(year))
+            .flat_map(|games| games.values())
+            .sum();
+        println!(
+            "{:<24} {:>9} {:>6} {:>6}",
+            t!("column-member"),
+            t!("column-hours"),
+            t!("column-games"),
+            t!("column-share")
+        );
+        for (steam_id, years) in &by_player {
+            let Some(games) = years.get(year) else { continue };
+            let seconds: u64 = games.values().sum();
+            println!(
+                "{:<24} {:>9.1} {:>6} {:>5.1}%",
+                member_name(steam_id),
+                seconds as f64 / 3600.0,
+                games.len(),
+                seconds as f64 / family_seconds.max(1) as f64 * 100.0
+            );
+        }
+        println!("{:<24} {:>9.1}", t!("family-total"), family_seconds as f64 / 3600.0);
+
+        // Per game, with each member's part
+        let mut games: HashMap<&str, Vec<(String, u64)>> = HashMap::new();
+        for (steam_id, years) in &by_player {
+            for (app_id, &seconds) in years.get(year).into_iter().flatten() {
+                games.entry(app_id.as_str()).or_default().push((member_name(steam_id), seconds));
+            }
+        }
+        let mut games: Vec<(&str, Vec<(String, u64)>)> = games.into_iter().collect();
+        games.sort_by_key(|(app_id, players)| (std::cmp::Reverse(players.iter().map(|(_, s)| s).sum::<u64>()), *app_id));
+
+        let shared = games.iter().filter(|(_, players)| players.len() > 1).count();
+        println!("\n{}", t!("family-top-games", shared = shared));
+        for (rank, (app_id, players)) in games.iter().take(options.top_n).enumerate() {
+            let seconds: u64 = players.iter().map(|(_, s)| s).sum();
+            let split: Vec<String> = players
+                .iter()
+                .map(|(name, s)| {
+                    let borrowed = owners
+                        .as_ref()
+                        .is_some_and(|owners| !owners.get(*app_id).is_some_and(|o| o.contains(name)));
+                    if borrowed {
+                        format!("{} {:.1}h {}", name, *s as f64 / 3600.0, t!("family-borrowed"))
+                    } else {
+                        format!("{} {:.1}h", name, *s as f64 / 3600.0)
+                    }
+                })
+                .collect();
+            println!(
+                "{:>3}. {:<40} {:>7.1}h  {}",
+                rank + 1,
+                game_name(&mapping, app_id),
+                seconds as f64 / 3600.0,
+                split.join(", ")
+            );
+            if let Some(owner_names) = owners.as_ref().and_then(|owners| owners.get(*app_id)) {
+                let owner_names: Vec<&str> = owner_names.iter().map(|s| s.as_str()).collect();
+                println!("     {:<40} {}", "", t!("family-owned-by", owners = owner_names.join(", ")));
+            }
+        }
+    }
+
+    if let Some(output) = &options.output {
+        let mut content = String::from("year,member,steam_id,app_id,game_name,playtime_in_seconds,owned\n");
+        for (steam_id, years) in &by_player {
+            let name = member_name(steam_id);
+            for (year, games) in years {
+                let mut games: Vec<(&String, &u64)> = games.iter().collect();
+                games.sort();
+                for (app_id, seconds) in games {
+                    let owned = match &owners {
+                        Some(owners) => owners.get(app_id).is_some_and(|o| o.contains(&name)).to_string(),
+                        None => String::new(),
+                    };
+                    content.push_str(&format!(
+                        "{},{},{},{},{},{},{}\n",
+                        year,
+                        escape_csv_field(&name),
+                        steam_id,
+                        app_id,
+                        escape_csv_field(mapping.get(app_id).map(|s| s.as_str()).unwrap_or("")),
+                        seconds,
+                        owned
+                    ));
+                }
+            }
+        }
+        fs::write(output, content)
+            .with_context(|| format!("Failed to write {}", output))?;
+        summary::file_written(output);
+        println!("\n{}", t!("family-saved", path = output.as_str()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//! Next year's playtime, projected from the complete years in the dataset
+//! for `report --forecast`. Deliberately simple:
+//!
+//! - the yearly total follows a least-squares line through the complete
+//!   years' totals (flat with a single year);
+//! - months get the total in their average share of past years' hours;
+//! - each game's hours continue half of their change between the last two
+//!   years, and the highest projection is the likely top game.
+//!
+//! For the year in progress, the hours so far are scaled up by the share of
+//! a typical year the elapsed months account for ("on pace for").
+
+use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::analytics;
+use crate::dataset::PlaytimeRow;
+
+pub struct Forecast {
+    /// The year after the last complete one
+    pub year: i32,
+    /// How many complete years the trend was fitted to
+    pub years_used: usize,
+    pub total_hours: f64,
+    pub monthly_hours: [f64; 12],
+    /// (app_id, projected hours)
+    pub top_game: Option<(String, f64)>,
+    pub pace: Option<Pace>,
+}
+
+/// Mid-year estimate for the year in progress.
+pub struct Pace {
+    pub year: i32,
+    pub hours: f64,
+    /// Months up to the latest one with playtime
+    pub months: usize,
+    pub projected: f64,
+}
+
+/// None without a complete year to project from. Years before `today`'s
+/// are complete.
+pub fn forecast(rows: &[PlaytimeRow], today: NaiveDate) -> Option<Forecast> {
+    let games = analytics::summarize(rows, &HashMap::new());
+    let monthly = analytics::monthly_hours(rows);
+
+    // year -> hours, over every game
+    let mut totals: BTreeMap<i32, f64> = BTreeMap::new();
+    for game in &games {
+        for (year, seconds) in &game.years {
+            if let Ok(year) = year.parse() {
+                *totals.entry(year).or_default() += *seconds as f64 / 3600.0;
+            }
+        }
+    }
+    let complete: Vec<(i32, f64)> = totals.iter().filter(|(year, _)| **year < today.year()).map(|(y, h)| (*y, *h)).collect();
+    let &(last_year, _) = complete.last()?;
+    let year = last_year + 1;
+
+    let total_hours = trend(&complete, year).max(0.0);
+    let shares = seasonal_shares(&monthly, |year| year < today.year());
+    let monthly_hours = shares.map(|share| total_hours * share);
+
+    let hours_in = |game: &analytics::GameSummary, year: i32| {
+        let year = year.to_string();
+        game.years.iter().find(|(y, _)| *y == year).map(|(_, seconds)| *seconds as f64 / 3600.0).unwrap_or(0.0)
+    };
+    let top_game = games
+        .iter()
+        .map(|game| {
+            let last = hours_in(game, last_year);
+            let previous = hours_in(game, last_year - 1);
+            (game.app_id.clone(), (last + (last - previous) / 2.0).max(0.0))
+        })
+        .filter(|(_, hours)| *hours > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)));
+
+    let pace = monthly.get(&today.year().to_string()).and_then(|months| {
+        let elapsed = months.iter().rposition(|hours| *hours > 0.0)? + 1;
+        let hours: f64 = months[..elapsed].iter().sum();
+        let share: f64 = shares[..elapsed].iter().sum();
+        Some(Pace {
+            year: today.year(),
+            hours,
+            months: elapsed,
+            projected: hours / share.max(f64::EPSILON),
+        })
+    });
+
+    Some(Forecast {
+        year,
+        years_used: complete.len(),
+        total_hours,
+        monthly_hours,
+        top_game,
+        pace,
+    })
+}
+
+/// The least-squares line through `points`, evaluated at `x`.
+fn trend(points: &[(i32, f64)], x: i32) -> f64 {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| *x as f64).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let variance: f64 = points.iter().map(|(x, _)| (*x as f64 - mean_x).powi(2)).sum();
+    if variance == 0.0 {
+        return mean_y;
+    }
+    let slope = points.iter().map(|(x, y)| (*x as f64 - mean_x) * (y - mean_y)).sum::<f64>() / variance;
+    mean_y + slope * (x as f64 - mean_x)
+}
+
+/// Each month's average share of a year's hours, over the years `include`
+/// accepts that have monthly figures; even shares without any.
+fn seasonal_shares(monthly: &BTreeMap<String, [f64; 12]>, include: impl Fn(i32) -> bool) -> [f64; 12] {
+    let mut shares = [0.0; 12];
+    let mut years = 0;
+    for (year, months) in monthly {
+        let total: f64 = months.iter().sum();
+        if total <= 0.0 || !year.parse().is_ok_and(&include) {
+            continue;
+        }
+        for (share, hours) in shares.iter_mut().zip(months) {
+            *share += hours / total;
+        }
+        years += 1;
+    }
+
+    if years == 0 {
+        return [1.0 / 12.0; 12];
+    }
+    shares.map(|share| share / years as f64)
+}
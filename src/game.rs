@@ -0,0 +1,145 @@
+//! `game <appid|name>`: one game's history across every year in the dataset.
+
+use anyhow::{Result, bail};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::dataset::{self, PlaytimeRow};
+use crate::i18n::{self, t};
+use crate::report::{game_name, sparkline};
+
+pub fn print_game(query: &str, inputs: &[String], mapping_path: &str) -> Result<()> {
+    let rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(mapping_path)?;
+
+    let app_id = resolve_app_id(query, &rows, &mapping)?;
+
+    let heading = t!("game-heading", name = game_name(&mapping, &app_id), app_id = app_id.as_str());
+    println!("{}", heading);
+    println!("{}", "=".repeat(heading.chars().count()));
+    if let Some(entry) = crate::overrides::get(&app_id) {
+        if !entry.tags.is_empty() {
+            println!("{}", t!("game-tags", tags = entry.tags.join(", ")));
+        }
+        if let Some(notes) = &entry.notes {
+            println!("{}", t!("game-notes", notes = notes.as_str()));
+        }
+    }
+
+    // year -> every game's yearly total, for ranks and shares
+    let totals_by_year = crate::analytics::yearly_totals(&rows);
+
+    // year -> monthly seconds
+    let monthly: BTreeMap<&str, [u64; 12]> = crate::analytics::monthly_by_game(&rows)
+        .into_iter()
+        .filter_map(|(year, games)| games.get(app_id.as_str()).map(|months| (year, *months)))
+        .collect();
+
+    let mut overall_seconds = 0;
+    let mut game_seconds = 0;
+
+    println!(
+        "\n  {:<6} {:>9} {:>10} {:>8}",
+        t!("column-year"),
+        t!("column-hours"),
+        t!("column-rank"),
+        t!("column-share")
+    );
+    for (year, games) in totals_by_year {
+        let year_seconds: u64 = games.iter().map(|row| row.playtime_seconds).sum();
+        overall_seconds += year_seconds;
+
+        let Some(position) = games.iter().position(|row| row.app_id == app_id) else {
+            println!("  {:<6} {:>9} {:>10} {:>8}", year, "-", "-", "-");
+            continue;
+        };
+        let row = games[position];
+        game_seconds += row.playtime_seconds;
+
+        let line = format!(
+            "  {:<6} {:>9.1} {:>10} {:>7.1}%  {}",
+            year,
+            row.hours(),
+            format!("{}/{}", position + 1, games.len()),
+            share(row.playtime_seconds, year_seconds),
+            monthly.get(year).map(sparkline).unwrap_or_default()
+        );
+        println!("{}", line.trim_end());
+    }
+
+    println!(
+        "\n{}",
+        t!(
+            "game-all-years",
+            hours = format!("{:.1}", game_seconds as f64 / 3600.0),
+            share = format!("{:.1}", share(game_seconds, overall_seconds))
+        )
+    );
+
+    if !monthly.is_empty() {
+        println!("\n{}", t!("game-hours-per-month"));
+        print!("  {:<6}", t!("column-year"));
+        for index in 0..12 {
+            print!(" {:>6}", i18n::month_abbreviation(index, 3));
+        }
+        println!();
+
+        for (year, months) in monthly {
+            print!("  {:<6}", year);
+            for seconds in months {
+                if seconds == 0 {
+                    print!(" {:>6}", "-");
+                } else {
+                    print!(" {:>6.1}", seconds as f64 / 3600.0);
+                }
+            }
+            println!();
+        }
+    }
+
+    if let Some(row) = rows.iter().find(|row| row.app_id == app_id && row.month == "lifetime") {
+        println!("\n{}", t!("game-lifetime", hours = format!("{:.1}", row.hours())));
+    }
+
+    Ok(())
+}
+
+/// Accepts an app ID or a case-insensitive part of the game's name.
+fn resolve_app_id(query: &str, rows: &[PlaytimeRow], mapping: &HashMap<String, String>) -> Result<String> {
+    if rows.iter().any(|row| row.app_id == query) {
+        return Ok(query.to_string());
+    }
+
+    let needle = query.to_lowercase();
+    let mut matches: Vec<(&String, &String)> = mapping
+        .iter()
+        .filter(|(app_id, name)| {
+            name.to_lowercase().contains(&needle) && rows.iter().any(|row| &row.app_id == *app_id)
+        })
+        .collect();
+    matches.sort();
+
+    // An exact name wins over partial matches
+    if let Some((app_id, _)) = matches.iter().find(|(_, name)| name.to_lowercase() == needle) {
+        return Ok(app_id.to_string());
+    }
+
+    match matches.as_slice() {
+        [] => bail!("No game matching '{}' in the dataset", query),
+        [(app_id, _)] => Ok(app_id.to_string()),
+        _ => {
+            let candidates: Vec<String> = matches
+                .iter()
+                .map(|(app_id, name)| format!("  {} {}", app_id, name))
+                .collect();
+            bail!("'{}' matches several games; use an app ID:\n{}", query, candidates.join("\n"))
+        }
+    }
+}
+
+fn share(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64 * 100.0
+    }
+}
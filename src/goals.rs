@@ -0,0 +1,302 @@
+//! `goals`: yearly targets declared in config.toml, scored against the
+//! dataset. Each goal has a metric and either a `min` (at least) or a
+//! `max` (at most):
+//!
+//! ```toml
+//! [[goals]]
+//! name = "Try 12 new games"
+//! metric = "new_games"
+//! min = 12
+//!
+//! [[goals]]
+//! metric = "total_hours"
+//! max = 400
+//!
+//! [[goals]]
+//! name = "Dig into the backlog"
+//! metric = "back_catalog_hours"
+//! min = 20
+//! ```
+//!
+//! Metrics: `total_hours`, `games_played`, `new_games` (no playtime in any
+//! earlier year of the dataset), `new_release_hours` / `back_catalog_hours`
+//! (released that year / earlier, from the appdetails cache) and
+//! `game_hours` (the games listed in the goal's `app_ids`). Steam doesn't
+//! record when a game is finished, so "finish N games" is best tracked as
+//! `new_games`. For the current year progress is compared with the pace
+//! needed to reach the target by December. The output follows
+//! `--report-locale`.
+
+use anyhow::{Result, bail};
+use chrono::Datelike;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::analytics::{self, GameSummary};
+use crate::dataset;
+use crate::i18n::t;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    TotalHours,
+    GamesPlayed,
+    NewGames,
+    NewReleaseHours,
+    BackCatalogHours,
+    GameHours,
+}
+
+impl Metric {
+    fn counts_games(self) -> bool {
+        matches!(self, Metric::GamesPlayed | Metric::NewGames)
+    }
+
+    /// Selects the wording of `goals-default-name`
+    fn describe(self) -> &'static str {
+        match self {
+            Metric::TotalHours | Metric::GamesPlayed => "played",
+            Metric::NewGames => "new",
+            Metric::NewReleaseHours => "new-releases",
+            Metric::BackCatalogHours => "back-catalog",
+            Metric::GameHours => "listed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    Met,
+    OnTrack,
+    Behind,
+    Missed,
+    OverPace,
+}
+
+impl Status {
+    const ALL: [Status; 5] = [Status::Met, Status::OnTrack, Status::Behind, Status::Missed, Status::OverPace];
+
+    fn label(self) -> String {
+        match self {
+            Status::Met => t!("goals-status-met"),
+            Status::OnTrack => t!("goals-status-on-track"),
+            Status::Behind => t!("goals-status-behind"),
+            Status::Missed => t!("goals-status-missed"),
+            Status::OverPace => t!("goals-status-over-pace"),
+        }
+    }
+}
+
+/// How `value` stands against an `at_least` (min) or at-most (max) target.
+/// `elapsed` is the share of the year gone while the year is still running,
+/// so progress is judged against the pace the target needs; None once the
+/// year is over.
+fn status(value: f64, target: f64, at_least: bool, elapsed: Option<f64>) -> Status {
+    match (at_least, elapsed) {
+        (true, _) if value >= target => Status::Met,
+        (true, Some(elapsed)) if value >= target * elapsed => Status::OnTrack,
+        (true, Some(_)) => Status::Behind,
+        (true, None) => Status::Missed,
+        (false, _) if value > target => Status::Missed,
+        (false, Some(elapsed)) if value > target * elapsed => Status::OverPace,
+        (false, Some(_)) => Status::OnTrack,
+        (false, None) => Status::Met,
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Goal {
+    pub name: Option<String>,
+    pub metric: Metric,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// The games a `game_hours` goal counts
+    #[serde(default)]
+    pub app_ids: Vec<u64>,
+}
+
+pub struct GoalsOptions {
+    pub mapping_path: String,
+    /// Defaults to the latest year in the dataset
+    pub year: Option<String>,
+}
+
+pub fn print_goals(inputs: &[String], options: &GoalsOptions) -> Result<()> {
+    let goals = crate::config::load()?.goals;
+    if goals.is_empty() {
+        bail!(
+            "No goals configured; add [[goals]] tables (metric, min or max) to {}",
+            crate::config::config_path()?.display()
+        );
+    }
+    for (index, goal) in goals.iter().enumerate() {
+        match (goal.min, goal.max) {
+            (Some(_), None) | (None, Some(_)) => {}
+            _ => bail!("Goal {} needs exactly one of min or max", index + 1),
+        }
+        if goal.metric == Metric::GameHours && goal.app_ids.is_empty() {
+            bail!("Goal {} (game_hours) needs app_ids", index + 1);
+        }
+    }
+
+    let rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+    let games = analytics::summarize(&rows, &mapping);
+
+    let year = match &options.year {
+        Some(year) => year.clone(),
+        None => match games
+            .iter()
+            .flat_map(|game| game.years.iter().map(|(year, _)| year))
+            .filter(|year| year.parse::<i32>().is_ok())
+            .max()
+        {
+            Some(year) => year.clone(),
+            None => bail!("No replay years in the dataset to score goals against"),
+        },
+    };
+
+    // Share of the year gone, for pacing; None once the year is over
+    let today = chrono::Local::now().date_naive();
+    let elapsed = (year == today.year().to_string()).then(|| today.ordinal() as f64 / 365.0);
+
+    println!("{}", t!("goals-title", year = year.as_str()));
+    if let Some(elapsed) = elapsed {
+        println!("{}", t!("goals-elapsed", percent = format!("{:.0}", elapsed * 100.0)));
+    }
+    println!();
+
+    let mut release_years: HashMap<String, Option<i32>> = HashMap::new();
+    let mut met = 0;
+    let status_width = Status::ALL.iter().map(|status| status.label().chars().count() + 2).max().unwrap_or(0);
+
+    for goal in &goals {
+        let value = measure(goal, &games, &year, &mut release_years);
+        let (target, at_least) = match (goal.min, goal.max) {
+            (Some(min), _) => (min, true),
+            (_, Some(max)) => (max, false),
+            _ => unreachable!("validated above"),
+        };
+
+        let status = status(value, target, at_least, elapsed);
+        if status == Status::Met {
+            met += 1;
+        }
+
+        let bound = if at_least { "min" } else { "max" };
+        let name = goal.name.clone().unwrap_or_else(|| {
+            t!(
+                "goals-default-name",
+                bound = bound,
+                target = format_value(target, goal.metric),
+                metric = goal.metric.describe()
+            )
+        });
+        println!(
+            "  {:<width$} {:<40} {} / {} {} ({:.0}%)",
+            format!("[{}]", status.label()),
+            name,
+            format_value(value, goal.metric),
+            t!("goals-bound", bound = bound),
+            format_value(target, goal.metric),
+            value / target.max(f64::EPSILON) * 100.0,
+            width = status_width
+        );
+    }
+
+    crate::summary::count("goals_met", met);
+    println!("\n{}", t!("goals-summary", met = met, total = goals.len()));
+
+    Ok(())
+}
+
+/// The goal's metric for `year`.
+fn measure(goal: &Goal, games: &[GameSummary], year: &str, release_years: &mut HashMap<String, Option<i32>>) -> f64 {
+    let seconds_in = |game: &GameSummary, wanted: &str| {
+        game.years.iter().find(|(y, _)| y == wanted).map(|(_, seconds)| *seconds)
+    };
+    let played: Vec<(&GameSummary, u64)> = games
+        .iter()
+        .filter_map(|game| seconds_in(game, year).map(|seconds| (game, seconds)))
+        .collect();
+    let hours = |selected: &mut dyn Iterator<Item = &(&GameSummary, u64)>| {
+        selected.map(|(_, seconds)| *seconds).sum::<u64>() as f64 / 3600.0
+    };
+
+    match goal.metric {
+        Metric::TotalHours => hours(&mut played.iter()),
+        Metric::GamesPlayed => played.len() as f64,
+        Metric::NewGames => played
+            .iter()
+            .filter(|(game, _)| !game.years.iter().any(|(y, _)| y.as_str() < year))
+            .count() as f64,
+        Metric::NewReleaseHours | Metric::BackCatalogHours => {
+            let year_number: Option<i32> = year.parse().ok();
+            let new_release = goal.metric == Metric::NewReleaseHours;
+            hours(&mut played.iter().filter(|(game, _)| {
+                let released = *release_years
+                    .entry(game.app_id.clone())
+                    .or_insert_with(|| release_year(&game.app_id));
+                match (released, year_number) {
+                    (Some(released), Some(year)) if new_release => released == year,
+                    (Some(released), Some(year)) => released < year,
+                    _ => false,
+                }
+            }))
+        }
+        Metric::GameHours => {
+            let listed: HashSet<String> = goal.app_ids.iter().map(|id| id.to_string()).collect();
+            hours(&mut played.iter().filter(|(game, _)| listed.contains(&game.app_id)))
+        }
+    }
+}
+
+/// Release year from the appdetails cache, fetching what's missing.
+fn release_year(app_id: &str) -> Option<i32> {
+    match crate::details::load(app_id, false) {
+        Ok(entry) => crate::details::release_year(&entry),
+        Err(e) => {
+            println!("  Warning: No release date for app ID {}: {:#}", app_id, e);
+            None
+        }
+    }
+}
+
+fn format_value(value: f64, metric: Metric) -> String {
+    if metric.counts_games() {
+        t!("goals-games", count = value.round() as i64)
+    } else {
+        format!("{:.1}h", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_min_goals_of_past_years() {
+        assert_eq!(status(12.0, 12.0, true, None), Status::Met);
+        assert_eq!(status(11.0, 12.0, true, None), Status::Missed);
+    }
+
+    #[test]
+    fn paces_min_goals_of_the_current_year() {
+        assert_eq!(status(12.0, 12.0, true, Some(0.5)), Status::Met);
+        assert_eq!(status(6.0, 12.0, true, Some(0.5)), Status::OnTrack);
+        assert_eq!(status(5.0, 12.0, true, Some(0.5)), Status::Behind);
+    }
+
+    #[test]
+    fn scores_max_goals_of_past_years() {
+        assert_eq!(status(400.0, 400.0, false, None), Status::Met);
+        assert_eq!(status(401.0, 400.0, false, None), Status::Missed);
+    }
+
+    #[test]
+    fn paces_max_goals_of_the_current_year() {
+        assert_eq!(status(100.0, 400.0, false, Some(0.25)), Status::OnTrack);
+        assert_eq!(status(150.0, 400.0, false, Some(0.25)), Status::OverPace);
+        assert_eq!(status(450.0, 400.0, false, Some(0.25)), Status::Missed);
+    }
+}
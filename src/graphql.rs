@@ -0,0 +1,588 @@
+//! `graphql`: serves the local dataset as a GraphQL API on localhost, so a
+//! dashboard can fetch games, years and months with their playtime, store
+//! details and achievement completion in the shape it needs in one request.
+//! `POST /graphql` takes the usual `{ "query", "variables" }` body; `GET /`
+//! opens GraphiQL and `GET /schema.graphql` returns the schema. The data is
+//! reloaded when an input, the mapping or the achievements file changes.
+//! Browsers only let other pages read the API with `--allow-origin`, e.g.
+//! `--allow-origin http://localhost:5173` for a dashboard's dev server.
+//!
+//! Store details come from the appdetails cache only; run `details` or
+//! `map-games` first to fill it.
+
+use anyhow::{Context as _, Result, bail};
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::dataset::{self, PlaytimeRow};
+use crate::details;
+use crate::analytics::{self, GameSummary};
+
+pub const DEFAULT_PORT: u16 = 8751;
+
+/// Requests larger than this are refused
+const MAX_BODY_BYTES: usize = 1 << 20;
+/// Requests are answered one at a time, so a client that stalls can hold
+/// up the others for at most this long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct GraphqlOptions {
+    pub mapping_path: String,
+    pub achievements_path: String,
+    pub port: u16,
+    /// Origin sent in `Access-Control-Allow-Origin`; none without it
+    pub allow_origin: Option<String>,
+}
+
+type DashboardSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+struct Dataset {
+    rows: Vec<PlaytimeRow>,
+    /// Per-game totals, in app ID order
+    games: Vec<GameSummary>,
+    achievements: HashMap<String, Achievements>,
+}
+
+impl Dataset {
+    fn load(inputs: &[String], options: &GraphqlOptions) -> Result<Dataset> {
+        let rows = dataset::load_rows(inputs)?;
+        let mapping = dataset::load_mapping(&options.mapping_path)?;
+        let games = analytics::summarize(&rows, &mapping);
+        let achievements = load_achievements(&options.achievements_path)?;
+
+        Ok(Dataset { rows, games, achievements })
+    }
+
+    fn game(self: &Arc<Self>, app_id: &str) -> Option<Game> {
+        let index = self.games.binary_search_by(|game| game.app_id.as_str().cmp(app_id)).ok()?;
+        Some(Game { dataset: Arc::clone(self), index })
+    }
+
+    fn years(self: &Arc<Self>) -> Vec<Year> {
+        let mut years: BTreeMap<&str, u64> = BTreeMap::new();
+        for game in &self.games {
+            for (year, seconds) in &game.years {
+                *years.entry(year.as_str()).or_default() += seconds;
+            }
+        }
+
+        years
+            .into_iter()
+            .map(|(year, playtime_seconds)| Year {
+                dataset: Arc::clone(self),
+                year: year.to_string(),
+                playtime_seconds,
+            })
+            .collect()
+    }
+
+    /// (playtime, index into `games`) of the games played in `year`, or of
+    /// every game without one, most played first (ties by app ID).
+    fn ranked_games(&self, year: Option<&str>) -> Vec<(u64, usize)> {
+        let mut games: Vec<(u64, usize)> = self
+            .games
+            .iter()
+            .enumerate()
+            .filter_map(|(index, game)| match year {
+                Some(year) => game.years.iter().find(|(y, _)| y == year).map(|(_, seconds)| (*seconds, index)),
+                None => Some((game.playtime_seconds, index)),
+            })
+            .collect();
+        games.sort_by_key(|&(seconds, index)| (Reverse(seconds), index));
+        games
+    }
+
+    /// Monthly rows, without yearly totals and lifetime imports
+    fn monthly_rows(&self) -> impl Iterator<Item = &PlaytimeRow> {
+        self.rows.iter().filter(|row| row.month != "total" && row.month != "lifetime")
+    }
+
+    /// (game, playtime) for the rows `keep` selects, most played first.
+    fn ranked(self: &Arc<Self>, keep: impl Fn(&PlaytimeRow) -> bool, limit: Option<usize>) -> Vec<GamePlaytime> {
+        analytics::rank_games(self.monthly_rows().filter(|row| keep(row)))
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .filter_map(|(app_id, playtime_seconds)| {
+                Some(GamePlaytime {
+                    game: self.game(app_id)?,
+                    playtime_seconds,
+                })
+            })
+            .collect()
+    }
+}
+
+/// `achievement_completion.csv` (steam_id, app_id, achieved, total,
+/// completion) by app ID; empty when the file doesn't exist.
+fn load_achievements(path: &str) -> Result<HashMap<String, Achievements>> {
+    if !Path::new(path).exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+
+    Ok(content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = dataset::parse_csv_line(line);
+            Some((
+                fields.get(1)?.clone(),
+                Achievements {
+                    achieved: fields.get(2)?.parse().ok()?,
+                    total: fields.get(3)?.parse().ok()?,
+                    completion: fields.get(4)?.parse().ok()?,
+                },
+            ))
+        })
+        .collect())
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    /// Games in the dataset, most played first. With `year`, only games
+    /// played that year, ranked by that year's playtime.
+    async fn games(&self, ctx: &Context<'_>, year: Option<String>, limit: Option<usize>) -> Vec<Game> {
+        let dataset = ctx.data_unchecked::<Arc<Dataset>>();
+
+        dataset
+            .ranked_games(year.as_deref())
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|(_, index)| Game { dataset: Arc::clone(dataset), index })
+            .collect()
+    }
+
+    async fn game(&self, ctx: &Context<'_>, app_id: String) -> Option<Game> {
+        ctx.data_unchecked::<Arc<Dataset>>().game(&app_id)
+    }
+
+    /// Replay years, oldest first.
+    async fn years(&self, ctx: &Context<'_>) -> Vec<Year> {
+        ctx.data_unchecked::<Arc<Dataset>>().years()
+    }
+
+    async fn year(&self, ctx: &Context<'_>, year: String) -> Option<Year> {
+        ctx.data_unchecked::<Arc<Dataset>>().years().into_iter().find(|y| y.year == year)
+    }
+}
+
+struct Game {
+    dataset: Arc<Dataset>,
+    /// Position in `Dataset::games`
+    index: usize,
+}
+
+impl Game {
+    fn summary(&self) -> &GameSummary {
+        &self.dataset.games[self.index]
+    }
+}
+
+#[Object]
+impl Game {
+    async fn app_id(&self) -> &str {
+        &self.summary().app_id
+    }
+
+    /// The mapped name; null for games missing from the mapping
+    async fn name(&self) -> Option<&str> {
+        Some(self.summary().name.as_str()).filter(|name| !name.is_empty())
+    }
+
+    /// Lifetime playtime when imported from a launcher, else the replay years summed
+    async fn playtime_seconds(&self) -> u64 {
+        self.summary().playtime_seconds
+    }
+
+    async fn hours(&self) -> f64 {
+        self.summary().playtime_seconds as f64 / 3600.0
+    }
+
+    /// The replay years the game was played in, oldest first.
+    async fn years(&self) -> Vec<GameYear> {
+        self.summary()
+            .years
+            .iter()
+            .map(|(year, playtime_seconds)| GameYear {
+                dataset: Arc::clone(&self.dataset),
+                app_id: self.summary().app_id.clone(),
+                year: year.clone(),
+                playtime_seconds: *playtime_seconds,
+            })
+            .collect()
+    }
+
+    /// Store details from the appdetails cache; null when not fetched yet
+    /// or the store has no data for the game.
+    async fn details(&self) -> Option<Details> {
+        let entry = details::cached(&self.summary().app_id).ok()??;
+        details::data(&entry)?;
+
+        Some(Details {
+            app_type: details::mapping_field(&entry, "type").as_str().map(|s| s.to_string()),
+            is_free: details::mapping_field(&entry, "is_free").as_bool(),
+            release_year: details::release_year(&entry),
+            header_image: details::mapping_field(&entry, "header_image").as_str().map(|s| s.to_string()),
+            genres: details::list_field(&entry, "genres"),
+            categories: details::list_field(&entry, "categories"),
+            developers: details::list_field(&entry, "developers"),
+            publishers: details::list_field(&entry, "publishers"),
+        })
+    }
+
+    /// From achievement_completion.csv; null for games without achievements
+    async fn achievements(&self) -> Option<Achievements> {
+        self.dataset.achievements.get(&self.summary().app_id).cloned()
+    }
+}
+
+struct GameYear {
+    dataset: Arc<Dataset>,
+    app_id: String,
+    year: String,
+    playtime_seconds: u64,
+}
+
+#[Object]
+impl GameYear {
+    async fn year(&self) -> &str {
+        &self.year
+    }
+
+    async fn playtime_seconds(&self) -> u64 {
+        self.playtime_seconds
+    }
+
+    /// Months the game was played in that year, in calendar order.
+    async fn months(&self) -> Vec<MonthPlaytime> {
+        let mut months: Vec<MonthPlaytime> = self
+            .dataset
+            .monthly_rows()
+            .filter(|row| row.app_id == self.app_id && row.year == self.year)
+            .map(|row| MonthPlaytime {
+                month: row.month.clone(),
+                playtime_seconds: row.playtime_seconds,
+            })
+            .collect();
+        months.sort_by_key(|month| dataset::month_order(&month.month));
+        months
+    }
+}
+
+#[derive(SimpleObject)]
+struct MonthPlaytime {
+    month: String,
+    playtime_seconds: u64,
+}
+
+struct Year {
+    dataset: Arc<Dataset>,
+    year: String,
+    playtime_seconds: u64,
+}
+
+#[Object]
+impl Year {
+    async fn year(&self) -> &str {
+        &self.year
+    }
+
+    async fn playtime_seconds(&self) -> u64 {
+        self.playtime_seconds
+    }
+
+    async fn hours(&self) -> f64 {
+        self.playtime_seconds as f64 / 3600.0
+    }
+
+    /// Months of the year with playtime, in calendar order. Empty for years
+    /// whose replay only lists yearly totals.
+    async fn months(&self) -> Vec<Month> {
+        let mut months: BTreeMap<usize, (String, u64)> = BTreeMap::new();
+        for row in self.dataset.monthly_rows().filter(|row| row.year == self.year) {
+            months
+                .entry(dataset::month_order(&row.month))
+                .or_insert_with(|| (row.month.clone(), 0))
+                .1 += row.playtime_seconds;
+        }
+
+        months
+            .into_values()
+            .map(|(month, playtime_seconds)| Month {
+                dataset: Arc::clone(&self.dataset),
+                year: self.year.clone(),
+                month,
+                playtime_seconds,
+            })
+            .collect()
+    }
+
+    /// The year's games, most played first, by the same yearly figures as
+    /// `playtimeSeconds`: the replay's yearly total, else the months summed.
+    async fn games(&self, limit: Option<usize>) -> Vec<GamePlaytime> {
+        self.dataset
+            .ranked_games(Some(&self.year))
+            .into_iter()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|(playtime_seconds, index)| GamePlaytime {
+                game: Game { dataset: Arc::clone(&self.dataset), index },
+                playtime_seconds,
+            })
+            .collect()
+    }
+}
+
+struct Month {
+    dataset: Arc<Dataset>,
+    year: String,
+    month: String,
+    playtime_seconds: u64,
+}
+
+#[Object]
+impl Month {
+    async fn year(&self) -> &str {
+        &self.year
+    }
+
+    async fn month(&self) -> &str {
+        &self.month
+    }
+
+    async fn playtime_seconds(&self) -> u64 {
+        self.playtime_seconds
+    }
+
+    /// The month's games, most played first.
+    async fn games(&self, limit: Option<usize>) -> Vec<GamePlaytime> {
+        self.dataset
+            .ranked(|row| row.year == self.year && row.month == self.month, limit)
+    }
+}
+
+#[derive(SimpleObject)]
+struct GamePlaytime {
+    game: Game,
+    /// Playtime within the enclosing year or month
+    playtime_seconds: u64,
+}
+
+#[derive(SimpleObject)]
+struct Details {
+    /// "game", "dlc", "demo", ...
+    #[graphql(name = "type")]
+    app_type: Option<String>,
+    is_free: Option<bool>,
+    release_year: Option<i32>,
+    header_image: Option<String>,
+    genres: Vec<String>,
+    categories: Vec<String>,
+    developers: Vec<String>,
+    publishers: Vec<String>,
+}
+
+#[derive(Clone, SimpleObject)]
+struct Achievements {
+    achieved: u64,
+    total: u64,
+    /// Percentage unlocked
+    completion: f64,
+}
+
+struct Site {
+    dataset: Arc<Dataset>,
+    /// Modification times of the watched files when the data was loaded
+    stamps: Vec<Option<SystemTime>>,
+}
+
+pub fn serve_graphql(inputs: &[String], options: &GraphqlOptions) -> Result<()> {
+    let watched: Vec<&str> = inputs
+        .iter()
+        .map(|s| s.as_str())
+        .chain([options.mapping_path.as_str(), options.achievements_path.as_str()])
+        .collect();
+
+    let mut site = Site {
+        dataset: Arc::new(Dataset::load(inputs, options)?),
+        stamps: stamps(&watched),
+    };
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+
+    let listener = TcpListener::bind(("127.0.0.1", options.port))
+        .with_context(|| format!("Failed to listen on port {}; pick another with --port", options.port))?;
+    let address = listener.local_addr()?;
+
+    println!("Serving {} game(s) at http://{}/graphql", site.dataset.games.len(), address);
+    println!("GraphiQL: http://{}/  Schema: http://{}/schema.graphql", address, address);
+    println!("Watching {} file(s) for changes; press Ctrl+C to stop", watched.len());
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("Warning: Connection failed: {}", e);
+                continue;
+            }
+        };
+        refresh(&mut site, &watched, inputs, options);
+        if let Err(e) = respond(stream, &schema, &site.dataset, options.allow_origin.as_deref()) {
+            println!("Warning: Failed to answer a request: {:#}", e);
+        }
+    }
+
+    Ok(())
+}
+
+fn stamps(watched: &[&str]) -> Vec<Option<SystemTime>> {
+    watched
+        .iter()
+        .map(|path| Path::new(path).metadata().and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// Reloads the data when a watched file changed. A failed reload (say, a
+/// CSV caught mid-write) keeps serving the previous data.
+fn refresh(site: &mut Site, watched: &[&str], inputs: &[String], options: &GraphqlOptions) {
+    let stamps = stamps(watched);
+    if stamps == site.stamps {
+        return;
+    }
+    site.stamps = stamps;
+
+    match Dataset::load(inputs, options) {
+        Ok(dataset) => {
+            site.dataset = Arc::new(dataset);
+            println!("Reloaded the data ({})", chrono::Local::now().format("%H:%M:%S"));
+        }
+        Err(e) => println!("Warning: Could not reload the data: {:#}", e),
+    }
+}
+
+fn respond(mut stream: TcpStream, schema: &DashboardSchema, dataset: &Arc<Dataset>, allow_origin: Option<&str>) -> Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut content_length = 0;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().context("Invalid Content-Length")?;
+        }
+        header.clear();
+    }
+    if content_length > MAX_BODY_BYTES {
+        bail!("Request body of {} bytes is too large", content_length);
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("GET");
+    let path = parts.next().unwrap_or("/");
+    let path = path.split('?').next().unwrap_or(path);
+
+    let (status, content_type, body) = match (method, path) {
+        ("OPTIONS", _) => ("204 No Content", "text/plain", String::new()),
+        ("GET", "/") => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            GraphiQLSource::build().endpoint("/graphql").finish(),
+        ),
+        ("GET", "/schema.graphql") => ("200 OK", "text/plain; charset=utf-8", schema.sdl()),
+        ("POST", "/graphql") => match serde_json::from_slice::<async_graphql::Request>(&body) {
+            Ok(request) => {
+                let response = pollster::block_on(schema.execute(request.data(Arc::clone(dataset))));
+                ("200 OK", "application/json", serde_json::to_string(&response)?)
+            }
+            Err(e) => (
+                "400 Bad Request",
+                "application/json",
+                serde_json::json!({ "errors": [{ "message": format!("Invalid GraphQL request: {}", e) }] }).to_string(),
+            ),
+        },
+        _ => ("404 Not Found", "text/plain", "Not found".to_string()),
+    };
+
+    let cors = match allow_origin {
+        Some(origin) => format!(
+            "Access-Control-Allow-Origin: {}\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\nAccess-Control-Allow-Headers: Content-Type\r\nVary: Origin\r\n",
+            origin
+        ),
+        None => String::new(),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Cache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        cors,
+        body
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(app_id: &str, year: &str, month: &str, playtime_seconds: u64) -> PlaytimeRow {
+        PlaytimeRow {
+            app_id: app_id.to_string(),
+            playtime_seconds,
+            year: year.to_string(),
+            month: month.to_string(),
+        }
+    }
+
+    fn dataset(rows: Vec<PlaytimeRow>) -> Dataset {
+        let games = analytics::summarize(&rows, &HashMap::new());
+        Dataset { rows, games, achievements: HashMap::new() }
+    }
+
+    #[test]
+    fn ranks_a_totals_only_year_by_its_totals() {
+        let dataset = dataset(vec![row("10", "2022", "total", 3600), row("20", "2022", "total", 7200)]);
+
+        let ranked: Vec<(u64, &str)> = dataset
+            .ranked_games(Some("2022"))
+            .into_iter()
+            .map(|(seconds, index)| (seconds, dataset.games[index].app_id.as_str()))
+            .collect();
+        assert_eq!(ranked, [(7200, "20"), (3600, "10")]);
+    }
+
+    #[test]
+    fn ranks_a_year_by_its_total_over_its_months() {
+        let dataset = dataset(vec![
+            row("10", "2023", "January", 600),
+            row("10", "2023", "total", 1800),
+            row("20", "2023", "February", 1200),
+        ]);
+
+        let ranked: Vec<(u64, &str)> = dataset
+            .ranked_games(Some("2023"))
+            .into_iter()
+            .map(|(seconds, index)| (seconds, dataset.games[index].app_id.as_str()))
+            .collect();
+        assert_eq!(ranked, [(1800, "10"), (1200, "20")]);
+    }
+}
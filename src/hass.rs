@@ -0,0 +1,175 @@
+//! `push-hass`: publishes a few sensors to Home Assistant through its REST
+//! API (`POST /api/states/<entity_id>`), for wall dashboards:
+//!
+//! - `sensor.steamreplay_hours_this_year`: hours in the latest replay year
+//! - `sensor.steamreplay_hours_last_month`: hours in that year's latest month
+//! - `sensor.steamreplay_top_game`: the latest year's most played game
+//!
+//! The instance and a long-lived access token go in config.toml:
+//!
+//! ```toml
+//! [hass]
+//! url = "http://homeassistant.local:8123"
+//! token = "..."
+//! ```
+//!
+//! States set this way last until Home Assistant restarts, so run the push
+//! after each refresh of the dataset (or from a cron job). MQTT isn't
+//! supported; the REST API works without a broker.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+use crate::dataset;
+use crate::details;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct HassConfig {
+    pub url: Option<String>,
+    pub token: Option<String>,
+}
+
+pub struct PushOptions {
+    pub mapping_path: String,
+    /// Overrides the config's `url`
+    pub url: Option<String>,
+    /// Overrides the config's `token` and `HASS_TOKEN`
+    pub token: Option<String>,
+    /// Print the states instead of sending them
+    pub dry_run: bool,
+}
+
+struct Sensor {
+    entity_id: &'static str,
+    state: Value,
+    attributes: Value,
+}
+
+pub fn push_hass(inputs: &[String], options: &PushOptions) -> Result<()> {
+    let rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+    let sensors = sensors(&rows, &mapping)?;
+
+    if options.dry_run {
+        for sensor in &sensors {
+            println!("{} = {} {}", sensor.entity_id, sensor.state, sensor.attributes);
+        }
+        return Ok(());
+    }
+
+    let config = crate::config::load()?.hass.unwrap_or_default();
+    let Some(url) = options.url.clone().or(config.url) else {
+        bail!(
+            "No Home Assistant URL; add url to the [hass] table in {} or pass --url",
+            crate::config::config_path()?.display()
+        );
+    };
+    let Some(token) = options
+        .token
+        .clone()
+        .or_else(|| std::env::var("HASS_TOKEN").ok())
+        .or(config.token)
+    else {
+        bail!("No Home Assistant access token; add token to the [hass] table, set HASS_TOKEN or pass --token");
+    };
+
+    let base = url.trim_end_matches('/');
+    for sensor in &sensors {
+        let endpoint = format!("{}/api/states/{}", base, sensor.entity_id);
+        let body = json!({ "state": sensor.state, "attributes": sensor.attributes });
+
+        crate::http::send(crate::http::client().post(&endpoint).bearer_auth(&token).json(&body))
+            .and_then(|response| Ok(response.error_for_status()?))
+            .with_context(|| format!("Failed to update {}", sensor.entity_id))?;
+
+        println!("Updated {} = {}", sensor.entity_id, sensor.state.as_str().map(String::from).unwrap_or_else(|| sensor.state.to_string()));
+    }
+
+    crate::summary::count("sensors_pushed", sensors.len() as u64);
+    println!("Pushed {} sensor(s) to {}", sensors.len(), base);
+
+    Ok(())
+}
+
+/// The sensors for the latest year in the dataset.
+fn sensors(rows: &[dataset::PlaytimeRow], mapping: &std::collections::HashMap<String, String>) -> Result<Vec<Sensor>> {
+    let games = crate::analytics::summarize(rows, mapping);
+
+    let Some(year) = games
+        .iter()
+        .flat_map(|game| game.years.iter().map(|(year, _)| year))
+        .filter(|year| year.parse::<i32>().is_ok())
+        .max()
+        .cloned()
+    else {
+        bail!("No replay years in the dataset to report on");
+    };
+
+    // (game, seconds) for the year, most played first
+    let mut year_games: Vec<(&crate::analytics::GameSummary, u64)> = games
+        .iter()
+        .filter_map(|game| game.years.iter().find(|(y, _)| *y == year).map(|(_, seconds)| (game, *seconds)))
+        .collect();
+    year_games.sort_by_key(|(game, seconds)| (std::cmp::Reverse(*seconds), game.app_id.clone()));
+    let year_seconds: u64 = year_games.iter().map(|(_, seconds)| seconds).sum();
+
+    let mut months: BTreeMap<usize, (&str, u64)> = BTreeMap::new();
+    for row in rows.iter().filter(|row| row.year == year) {
+        let order = dataset::month_order(&row.month);
+        if (1..=12).contains(&order) {
+            months.entry(order).or_insert((row.month.as_str(), 0)).1 += row.playtime_seconds;
+        }
+    }
+    let (month, month_seconds) = months.values().next_back().copied().unwrap_or(("", 0));
+
+    let hours = |seconds: u64| json!((seconds as f64 / 360.0).round() / 10.0);
+    let mut sensors = vec![
+        Sensor {
+            entity_id: "sensor.steamreplay_hours_this_year",
+            state: hours(year_seconds),
+            attributes: json!({
+                "friendly_name": "Steam hours this year",
+                "unit_of_measurement": "h",
+                "icon": "mdi:steam",
+                "year": year,
+            }),
+        },
+        Sensor {
+            entity_id: "sensor.steamreplay_hours_last_month",
+            state: hours(month_seconds),
+            attributes: json!({
+                "friendly_name": "Steam hours last month",
+                "unit_of_measurement": "h",
+                "icon": "mdi:steam",
+                "year": year,
+                "month": month,
+            }),
+        },
+    ];
+
+    if let Some((game, seconds)) = year_games.first() {
+        let mut attributes = json!({
+            "friendly_name": "Steam top game",
+            "icon": "mdi:trophy",
+            "app_id": game.app_id,
+            "hours": hours(*seconds),
+            "year": year,
+        });
+        // Shown instead of the icon on dashboard cards
+        if let Ok(Some(entry)) = details::cached(&game.app_id)
+            && let Some(image) = details::mapping_field(&entry, "header_image").as_str()
+        {
+            attributes["entity_picture"] = json!(image);
+        }
+
+        sensors.push(Sensor {
+            entity_id: "sensor.steamreplay_top_game",
+            state: json!(crate::report::game_name(mapping, &game.app_id)),
+            attributes,
+        });
+    }
+
+    Ok(sensors)
+}
@@ -0,0 +1,159 @@
+//! Calendar heatmap of hours played per month: years as rows, months as
+//! columns, shaded like a contributions graph. Printed to the terminal and
+//! optionally written as SVG. Years the dataset only has totals for (no
+//! monthly rows) get their total and a note instead of an empty row.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::dataset::{self, PlaytimeRow};
+use crate::i18n::{self, t};
+
+const SHADES: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+pub const COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+const CELL: usize = 28;
+const GAP: usize = 4;
+const LABEL_WIDTH: usize = 48;
+const HEADER_HEIGHT: usize = 24;
+
+pub fn print_heatmap(inputs: &[String], svg_path: Option<&str>) -> Result<()> {
+    let rows = dataset::load_rows(inputs)?;
+    let hours = crate::analytics::monthly_hours(&rows);
+    let totals = totals_only(&rows);
+
+    if hours.is_empty() {
+        println!("{}", t!("heatmap-empty"));
+        return Ok(());
+    }
+
+    let max = hours.values().flatten().copied().fold(0.0, f64::max);
+
+    println!("{}", t!("heatmap-title"));
+    print!("{:<6}", "");
+    for index in 0..12 {
+        print!(" {}", i18n::month_abbreviation(index, 1));
+    }
+    println!();
+
+    for (year, months) in &hours {
+        print!("{:<6}", year);
+        if let Some(total) = totals.get(year) {
+            println!(" {:<23}  {:>7.1}h", t!("heatmap-no-months"), total);
+            continue;
+        }
+        for &value in months {
+            print!(" {}", SHADES[level(value, max)]);
+        }
+        println!("  {:>7.1}h", months.iter().sum::<f64>());
+    }
+
+    println!(
+        "\n{}",
+        t!("heatmap-legend", none = SHADES[0], low = SHADES[1], high = SHADES[4], max = format!("{:.1}", max))
+    );
+
+    if let Some(path) = svg_path {
+        fs::write(path, render_svg(&hours, &totals, max))
+            .with_context(|| format!("Failed to write {}", path))?;
+        crate::summary::file_written(path);
+        println!("{}", t!("heatmap-saved", path = path));
+    }
+
+    Ok(())
+}
+
+/// The heatmap as an SVG document, or None without monthly playtime.
+pub fn svg(rows: &[PlaytimeRow]) -> Option<String> {
+    let hours = crate::analytics::monthly_hours(rows);
+    if hours.is_empty() {
+        return None;
+    }
+    let max = hours.values().flatten().copied().fold(0.0, f64::max);
+    Some(render_svg(&hours, &totals_only(rows), max))
+}
+
+/// year -> hours, for the years with yearly totals but no monthly rows.
+fn totals_only(rows: &[PlaytimeRow]) -> BTreeMap<String, f64> {
+    let monthly = crate::analytics::monthly_by_game(rows);
+    crate::analytics::yearly_totals(rows)
+        .into_iter()
+        .filter(|(year, _)| !monthly.contains_key(year))
+        .map(|(year, games)| (year.to_string(), games.iter().map(|row| row.hours()).sum()))
+        .collect()
+}
+
+/// Shade index: 0 for no playtime, then four equal steps up to `max`.
+pub fn level(value: f64, max: f64) -> usize {
+    if value <= 0.0 || max <= 0.0 {
+        0
+    } else {
+        1 + ((value / max * 4.0).ceil() as usize - 1).min(3)
+    }
+}
+
+fn render_svg(hours: &BTreeMap<String, [f64; 12]>, totals: &BTreeMap<String, f64>, max: f64) -> String {
+    let width = LABEL_WIDTH + 12 * (CELL + GAP);
+    let height = HEADER_HEIGHT + hours.len() * (CELL + GAP);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        width, height
+    );
+
+    for index in 0..12 {
+        let x = LABEL_WIDTH + index * (CELL + GAP) + CELL / 2;
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"16\" text-anchor=\"middle\" fill=\"#57606a\">{}</text>\n",
+            x,
+            i18n::month_abbreviation(index, 3)
+        ));
+    }
+
+    for (row, (year, months)) in hours.iter().enumerate() {
+        let y = HEADER_HEIGHT + row * (CELL + GAP);
+        svg.push_str(&format!(
+            "  <text x=\"0\" y=\"{}\" fill=\"#57606a\">{}</text>\n",
+            y + CELL / 2 + 4,
+            year
+        ));
+
+        if let Some(total) = totals.get(year) {
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"3\" fill=\"none\" stroke=\"{}\" stroke-dasharray=\"4 3\"/>\n",
+                LABEL_WIDTH,
+                y,
+                12 * (CELL + GAP) - GAP,
+                CELL,
+                COLORS[2]
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"#57606a\">{} · {:.1}h</text>\n",
+                LABEL_WIDTH + (12 * (CELL + GAP) - GAP) / 2,
+                y + CELL / 2 + 4,
+                crate::html_report::escape(&t!("heatmap-no-months")),
+                total
+            ));
+            continue;
+        }
+
+        for (index, &value) in months.iter().enumerate() {
+            let x = LABEL_WIDTH + index * (CELL + GAP);
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"3\" fill=\"{}\"><title>{} {}: {:.1}h</title></rect>\n",
+                x,
+                y,
+                CELL,
+                CELL,
+                COLORS[level(value, max)],
+                i18n::month_name(index),
+                year,
+                value
+            ));
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
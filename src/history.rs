@@ -0,0 +1,155 @@
+//! Append-only log of every run (`runs.jsonl` in the data directory), one
+//! summary.json-style record per line, and the `history` command that
+//! lists it.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::config;
+
+pub const RUNS_FILENAME: &str = "runs.jsonl";
+
+pub struct HistoryOptions {
+    /// Show the most recent N runs
+    pub limit: usize,
+    /// Only runs of this command
+    pub command: Option<String>,
+    /// Only runs that failed or completed with warnings
+    pub failed: bool,
+    /// Also list each run's inputs, outputs and failures
+    pub details: bool,
+}
+
+/// The fields of a record `history` shows; anything else in the line is
+/// ignored, so older records stay readable.
+#[derive(Deserialize)]
+struct Run {
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    inputs: Vec<String>,
+    started_at: String,
+    duration_seconds: f64,
+    success: bool,
+    error: Option<String>,
+    #[serde(default)]
+    files_written: Vec<String>,
+    #[serde(default)]
+    failures: Vec<RunFailure>,
+    #[serde(default)]
+    http_requests: BTreeMap<String, u64>,
+}
+
+#[derive(Deserialize)]
+struct RunFailure {
+    item: String,
+    reason: String,
+}
+
+pub fn runs_path() -> Result<PathBuf> {
+    Ok(config::data_dir()?.join(RUNS_FILENAME))
+}
+
+/// Appends one JSON record (without a trailing newline) to runs.jsonl.
+pub fn append(record: &str) -> Result<()> {
+    let path = runs_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", record)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
+pub fn print_history(options: &HistoryOptions) -> Result<()> {
+    let path = runs_path()?;
+    if !path.exists() {
+        println!("No runs recorded yet ({} doesn't exist)", path.display());
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let mut skipped = 0;
+    let runs: Vec<Run> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let run = serde_json::from_str(line).ok();
+            skipped += run.is_none() as usize;
+            run
+        })
+        .filter(|run: &Run| options.command.as_ref().is_none_or(|command| &run.command == command))
+        .filter(|run| !options.failed || !run.success || !run.failures.is_empty())
+        .collect();
+
+    let shown = &runs[runs.len().saturating_sub(options.limit)..];
+
+    println!(
+        "{:<25} {:<16} {:>9} {:<10} {:>5} {:>5} {:>8}",
+        "Started", "Command", "Duration", "Status", "Files", "HTTP", "Failures"
+    );
+    for run in shown {
+        let status = match (run.success, run.failures.is_empty()) {
+            (false, _) => "error",
+            (true, false) => "warnings",
+            (true, true) => "ok",
+        };
+        println!(
+            "{:<25} {:<16} {:>8.1}s {:<10} {:>5} {:>5} {:>8}",
+            run.started_at.get(..19).unwrap_or(&run.started_at).replace('T', " "),
+            run.command,
+            run.duration_seconds,
+            status,
+            run.files_written.len(),
+            run.http_requests.values().sum::<u64>(),
+            run.failures.len()
+        );
+
+        if options.details {
+            print_details(run);
+        }
+    }
+
+    println!("\n{} of {} run(s) shown, from {}", shown.len(), runs.len(), path.display());
+    if skipped > 0 {
+        println!("Warning: Skipped {} unreadable line(s)", skipped);
+    }
+
+    Ok(())
+}
+
+fn print_details(run: &Run) {
+    if !run.args.is_empty() {
+        println!("    args:    {}", run.args.join(" "));
+    }
+    if !run.inputs.is_empty() {
+        println!("    inputs:  {}", run.inputs.join(", "));
+    }
+    for file in &run.files_written {
+        println!("    wrote:   {}", file);
+    }
+    for (host, count) in &run.http_requests {
+        println!("    http:    {} x{}", host, count);
+    }
+    for failure in &run.failures {
+        println!("    failed:  {}: {}", failure.item, failure.reason);
+    }
+    if let Some(error) = &run.error {
+        println!("    error:   {}", error);
+    }
+}
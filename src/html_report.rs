@@ -0,0 +1,87 @@
+//! The report as a standalone HTML page (`report --html FILE`, and what
+//! `report --serve` hosts): each year's top games with bars and the monthly
+//! heatmap. Labels follow `--report-locale` like the terminal report.
+
+use anyhow::{Context, Result};
+use std::fs;
+
+use crate::dataset;
+use crate::i18n::t;
+use crate::report::{ReportOptions, game_name};
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:56rem;margin:2rem auto;padding:0 1rem;color:#24292f}\
+h1{border-bottom:2px solid #d0d7de;padding-bottom:.3rem}\
+h2{margin-top:2rem}\
+table{border-collapse:collapse;width:100%}\
+td{padding:.2rem .5rem;vertical-align:middle}\
+td.rank,td.hours{text-align:right;white-space:nowrap;width:1%}\
+.bar{background:#40c463;height:.8rem;border-radius:2px}\
+.generated{color:#57606a;font-size:.85rem;margin-top:3rem}";
+
+pub fn render(inputs: &[String], options: &ReportOptions) -> Result<String> {
+    let rows = crate::report::load_rows(inputs, options)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+    let title = t!("report-title");
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape(&title),
+        STYLE,
+        escape(&title)
+    );
+    if let Some(months) = &options.months {
+        html.push_str(&format!(
+            "<p>{}</p>\n",
+            escape(&t!("report-months", months = dataset::describe_months(months)))
+        ));
+    }
+
+    for (year, games) in crate::analytics::yearly_totals(&rows) {
+        let total_hours: f64 = games.iter().map(|row| row.hours()).sum();
+        html.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n",
+            escape(&t!("report-year-summary", year = year, hours = format!("{:.1}", total_hours), games = games.len()))
+        ));
+
+        let top_hours = games.first().map(|row| row.hours()).unwrap_or(0.0).max(f64::EPSILON);
+        for (rank, row) in games.iter().take(options.top_n).enumerate() {
+            html.push_str(&format!(
+                "<tr><td class=\"rank\">{}.</td><td>{}</td><td class=\"hours\">{:.1}h</td><td><div class=\"bar\" style=\"width:{:.0}%\"></div></td></tr>\n",
+                rank + 1,
+                escape(&game_name(&mapping, &row.app_id)),
+                row.hours(),
+                row.hours() / top_hours * 100.0
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    if let Some(svg) = crate::heatmap::svg(&rows) {
+        html.push_str(&format!("<h2>{}</h2>\n{}", escape(&t!("heatmap-title")), svg));
+    }
+
+    html.push_str(&format!(
+        "<p class=\"generated\">steamreplay {} &middot; {}</p>\n</body>\n</html>\n",
+        env!("CARGO_PKG_VERSION"),
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    ));
+
+    Ok(html)
+}
+
+pub fn write_html(inputs: &[String], options: &ReportOptions, path: &str) -> Result<()> {
+    fs::write(path, render(inputs, options)?)
+        .with_context(|| format!("Failed to write {}", path))?;
+    crate::summary::file_written(path);
+
+    println!("HTML report saved to: {}", path);
+
+    Ok(())
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
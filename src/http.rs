@@ -0,0 +1,160 @@
+//! Shared HTTP client. Every request goes through `send`, which paces calls
+//! per host with a token bucket so that commands run back to back in one
+//! pipeline don't add up to bursts that trip Steam's rate limits.
+//!
+//! With `--offline` it refuses every request instead, so nothing reaches the
+//! network; commands then work from the caches or report what they couldn't
+//! resolve.
+
+use anyhow::{Result, bail};
+use reqwest::StatusCode;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_offline() {
+    OFFLINE.store(true, Ordering::Relaxed);
+}
+
+pub fn offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Burst size and sustained interval between requests for a host.
+struct Limit {
+    burst: f64,
+    interval: Duration,
+}
+
+fn limit_for(host: &str) -> Option<Limit> {
+    let (burst, interval_ms) = match host {
+        // appdetails allows roughly 200 requests per 5 minutes
+        "store.steampowered.com" => (1.0, 1500),
+        "steamcommunity.com" => (1.0, 1000),
+        "api.steampowered.com" => (5.0, 250),
+        _ => return None,
+    };
+
+    Some(Limit {
+        burst,
+        interval: Duration::from_millis(interval_ms),
+    })
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    // Set after a 429 so that the host is left alone for a while
+    blocked_until: Option<Instant>,
+}
+
+fn buckets() -> &'static Mutex<HashMap<String, Bucket>> {
+    static BUCKETS: OnceLock<Mutex<HashMap<String, Bucket>>> = OnceLock::new();
+    BUCKETS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .user_agent(concat!("steamreplay/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("Failed to build HTTP client")
+    })
+}
+
+pub fn get(url: &str) -> Result<Response> {
+    send(client().get(url))
+}
+
+pub fn send(request: RequestBuilder) -> Result<Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    let host = request.url().host_str().unwrap_or_default().to_string();
+    if offline() {
+        bail!("Not fetching {}: network access is disabled by --offline", redact_url(request.url()));
+    }
+
+    wait_for_slot(&host);
+    crate::summary::http_request(&host);
+
+    let response = client.execute(request).map_err(redact_error)?;
+
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(60);
+        println!("  Rate limited by {}, pausing requests to it for {}s", host, retry_after);
+        if let Some(bucket) = buckets().lock().unwrap().get_mut(&host) {
+            bucket.blocked_until = Some(Instant::now() + Duration::from_secs(retry_after));
+        }
+    }
+
+    Ok(response)
+}
+
+/// Hides the Web API key in the URL a request error carries, which its
+/// message includes and which ends up in logs and the run history.
+pub fn redact_error(mut error: reqwest::Error) -> reqwest::Error {
+    if let Some(url) = error.url_mut() {
+        *url = redact_url(url);
+    }
+    error
+}
+
+fn redact_url(url: &reqwest::Url) -> reqwest::Url {
+    let mut redacted = url.clone();
+    if url.query_pairs().any(|(name, _)| name == "key") {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(name, value)| {
+                let value = if name == "key" { "REDACTED".to_string() } else { value.into_owned() };
+                (name.into_owned(), value)
+            })
+            .collect();
+        redacted.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    redacted
+}
+
+fn wait_for_slot(host: &str) {
+    let Some(limit) = limit_for(host) else {
+        return;
+    };
+
+    loop {
+        let wait = {
+            let mut buckets = buckets().lock().unwrap();
+            let bucket = buckets.entry(host.to_string()).or_insert_with(|| Bucket {
+                tokens: limit.burst,
+                updated: Instant::now(),
+                blocked_until: None,
+            });
+
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            let refill = elapsed / limit.interval.as_secs_f64();
+            bucket.tokens = (bucket.tokens + refill).min(limit.burst);
+            bucket.updated = now;
+
+            match bucket.blocked_until {
+                Some(until) if until > now => until - now,
+                _ if bucket.tokens >= 1.0 => {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                _ => limit.interval.mul_f64(1.0 - bucket.tokens),
+            }
+        };
+
+        thread::sleep(wait);
+    }
+}
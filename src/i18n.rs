@@ -0,0 +1,190 @@
+//! Localised report labels, using Fluent messages bundled from
+//! `locales/<lang>.ftl`. What is translated is the output of the commands
+//! that present the dataset: `report` (text, HTML and PDF), `compare-global`,
+//! `game`, `heatmap`, `goals` and `family report`. Progress, diagnostics
+//! and errors of the other commands stay in English.
+//!
+//! The locale comes from the global `--report-locale` flag, else `LC_ALL`,
+//! `LC_MESSAGES` or `LANG`, else English. Messages missing from a locale
+//! fall back to English. Data values (month columns in CSVs, app IDs) are
+//! never translated.
+
+use anyhow::{Result, bail};
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::env;
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// (language, Fluent source) for every bundled locale; the first is the
+/// fallback.
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+
+struct Bundles {
+    selected: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+static BUNDLES: OnceLock<Bundles> = OnceLock::new();
+
+/// Picks the locale for this run. An explicit `--report-locale` must be one of the
+/// bundled ones; an unsupported environment locale just falls back to
+/// English.
+pub fn init(locale: Option<&str>) -> Result<()> {
+    let language = match locale {
+        Some(locale) => match language_of(locale).filter(|lang| is_bundled(lang)) {
+            Some(lang) => lang,
+            None => bail!("Unsupported locale '{}' (available: {})", locale, available().join(", ")),
+        },
+        None => environment_language(),
+    };
+
+    let _ = BUNDLES.set(bundles(&language));
+
+    Ok(())
+}
+
+fn environment_language() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| language_of(&value))
+        .filter(|lang| is_bundled(lang))
+        .unwrap_or_else(|| LOCALES[0].0.to_string())
+}
+
+fn bundles(language: &str) -> Bundles {
+    Bundles {
+        selected: bundle(language),
+        fallback: bundle(LOCALES[0].0),
+    }
+}
+
+fn available() -> Vec<&'static str> {
+    LOCALES.iter().map(|(lang, _)| *lang).collect()
+}
+
+/// `de_DE.UTF-8` / `es-MX` -> `de` / `es`
+fn language_of(locale: &str) -> Option<String> {
+    let tag = locale.split(['.', '@']).next()?.replace('_', "-");
+    let langid: LanguageIdentifier = tag.parse().ok()?;
+    Some(langid.language.as_str().to_string())
+}
+
+fn is_bundled(language: &str) -> bool {
+    LOCALES.iter().any(|(lang, _)| *lang == language)
+}
+
+fn bundle(language: &str) -> FluentBundle<FluentResource> {
+    let (lang, source) = LOCALES
+        .iter()
+        .find(|(lang, _)| *lang == language)
+        .unwrap_or(&LOCALES[0]);
+
+    let langid: LanguageIdentifier = lang.parse().expect("Invalid bundled locale");
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Unicode isolation marks would throw off terminal column alignment
+    bundle.set_use_isolating(false);
+
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, errors)| panic!("Invalid locales/{}.ftl: {:?}", lang, errors));
+    bundle
+        .add_resource(resource)
+        .expect("Duplicate message in bundled locale");
+
+    bundle
+}
+
+/// Formats message `id`, falling back to English and then to the ID itself.
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    let bundles = BUNDLES.get_or_init(|| bundles(&environment_language()));
+
+    for bundle in [&bundles.selected, &bundles.fallback] {
+        let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        return bundle.format_pattern(pattern, args, &mut errors).into_owned();
+    }
+
+    id.to_string()
+}
+
+/// Display name of a month (0 = January); use `get_month_name` for the
+/// values stored in datasets.
+pub fn month_name(index: usize) -> String {
+    message(&format!("month-{}", index + 1), None)
+}
+
+/// The first `len` characters of the month's display name.
+pub fn month_abbreviation(index: usize, len: usize) -> String {
+    month_name(index).chars().take(len).collect()
+}
+
+/// Display name of a `--group-by` period label; quarters stay as they are.
+pub fn period_name(label: &str) -> String {
+    match label {
+        "Winter" | "Spring" | "Summer" | "Fall" => message(&format!("season-{}", label.to_lowercase()), None),
+        _ => label.to_string(),
+    }
+}
+
+/// `t!("id")` or `t!("id", name = value, ...)`: a localised message.
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:expr, $($key:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($key), $value);)+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+
+pub(crate) use t;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn message_ids(source: &str) -> BTreeSet<&str> {
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+            .filter_map(|line| line.split_once('=').map(|(id, _)| id.trim()))
+            .collect()
+    }
+
+    #[test]
+    fn every_locale_has_the_english_messages() {
+        let english = message_ids(LOCALES[0].1);
+        for (lang, source) in &LOCALES[1..] {
+            let ids = message_ids(source);
+            let missing: Vec<_> = english.difference(&ids).collect();
+            let unknown: Vec<_> = ids.difference(&english).collect();
+            assert!(missing.is_empty(), "locales/{}.ftl is missing {:?}", lang, missing);
+            assert!(unknown.is_empty(), "locales/{}.ftl has messages en.ftl doesn't: {:?}", lang, unknown);
+        }
+    }
+
+    #[test]
+    fn every_locale_parses() {
+        for (lang, _) in LOCALES {
+            let bundle = bundle(lang);
+            assert!(bundle.has_message("month-1"), "locales/{}.ftl", lang);
+        }
+    }
+
+    #[test]
+    fn reads_the_language_from_locale_names() {
+        assert_eq!(language_of("de_DE.UTF-8").as_deref(), Some("de"));
+        assert_eq!(language_of("es-MX").as_deref(), Some("es"));
+        assert_eq!(language_of("en_GB.UTF-8@euro").as_deref(), Some("en"));
+    }
+}
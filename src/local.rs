@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::vdf;
+
+// SteamID64 = account ID (the userdata/<id> folder name) + this base
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+pub fn import_local(config_path: Option<&str>, year_filter: Option<&str>, name_template: Option<&str>) -> Result<()> {
+    let config_path = match config_path {
+        Some(path) => PathBuf::from(path),
+        None => find_localconfig().context(
+            "Could not locate localconfig.vdf; pass its path explicitly \
+             (Steam/userdata/<account_id>/config/localconfig.vdf)",
+        )?,
+    };
+
+    println!("Reading local Steam config: {}", config_path.display());
+
+    let root = vdf::parse_file(&config_path)?;
+
+    let apps = find_apps_section(&root)
+        .context("No Software/Valve/Steam/apps section found in localconfig.vdf")?;
+
+    let mut csv_rows: Vec<(String, u64, String)> = Vec::new(); // (app_id, playtime_seconds, year)
+
+    for (app_id, entry) in apps {
+        if !app_id.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+
+        // Playtime is stored in minutes, LastPlayed as a unix timestamp
+        let playtime_minutes = entry
+            .get("Playtime")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        if playtime_minutes == 0 {
+            continue;
+        }
+
+        let year = entry
+            .get("LastPlayed")
+            .and_then(|v| v.as_u64())
+            .filter(|&ts| ts > 0)
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+            .map(|dt| dt.format("%Y").to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+
+        if let Some(wanted) = year_filter
+            && year != wanted
+        {
+            continue;
+        }
+
+        csv_rows.push((app_id.clone(), playtime_minutes * 60, year));
+    }
+
+    println!("  Found {} apps with recorded playtime", csv_rows.len());
+
+    csv_rows.sort_by(|a, b| a.2.cmp(&b.2).then(a.0.cmp(&b.0)));
+
+    // Same schema as steam_replay_data.csv; local playtime is lifetime, not per-month
+    let steam_id = extract_account_id(&config_path)
+        .map(|id| (id + STEAM_ID64_BASE).to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let csv_filename = crate::template::render(
+        name_template.unwrap_or("steam_local_data_{steamid}.csv"),
+        &[("steamid", &steam_id)],
+    )?;
+    crate::template::create_parent_dir(std::path::Path::new(&csv_filename))?;
+    let mut csv_content = String::from("app_id,playtime_in_seconds,year,month\n");
+
+    for (app_id, playtime_seconds, year) in csv_rows {
+        csv_content.push_str(&format!("{},{},{},lifetime\n", app_id, playtime_seconds, year));
+    }
+
+    fs::write(&csv_filename, csv_content)
+        .context("Failed to write CSV file")?;
+
+    crate::summary::file_written(&csv_filename);
+    println!("\nLocal playtime data saved to: {}", csv_filename);
+
+    Ok(())
+}
+
+fn find_localconfig() -> Option<PathBuf> {
+    let home = env::var("HOME").or_else(|_| env::var("USERPROFILE")).ok()?;
+    let home = Path::new(&home);
+
+    let steam_roots = [
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+        home.join("Library/Application Support/Steam"),
+        PathBuf::from("C:\\Program Files (x86)\\Steam"),
+    ];
+
+    for root in steam_roots {
+        let Ok(entries) = fs::read_dir(root.join("userdata")) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let candidate = entry.path().join("config").join("localconfig.vdf");
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+
+    None
+}
+
+fn extract_account_id(config_path: &Path) -> Option<u64> {
+    // .../userdata/<account_id>/config/localconfig.vdf
+    config_path
+        .parent()?
+        .parent()?
+        .file_name()?
+        .to_str()?
+        .parse()
+        .ok()
+}
+
+fn find_apps_section(root: &vdf::Value) -> Option<&[(String, vdf::Value)]> {
+    let store = root.get("UserLocalConfigStore").unwrap_or(root);
+    store.get_path(&["Software", "Valve", "Steam", "apps"])?.entries()
+}
@@ -1,63 +1,711 @@
 use anyhow::{Context, Result};
+use chrono::Datelike;
 use scraper::{Html, Selector};
 use serde_json::{json, Value};
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
-use std::thread;
-use std::time::Duration;
+use std::time::Instant;
+
+
+mod achievements;
+mod analytics;
+mod archive;
+mod audit;
+mod auth;
+mod badges;
+mod check_new;
+mod clean;
+mod community;
+mod compare;
+mod config;
+mod consolidate;
+mod cookies;
+mod csv_export;
+mod dataset;
+mod details;
+mod doctor;
+mod drift;
+mod export;
+mod family;
+mod external;
+mod forecast;
+mod game;
+mod goals;
+mod graphql;
+mod hass;
+mod heatmap;
+mod history;
+mod html_report;
+mod http;
+mod i18n;
+mod local;
+mod mapping_failures;
+mod master;
+mod milestones;
+mod narrative;
+mod overrides;
+mod pdf_report;
+mod per_game;
+mod progress;
+mod replay;
+mod report;
+mod reviews;
+mod provenance;
+mod saved_page;
+mod schema;
+mod self_update;
+mod serve;
+mod session;
+mod share_image;
+mod shields;
+mod summary;
+mod template;
+mod trial;
+mod vdf;
+mod verify;
 
 fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let write_summary = take_flag(&mut args, "--summary");
+    let locale = take_option(&mut args, "--report-locale");
+    i18n::init(locale.as_deref())?;
+    summary::set_strict(take_flag(&mut args, "--strict"));
+    match take_option(&mut args, "--progress").as_deref() {
+        Some("json") => progress::enable(),
+        Some("text") | None => {}
+        Some(other) => anyhow::bail!("Unknown progress format '{}' (expected json)", other),
+    }
+    if take_flag(&mut args, "--offline") {
+        http::set_offline();
+    }
 
     if args.len() < 2 {
         eprintln!("Usage:");
-        eprintln!("  {} scrape <steam_replay_url>", args[0]);
-        eprintln!("  {} map-games [json_files...]", args[0]);
-        eprintln!("  {} to-csv [json_files...]", args[0]);
+        eprintln!("  {} scrape <steam_replay_url|steamid> [--latest] [--allow-archive] [--keep-html] [--from-html FILE] [--with-share-image] [--name-template TEMPLATE] [--accept-schema]", args[0]);
+        eprintln!("  {} check-new <steamid|replay_url> [--year YYYY] [--webhook URL] [--scrape]", args[0]);
+        eprintln!("  {} map-games [json_files...] [--format csv|json|toml] [--fields type,is_free,release_year,header_image] [--limit N|--sample N]", args[0]);
+        eprintln!("  {} map-games --retry-failed [--format csv|json|toml] [--fields ...] [--limit N|--sample N]", args[0]);
+        eprintln!("  {} to-csv [json_files...] [--columns COLS] [--excel [--excel-locale LOCALE]] [--merge] [--strict] [--group-by quarter|season [--hemisphere north|south]] [--months 6..9] [--filter-name REGEX] [--exclude-name REGEX] [--fold-demos] [--sort-by playtime|name|month|app_id [--desc]] [--name-template TEMPLATE] [--split-per-file [--split-name-template TEMPLATE] [--no-combined]]", args[0]);
+        eprintln!("  {} import-local [localconfig.vdf] [--year YYYY] [--name-template TEMPLATE]", args[0]);
+        eprintln!("  {} community-games <steamid|vanity|profile_url> [--name-template TEMPLATE]", args[0]);
+        eprintln!("  {} achievements [json_files...] [--api-key KEY] [--limit N|--sample N]", args[0]);
+        eprintln!("  {} auth set-key [KEY]", args[0]);
+        eprintln!("  {} auth check", args[0]);
+        eprintln!("  {} auth login", args[0]);
+        eprintln!("  {} auth import-cookies --browser firefox|chrome [--profile COOKIE_DB]", args[0]);
+        eprintln!("  {} profile-badges <steamid|vanity|profile_url> [--year YYYY]", args[0]);
+        eprintln!("  {} reviews <steamid|vanity|profile_url>", args[0]);
+        eprintln!("  {} report [csv_or_json_files...] [--mapping FILE] [--profile FILE] [--reviews FILE] [--top N] [--deltas] [--release-split] [--forecast] [--milestones] [--narrative] [--months 6..9] [--filter-name REGEX] [--exclude-name REGEX] [--fold-demos] [--group-by quarter|season [--hemisphere north|south]] [--format text|html|pdf] [--output FILE] [--font FILE] [--html FILE] [--serve [--port N] [--no-open]]", args[0]);
+        eprintln!("  {} compare-global [csv_or_json_files...] [--baseline FILE] [--mapping FILE]", args[0]);
+        eprintln!("  {} details <appid...> [--from FILE] [--refresh] [--limit N|--sample N]", args[0]);
+        eprintln!("  {} audit-mapping [csv_or_json_files...] [--mapping FILE] [--output FILE]", args[0]);
+        eprintln!("  {} verify-mapping [--mapping FILE] [--limit N|--sample N] [--output FILE] [--apply]", args[0]);
+        eprintln!("  {} export --format steamdb|backloggd|hltb|playnite|arrow [csv_or_json_files...] [--output FILE] [--year YYYY] [--mapping FILE] [--split-by genre|category|developer|publisher]", args[0]);
+        eprintln!("  {} import --format playnite|csv <file> [--year YYYY] [--source NAME] [--mapping FILE]", args[0]);
+        eprintln!("  {} game <appid|name> [csv_or_json_files...] [--mapping FILE]", args[0]);
+        eprintln!("  {} heatmap [csv_or_json_files...] [--svg FILE]", args[0]);
+        eprintln!("  {} merge <json_files...> [--output master.json]", args[0]);
+        eprintln!("  {} graphql [csv_or_json_files...] [--mapping FILE] [--achievements FILE] [--port N] [--allow-origin ORIGIN]", args[0]);
+        eprintln!("  {} goals [csv_or_json_files...] [--year YYYY] [--mapping FILE]", args[0]);
+        eprintln!("  {} badge [csv_or_json_files...] [--year YYYY] [--mapping FILE] [--output-dir DIR]", args[0]);
+        eprintln!("  {} push-hass [csv_or_json_files...] [--mapping FILE] [--url URL] [--token TOKEN] [--dry-run]", args[0]);
+        eprintln!("  {} split-per-game [csv_or_json_files...] [--output-dir DIR] [--mapping FILE] [--with-artwork]", args[0]);
+        eprintln!("  {} schema replay|dataset|master [--output FILE|-]", args[0]);
+        eprintln!("  {} doctor [replay_url]", args[0]);
+        eprintln!("  {} clean [--cache|--art|--all]", args[0]);
+        eprintln!("  {} self-update [--check] [--version TAG] [--force]", args[0]);
+        eprintln!("  {} family scrape --year YYYY [--name-template TEMPLATE]", args[0]);
+        eprintln!("  {} family report <json_files...> [--year YYYY] [--top N] [--mapping FILE] [--output FILE] [--api-key KEY]", args[0]);
+        eprintln!("  {} history [--limit N] [--command NAME] [--failed] [--details]", args[0]);
+        eprintln!("\nName templates take {{steamid}}, {{year}} and {{date}} (YYYY-MM-DD), e.g. \"replays/{{steamid}}_{{year}}_{{date}}.json\".");
+        eprintln!("\nAdd --summary to any command to also write {}.", summary::SUMMARY_FILENAME);
+        eprintln!(
+            "Items that fail (bad files, failed fetches, unmapped games) are skipped and the run exits with status {}; add --strict to stop at the first one instead.",
+            summary::EXIT_COMPLETED_WITH_WARNINGS
+        );
+        eprintln!("Add --progress json to any command for newline-delimited JSON progress events on stderr.");
+        eprintln!("Add --offline to any command to work from the caches only: nothing is fetched, and what isn't cached is reported.");
+        eprintln!("Add --report-locale en|de|es to localise report labels (report, compare-global, game, heatmap, goals, family report; default: from LANG).");
         eprintln!("\nExamples:");
         eprintln!("  {} scrape https://store.steampowered.com/replay/76561198069815823/2024?l=english", args[0]);
+        eprintln!("  cat targets.txt | {} scrape -", args[0]);
         eprintln!("  {} map-games steam_replay_*.json", args[0]);
         eprintln!("  {} to-csv steam_replay_*.json", args[0]);
+        eprintln!("  {} to-csv steam_replay_*.json --columns \"app_id,game_name=Game,hours,month\"", args[0]);
+        eprintln!("  {} import-local --year 2024", args[0]);
+        eprintln!("  {} community-games 76561198069815823", args[0]);
+        eprintln!("  {} auth set-key", args[0]);
+        eprintln!("  {} achievements steam_replay_*.json", args[0]);
+        eprintln!("  {} report steam_replay_data.csv", args[0]);
         std::process::exit(1);
     }
 
+    let started_at = chrono::Utc::now();
+    let started = Instant::now();
+    progress::start(&args[1]);
+    let result = run(&args);
+    progress::done(&result);
+
+    summary::finish(&args[1..], started_at, started, &result, write_summary)?;
+
+    result?;
+
+    if let Some(status) = summary::exit_status() {
+        std::process::exit(status);
+    }
+    if summary::print_warnings() {
+        std::process::exit(summary::EXIT_COMPLETED_WITH_WARNINGS);
+    }
+
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<()> {
     let command = &args[1];
 
     match command.as_str() {
         "scrape" => {
-            if args.len() < 3 {
+            let mut rest = args[2..].to_vec();
+            let options = ScrapeOptions {
+                with_share_image: take_flag(&mut rest, "--with-share-image"),
+                allow_archive: take_flag(&mut rest, "--allow-archive"),
+                keep_html: take_flag(&mut rest, "--keep-html"),
+                from_html: take_option(&mut rest, "--from-html"),
+                name_template: take_option(&mut rest, "--name-template"),
+                accept_schema: take_flag(&mut rest, "--accept-schema"),
+            };
+            let latest = take_flag(&mut rest, "--latest");
+            let delay = match take_option(&mut rest, "--delay") {
+                Some(delay) => delay.parse().with_context(|| format!("Invalid delay '{}'", delay))?,
+                None => SCRAPE_BATCH_DELAY_SECS,
+            };
+            let Some(target) = rest.first() else {
                 eprintln!("Error: Missing URL argument");
-                eprintln!("Usage: {} scrape <steam_replay_url>", args[0]);
+                eprintln!("Usage: {} scrape <steam_replay_url|steamid|-> [--latest] [--allow-archive] [--keep-html] [--from-html FILE] [--with-share-image] [--name-template TEMPLATE] [--accept-schema] [--delay SECONDS]", args[0]);
                 std::process::exit(1);
+            };
+            if target == "-" {
+                scrape_batch(std::io::stdin().lock(), &options, latest, delay)?;
+                return Ok(());
+            }
+            // A bare SteamID64 has no year, so it always means the latest one
+            if latest || !target.contains('/') {
+                let steam_id = if target.contains('/') {
+                    extract_steam_id(target).context("No Steam ID in the replay URL")?
+                } else {
+                    target.as_str()
+                };
+                let year = latest_replay_year(steam_id)?;
+                scrape_replay(&format!("{}/{}/{}", REPLAY_URL_BASE, steam_id, year), &options)?;
+            } else {
+                scrape_replay(target, &options)?;
             }
-            scrape_replay(&args[2])?;
+        }
+        "check-new" => {
+            let mut rest = args[2..].to_vec();
+            let options = check_new::CheckOptions {
+                year: match take_option(&mut rest, "--year") {
+                    Some(year) => Some(year.parse().with_context(|| format!("Invalid year '{}'", year))?),
+                    None => None,
+                },
+                webhook: take_option(&mut rest, "--webhook"),
+                scrape: take_flag(&mut rest, "--scrape"),
+            };
+            let Some(target) = rest.first() else {
+                eprintln!("Error: Missing Steam ID argument");
+                eprintln!("Usage: {} check-new <steamid|replay_url> [--year YYYY] [--webhook URL] [--scrape]", args[0]);
+                std::process::exit(1);
+            };
+            let steam_id = if target.contains('/') {
+                extract_steam_id(target).context("No Steam ID in the replay URL")?
+            } else {
+                target.as_str()
+            };
+            check_new::check_new(steam_id, &options)?;
         }
         "map-games" => {
-            if args.len() < 3 {
+            let mut json_files: Vec<String> = args[2..].to_vec();
+            let format = take_option(&mut json_files, "--format").unwrap_or_else(|| "csv".to_string());
+            if !matches!(format.as_str(), "csv" | "json" | "toml") {
+                anyhow::bail!("Unknown mapping format '{}' (expected csv, json or toml)", format);
+            }
+            let fields: Vec<String> = take_option(&mut json_files, "--fields")
+                .map(|spec| spec.split(',').map(|field| field.trim().to_string()).collect())
+                .unwrap_or_default();
+            if let Some(field) = fields.iter().find(|field| !details::MAPPING_FIELDS.contains(&field.as_str())) {
+                anyhow::bail!("Unknown mapping field '{}' (available: {})", field, details::MAPPING_FIELDS.join(", "));
+            }
+            let subset = take_subset(&mut json_files)?;
+            if take_flag(&mut json_files, "--retry-failed") {
+                retry_failed_mappings(&format, &fields, subset)?;
+            } else if json_files.is_empty() {
                 eprintln!("Error: Missing JSON file argument(s)");
-                eprintln!("Usage: {} map-games <json_files...>", args[0]);
+                eprintln!("Usage: {} map-games <json_files...> [--format csv|json|toml] [--fields type,is_free,release_year,header_image] [--limit N|--sample N]", args[0]);
+                eprintln!("       {} map-games --retry-failed [--format csv|json|toml] [--fields ...] [--limit N|--sample N]", args[0]);
                 std::process::exit(1);
+            } else {
+                map_games_master(&json_files, &format, &fields, subset)?;
             }
-            let json_files: Vec<String> = args[2..].to_vec();
-            map_games_master(&json_files)?;
         }
         "to-csv" => {
-            if args.len() < 3 {
+            let mut json_files = args[2..].to_vec();
+            let split_per_file = take_flag(&mut json_files, "--split-per-file");
+            let no_combined = take_flag(&mut json_files, "--no-combined");
+            if no_combined && !split_per_file {
+                anyhow::bail!("--no-combined only applies with --split-per-file; without it the combined CSV is the only output");
+            }
+            let columns = match take_option(&mut json_files, "--columns") {
+                Some(spec) => csv_export::parse_columns(&spec)?,
+                None => csv_export::default_columns(),
+            };
+            let sort_by = take_option(&mut json_files, "--sort-by");
+            let options = csv_export::CsvOptions {
+                split_per_file,
+                combined: !no_combined,
+                columns,
+                mapping_path: take_option(&mut json_files, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                format: if take_flag(&mut json_files, "--excel") {
+                    csv_export::CsvFormat::excel(take_option(&mut json_files, "--excel-locale").as_deref())
+                } else {
+                    csv_export::CsvFormat::standard()
+                },
+                merge: take_flag(&mut json_files, "--merge"),
+                strict: summary::is_strict(),
+                group_by: take_grouping(&mut json_files)?,
+                name_template: take_option(&mut json_files, "--name-template")
+                    .unwrap_or_else(|| csv_export::NAME_TEMPLATE.to_string()),
+                split_name_template: take_option(&mut json_files, "--split-name-template")
+                    .unwrap_or_else(|| csv_export::SPLIT_NAME_TEMPLATE.to_string()),
+                months: take_option(&mut json_files, "--months")
+                    .map(|spec| dataset::parse_months(&spec))
+                    .transpose()?,
+                name_filter: take_name_filter(&mut json_files)?,
+                fold_demos: take_flag(&mut json_files, "--fold-demos"),
+                sort_by: sort_by
+                    .as_deref()
+                    .map(csv_export::SortKey::parse)
+                    .transpose()?
+                    .unwrap_or(csv_export::SortKey::Playtime),
+                // Ranked by hours, most played first, unless asked otherwise
+                descending: take_flag(&mut json_files, "--desc") || sort_by.is_none(),
+            };
+            if json_files.is_empty() {
+                eprintln!("Error: Missing JSON file argument(s)");
+                eprintln!("Usage: {} to-csv <json_files...> [--columns COLS] [--excel [--excel-locale LOCALE]] [--merge] [--strict] [--group-by quarter|season [--hemisphere north|south]] [--months 6..9] [--filter-name REGEX] [--exclude-name REGEX] [--fold-demos] [--sort-by playtime|name|month|app_id [--desc]] [--name-template TEMPLATE] [--split-per-file [--split-name-template TEMPLATE] [--no-combined]]", args[0]);
+                std::process::exit(1);
+            }
+            csv_export::convert_to_csv(&json_files, &options)?;
+        }
+        "import-local" => {
+            let mut rest = args[2..].to_vec();
+            let year = take_option(&mut rest, "--year");
+            let name_template = take_option(&mut rest, "--name-template");
+            let config_path = rest.first().map(|s| s.as_str());
+            local::import_local(config_path, year.as_deref(), name_template.as_deref())?;
+        }
+        "community-games" => {
+            let mut rest = args[2..].to_vec();
+            let name_template = take_option(&mut rest, "--name-template");
+            let Some(profile) = rest.first() else {
+                eprintln!("Error: Missing profile argument");
+                eprintln!("Usage: {} community-games <steamid|vanity|profile_url> [--name-template TEMPLATE]", args[0]);
+                std::process::exit(1);
+            };
+            community::scrape_community_games(profile, name_template.as_deref())?;
+        }
+        "achievements" => {
+            let mut json_files = args[2..].to_vec();
+            let api_key = auth::resolve_api_key(take_option(&mut json_files, "--api-key"))?;
+            let subset = take_subset(&mut json_files)?;
+            let Some(api_key) = api_key else {
+                eprintln!("Error: A Steam Web API key is required");
+                eprintln!("Run '{} auth set-key', set STEAM_API_KEY, or pass --api-key", args[0]);
+                std::process::exit(1);
+            };
+            if json_files.is_empty() {
                 eprintln!("Error: Missing JSON file argument(s)");
-                eprintln!("Usage: {} to-csv <json_files...>", args[0]);
+                eprintln!("Usage: {} achievements <json_files...> [--api-key KEY] [--limit N|--sample N]", args[0]);
+                std::process::exit(1);
+            }
+            achievements::fetch_achievements(&json_files, &api_key, subset)?;
+        }
+        "profile-badges" => {
+            let mut rest = args[2..].to_vec();
+            let year = take_option(&mut rest, "--year");
+            let Some(profile) = rest.first() else {
+                eprintln!("Error: Missing profile argument");
+                eprintln!("Usage: {} profile-badges <steamid|vanity|profile_url> [--year YYYY]", args[0]);
+                std::process::exit(1);
+            };
+            badges::scrape_profile_badges(profile, year.as_deref())?;
+        }
+        "reviews" => {
+            let Some(profile) = args.get(2) else {
+                eprintln!("Error: Missing profile argument");
+                eprintln!("Usage: {} reviews <steamid|vanity|profile_url>", args[0]);
+                std::process::exit(1);
+            };
+            reviews::scrape_reviews(profile)?;
+        }
+        "auth" => match args.get(2).map(|s| s.as_str()) {
+            Some("set-key") => auth::set_key(args.get(3).map(|s| s.as_str()))?,
+            Some("check") => auth::check()?,
+            Some("login") => auth::login()?,
+            Some("import-cookies") => {
+                let mut rest = args[3..].to_vec();
+                let profile = take_option(&mut rest, "--profile");
+                let Some(browser) = take_option(&mut rest, "--browser") else {
+                    eprintln!("Error: Missing --browser firefox|chrome");
+                    std::process::exit(1);
+                };
+                cookies::import_cookies(&browser, profile.as_deref())?;
+            }
+            _ => {
+                eprintln!("Usage: {} auth <set-key [KEY]|check|login|import-cookies>", args[0]);
                 std::process::exit(1);
             }
-            let json_files: Vec<String> = args[2..].to_vec();
-            convert_to_csv(&json_files)?;
+        },
+        "report" => {
+            let mut inputs = args[2..].to_vec();
+            let options = report::ReportOptions {
+                mapping_path: take_option(&mut inputs, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                achievements_path: take_option(&mut inputs, "--achievements")
+                    .unwrap_or_else(|| achievements::COMPLETION_FILENAME.to_string()),
+                profile_path: take_option(&mut inputs, "--profile"),
+                unlocks_path: take_option(&mut inputs, "--unlocks")
+                    .unwrap_or_else(|| achievements::UNLOCKS_FILENAME.to_string()),
+                reviews_path: take_option(&mut inputs, "--reviews")
+                    .unwrap_or_else(|| reviews::REVIEWS_FILENAME.to_string()),
+                top_n: take_option(&mut inputs, "--top")
+                    .map(|n| n.parse().context("--top must be a number"))
+                    .transpose()?
+                    .unwrap_or(10),
+                deltas: take_flag(&mut inputs, "--deltas"),
+                group_by: take_grouping(&mut inputs)?,
+                release_split: take_flag(&mut inputs, "--release-split"),
+                months: take_option(&mut inputs, "--months")
+                    .map(|spec| dataset::parse_months(&spec))
+                    .transpose()?,
+                name_filter: take_name_filter(&mut inputs)?,
+                fold_demos: take_flag(&mut inputs, "--fold-demos"),
+                forecast: take_flag(&mut inputs, "--forecast"),
+                milestones: take_flag(&mut inputs, "--milestones"),
+                narrative: take_flag(&mut inputs, "--narrative"),
+            };
+            let format = take_option(&mut inputs, "--format").unwrap_or_else(|| "text".to_string());
+            let output = take_option(&mut inputs, "--output");
+            let font = take_option(&mut inputs, "--font");
+            let mut html_path = take_option(&mut inputs, "--html");
+            let mut pdf_path = None;
+            match format.as_str() {
+                "text" => {}
+                "html" => html_path = html_path.or(output).or_else(|| Some("steam_replay_report.html".to_string())),
+                "pdf" => pdf_path = Some(output.unwrap_or_else(|| pdf_report::DEFAULT_FILENAME.to_string())),
+                other => anyhow::bail!("Unknown report format '{}' (expected text, html or pdf)", other),
+            }
+            let serve = take_flag(&mut inputs, "--serve");
+            let port = take_option(&mut inputs, "--port")
+                .map(|n| n.parse().context("--port must be a port number"))
+                .transpose()?
+                .unwrap_or(serve::DEFAULT_PORT);
+            let open = !take_flag(&mut inputs, "--no-open");
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            if serve {
+                serve::serve_report(&inputs, &options, port, open)?;
+            } else if let Some(path) = pdf_path {
+                pdf_report::write_pdf(&inputs, &options, &path, font.as_deref())?;
+            } else if let Some(path) = html_path {
+                html_report::write_html(&inputs, &options, &path)?;
+            } else {
+                report::print_report(&inputs, &options)?;
+            }
+        }
+        "compare-global" => {
+            let mut inputs = args[2..].to_vec();
+            let baseline_path = take_option(&mut inputs, "--baseline")
+                .unwrap_or_else(|| compare::BASELINE_FILENAME.to_string());
+            let mapping_path = take_option(&mut inputs, "--mapping")
+                .unwrap_or_else(|| "game_mapping_master.csv".to_string());
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            compare::compare_global(&inputs, &baseline_path, &mapping_path)?;
+        }
+        "details" => {
+            let mut app_ids = args[2..].to_vec();
+            let refresh = take_flag(&mut app_ids, "--refresh");
+            let subset = take_subset(&mut app_ids)?;
+            if let Some(path) = take_option(&mut app_ids, "--from") {
+                app_ids.extend(details::read_app_ids(&path)?);
+            }
+            if app_ids.is_empty() {
+                eprintln!("Usage: {} details <appid...> [--from FILE] [--refresh] [--limit N|--sample N]", args[0]);
+                std::process::exit(1);
+            }
+            if let Some(subset) = subset {
+                app_ids = subset.apply(app_ids);
+            }
+            details::dump_details(&app_ids, refresh)?;
+        }
+        "audit-mapping" => {
+            let mut inputs = args[2..].to_vec();
+            let options = audit::AuditOptions {
+                mapping_path: take_option(&mut inputs, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                output: take_option(&mut inputs, "--output")
+                    .unwrap_or_else(|| audit::UNMAPPED_FILENAME.to_string()),
+            };
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            audit::audit_mapping(&inputs, &options)?;
+        }
+        "verify-mapping" => {
+            let mut rest = args[2..].to_vec();
+            let options = verify::VerifyOptions {
+                mapping_path: take_option(&mut rest, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                output: take_option(&mut rest, "--output")
+                    .unwrap_or_else(|| verify::MISMATCHES_FILENAME.to_string()),
+                subset: take_subset(&mut rest)?,
+                apply: take_flag(&mut rest, "--apply"),
+            };
+            if let Some(arg) = rest.first() {
+                eprintln!("Error: Unexpected argument '{}'", arg);
+                eprintln!("Usage: {} verify-mapping [--mapping FILE] [--limit N|--sample N] [--output FILE] [--apply]", args[0]);
+                std::process::exit(1);
+            }
+            verify::verify_mapping(&options)?;
+        }
+        "export" => {
+            let mut inputs = args[2..].to_vec();
+            let Some(format) = take_option(&mut inputs, "--format") else {
+                eprintln!("Usage: {} export --format steamdb|backloggd|hltb|playnite|arrow [csv_or_json_files...] [--output FILE] [--year YYYY] [--mapping FILE] [--split-by genre|category|developer|publisher]", args[0]);
+                std::process::exit(1);
+            };
+            let options = export::ExportOptions {
+                format,
+                output: take_option(&mut inputs, "--output"),
+                mapping_path: take_option(&mut inputs, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                year: take_option(&mut inputs, "--year"),
+                split_by: take_option(&mut inputs, "--split-by")
+                    .map(|name| export::SplitBy::parse(&name))
+                    .transpose()?,
+            };
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            export::export(&inputs, &options)?;
+        }
+        "import" => {
+            let mut rest = args[2..].to_vec();
+            let usage = || {
+                eprintln!("Usage: {} import --format playnite|csv <file> [--year YYYY] [--source NAME] [--mapping FILE]", args[0]);
+                std::process::exit(1);
+            };
+            let Some(format) = take_option(&mut rest, "--format") else { usage() };
+            let options = external::ImportOptions {
+                format,
+                mapping_path: take_option(&mut rest, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                year: take_option(&mut rest, "--year"),
+                source: take_option(&mut rest, "--source"),
+            };
+            let Some(path) = rest.first() else { usage() };
+            external::import_external(path, &options)?;
+        }
+        "game" => {
+            let mut inputs = args[2..].to_vec();
+            let mapping_path = take_option(&mut inputs, "--mapping")
+                .unwrap_or_else(|| "game_mapping_master.csv".to_string());
+            if inputs.is_empty() {
+                eprintln!("Usage: {} game <appid|name> [csv_or_json_files...] [--mapping FILE]", args[0]);
+                std::process::exit(1);
+            }
+            let query = inputs.remove(0);
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            game::print_game(&query, &inputs, &mapping_path)?;
+        }
+        "heatmap" => {
+            let mut inputs = args[2..].to_vec();
+            let svg_path = take_option(&mut inputs, "--svg");
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            heatmap::print_heatmap(&inputs, svg_path.as_deref())?;
+        }
+        "merge" => {
+            let mut inputs = args[2..].to_vec();
+            let output = take_option(&mut inputs, "--output")
+                .unwrap_or_else(|| "master.json".to_string());
+            if inputs.is_empty() {
+                eprintln!("Usage: {} merge <json_files...> [--output master.json]", args[0]);
+                std::process::exit(1);
+            }
+            master::merge(&inputs, &output)?;
+        }
+        "graphql" => {
+            let mut inputs = args[2..].to_vec();
+            let options = graphql::GraphqlOptions {
+                mapping_path: take_option(&mut inputs, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                achievements_path: take_option(&mut inputs, "--achievements")
+                    .unwrap_or_else(|| achievements::COMPLETION_FILENAME.to_string()),
+                port: match take_option(&mut inputs, "--port") {
+                    Some(port) => port.parse().with_context(|| format!("Invalid port '{}'", port))?,
+                    None => graphql::DEFAULT_PORT,
+                },
+                allow_origin: take_option(&mut inputs, "--allow-origin"),
+            };
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            graphql::serve_graphql(&inputs, &options)?;
+        }
+        "goals" => {
+            let mut inputs = args[2..].to_vec();
+            let options = goals::GoalsOptions {
+                mapping_path: take_option(&mut inputs, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                year: take_option(&mut inputs, "--year"),
+            };
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            goals::print_goals(&inputs, &options)?;
+        }
+        "badge" => {
+            let mut inputs = args[2..].to_vec();
+            let options = shields::BadgeOptions {
+                mapping_path: take_option(&mut inputs, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                output_dir: take_option(&mut inputs, "--output-dir")
+                    .unwrap_or_else(|| shields::DEFAULT_OUTPUT_DIR.to_string()),
+                year: take_option(&mut inputs, "--year"),
+            };
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            shields::write_badges(&inputs, &options)?;
+        }
+        "push-hass" => {
+            let mut inputs = args[2..].to_vec();
+            let options = hass::PushOptions {
+                mapping_path: take_option(&mut inputs, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                url: take_option(&mut inputs, "--url"),
+                token: take_option(&mut inputs, "--token"),
+                dry_run: take_flag(&mut inputs, "--dry-run"),
+            };
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            hass::push_hass(&inputs, &options)?;
+        }
+        "split-per-game" => {
+            let mut inputs = args[2..].to_vec();
+            let options = per_game::SplitOptions {
+                output_dir: take_option(&mut inputs, "--output-dir")
+                    .unwrap_or_else(|| "games".to_string()),
+                mapping_path: take_option(&mut inputs, "--mapping")
+                    .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                with_artwork: take_flag(&mut inputs, "--with-artwork"),
+            };
+            if inputs.is_empty() {
+                inputs.push("steam_replay_data.csv".to_string());
+            }
+            per_game::split_per_game(&inputs, &options)?;
+        }
+        "schema" => {
+            let mut rest = args[2..].to_vec();
+            let output = take_option(&mut rest, "--output");
+            let Some(kind) = rest.first() else {
+                eprintln!("Usage: {} schema replay|dataset|master [--output FILE|-]", args[0]);
+                std::process::exit(1);
+            };
+            schema::write_schema(kind, output.as_deref())?;
+        }
+        "family" => {
+            let mut rest = args[2..].to_vec();
+            let usage = || {
+                eprintln!("Usage: {} family scrape --year YYYY [--name-template TEMPLATE]", args[0]);
+                eprintln!("       {} family report <json_files...> [--year YYYY] [--top N] [--mapping FILE] [--output FILE] [--api-key KEY]", args[0]);
+                std::process::exit(1);
+            };
+            match rest.first().map(|s| s.as_str()) {
+                Some("scrape") => {
+                    let name_template = take_option(&mut rest, "--name-template");
+                    let Some(year) = take_option(&mut rest, "--year") else { usage() };
+                    family::scrape_family(&year, name_template.as_deref())?;
+                }
+                Some("report") => {
+                    let options = family::FamilyReportOptions {
+                        mapping_path: take_option(&mut rest, "--mapping")
+                            .unwrap_or_else(|| "game_mapping_master.csv".to_string()),
+                        year: take_option(&mut rest, "--year"),
+                        top_n: take_option(&mut rest, "--top")
+                            .map(|n| n.parse().context("--top must be a number"))
+                            .transpose()?
+                            .unwrap_or(10),
+                        output: take_option(&mut rest, "--output"),
+                        api_key: auth::resolve_api_key(take_option(&mut rest, "--api-key"))?,
+                    };
+                    let inputs = &rest[1..];
+                    if inputs.is_empty() {
+                        usage();
+                    }
+                    family::print_family_report(inputs, &options)?;
+                }
+                _ => usage(),
+            }
+        }
+        "history" => {
+            let mut rest = args[2..].to_vec();
+            let options = history::HistoryOptions {
+                limit: take_option(&mut rest, "--limit")
+                    .map(|n| n.parse().context("--limit must be a number"))
+                    .transpose()?
+                    .unwrap_or(20),
+                command: take_option(&mut rest, "--command"),
+                failed: take_flag(&mut rest, "--failed"),
+                details: take_flag(&mut rest, "--details"),
+            };
+            history::print_history(&options)?;
+        }
+        "doctor" => doctor::run_doctor(args.get(2).map(|s| s.as_str()))?,
+        "clean" => {
+            let mut rest = args[2..].to_vec();
+            let options = clean::CleanOptions {
+                cache: take_flag(&mut rest, "--cache"),
+                art: take_flag(&mut rest, "--art"),
+                all: take_flag(&mut rest, "--all"),
+            };
+            clean::clean(&options)?;
+        }
+        "self-update" => {
+            let mut rest = args[2..].to_vec();
+            let options = self_update::UpdateOptions {
+                check: take_flag(&mut rest, "--check"),
+                version: take_option(&mut rest, "--version"),
+                force: take_flag(&mut rest, "--force"),
+            };
+            self_update::self_update(&options)?;
         }
         url if url.starts_with("http") => {
             // Backwards compatibility - treat first arg as URL
-            scrape_replay(url)?;
+            scrape_replay(url, &ScrapeOptions::default())?;
         }
         _ => {
             eprintln!("Error: Unknown command '{}'", command);
-            eprintln!("Valid commands: scrape, map-games, to-csv");
+            eprintln!("Valid commands: scrape, check-new, map-games, to-csv, import-local, community-games, achievements, auth, profile-badges, reviews, report, compare-global, details, export, import, game, heatmap, merge, split-per-game, schema, doctor, clean, self-update, history, family, audit-mapping, verify-mapping, graphql, goals, badge, push-hass");
             std::process::exit(1);
         }
     }
@@ -65,21 +713,238 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn scrape_replay(url: &str) -> Result<()> {
-    println!("Fetching Steam Replay from: {}", url);
+/// Removes `<name> <value>` from the argument list and returns the value.
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == name)?;
+    if index + 1 >= args.len() {
+        eprintln!("Error: {} requires a value", name);
+        std::process::exit(1);
+    }
+    args.remove(index);
+    Some(args.remove(index))
+}
 
-    // Fetch the page
-    let response = reqwest::blocking::get(url)
-        .context("Failed to fetch the Steam Replay page")?;
+/// Removes `--group-by quarter|season [--hemisphere north|south]`.
+fn take_grouping(args: &mut Vec<String>) -> Result<Option<dataset::Grouping>> {
+    let hemisphere = take_option(args, "--hemisphere");
+    take_option(args, "--group-by")
+        .map(|group_by| dataset::Grouping::parse(&group_by, hemisphere.as_deref()))
+        .transpose()
+}
 
-    let html_content = response.text()
-        .context("Failed to read response body")?;
+/// Removes `--limit N` and `--sample N`.
+fn take_subset(args: &mut Vec<String>) -> Result<Option<trial::Subset>> {
+    let limit = take_option(args, "--limit");
+    let sample = take_option(args, "--sample");
+    trial::Subset::parse(limit.as_deref(), sample.as_deref())
+}
+
+/// Removes `--filter-name REGEX` and `--exclude-name REGEX`.
+fn take_name_filter(args: &mut Vec<String>) -> Result<Option<dataset::NameFilter>> {
+    let include = take_option(args, "--filter-name");
+    let exclude = take_option(args, "--exclude-name");
+    dataset::NameFilter::parse(include.as_deref(), exclude.as_deref())
+}
+
+/// Removes a boolean `<name>` flag from the argument list, reporting whether it was present.
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+#[derive(Default)]
+struct ScrapeOptions {
+    with_share_image: bool,
+    /// Fall back to a Wayback Machine snapshot when the live page is gone
+    allow_archive: bool,
+    /// Also save the page source, gzipped, next to the JSON
+    keep_html: bool,
+    /// Extract from a saved page instead of fetching the URL
+    from_html: Option<String>,
+    /// Output filename; see `template` for the placeholders
+    name_template: Option<String>,
+    /// Expect the page's current structure from now on (see `drift`)
+    accept_schema: bool,
+}
+
+const REPLAY_NAME_TEMPLATE: &str = "steam_replay_{steamid}_{year}.json";
+
+const REPLAY_URL_BASE: &str = "https://store.steampowered.com/replay";
+
+/// Steam Replay launched with 2022
+const FIRST_REPLAY_YEAR: i32 = 2022;
+
+/// The newest year with a replay page for the account, probing back from
+/// the current year.
+fn latest_replay_year(steam_id: &str) -> Result<i32> {
+    if !steam_id.chars().all(|c| c.is_ascii_digit()) {
+        anyhow::bail!("'{}' is not a SteamID64; pass the numeric ID or a replay URL", steam_id);
+    }
+
+    println!("Looking for the latest replay of {}...", steam_id);
+    let cookie = auth::resolve_login_cookie()?;
+
+    for year in (FIRST_REPLAY_YEAR..=chrono::Utc::now().year()).rev() {
+        if replay_published(steam_id, year, cookie.as_deref())? {
+            println!("  {}: available", year);
+            return Ok(year);
+        }
+        println!("  {}: not available", year);
+    }
+
+    anyhow::bail!(
+        "No replay found for {} since {}; the profile may be private (run 'auth login')",
+        steam_id,
+        FIRST_REPLAY_YEAR
+    )
+}
+
+/// Whether the account's replay page for `year` has the year in review
+/// data. A year's page exists before it's published, just without the data.
+fn replay_published(steam_id: &str, year: i32, cookie: Option<&str>) -> Result<bool> {
+    let url = format!("{}/{}/{}", REPLAY_URL_BASE, steam_id, year);
+    let mut request = http::client().get(&url);
+    if let Some(cookie) = cookie {
+        request = request.header(reqwest::header::COOKIE, format!("steamLoginSecure={}", cookie));
+    }
+    let html = http::send(request)
+        .and_then(|response| Ok(response.error_for_status()?.text()?))
+        .with_context(|| format!("Failed to fetch {}", url))?;
+
+    let document = Html::parse_document(&html);
+    let selector = Selector::parse("#application_config").expect("Failed to create selector");
+    Ok(document
+        .select(&selector)
+        .next()
+        .and_then(|element| element.value().attr("data-yearinreview"))
+        .and_then(|payload| serde_json::from_str::<Value>(payload).ok())
+        .is_some_and(|payload| payload.get("playtime_stats").is_some()))
+}
+
+/// Pause between targets of `scrape -`, on top of the per-host pacing
+const SCRAPE_BATCH_DELAY_SECS: u64 = 3;
+
+/// `scrape -`: one target per line of `input`, as a replay URL, a
+/// `steamid,year` pair or a bare SteamID64 (its latest replay). Blank lines
+/// and `#` comments are skipped. Targets that fail are reported and the rest
+/// carry on, so one private profile doesn't stop a survey's batch.
+fn scrape_batch(input: impl std::io::BufRead, options: &ScrapeOptions, latest: bool, delay: u64) -> Result<()> {
+    if options.from_html.is_some() {
+        anyhow::bail!("--from-html reads a single saved page; it can't be combined with 'scrape -'");
+    }
+    if let Some(name_template) = &options.name_template
+        && !name_template.contains("{steamid}")
+    {
+        anyhow::bail!("--name-template needs {{steamid}} when scraping several targets, or their outputs would overwrite each other");
+    }
+
+    let mut targets = Vec::new();
+    for line in input.lines() {
+        let line = line.context("Failed to read targets from stdin")?;
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            targets.push(line.to_string());
+        }
+    }
+    if targets.is_empty() {
+        anyhow::bail!("No targets on stdin; give one replay URL, steamid,year pair or SteamID64 per line");
+    }
+
+    let mut failed = 0;
+    progress::phase("scrape", targets.len());
+    for (index, target) in targets.iter().enumerate() {
+        progress::item(target, index);
+        if index > 0 {
+            std::thread::sleep(std::time::Duration::from_secs(delay));
+        }
+        println!("[{}/{}] {}", index + 1, targets.len(), target);
+
+        let result = match target.split_once([',', ' ', '\t']) {
+            Some((steam_id, year)) => {
+                scrape_replay(&format!("{}/{}/{}", REPLAY_URL_BASE, steam_id.trim(), year.trim()), options)
+            }
+            None if target.contains('/') && !latest => scrape_replay(target, options),
+            None => {
+                let steam_id = if target.contains('/') { extract_steam_id(target) } else { Some(target.as_str()) };
+                steam_id
+                    .context("No Steam ID in the replay URL")
+                    .and_then(latest_replay_year)
+                    .and_then(|year| {
+                        let steam_id = steam_id.unwrap_or_default();
+                        scrape_replay(&format!("{}/{}/{}", REPLAY_URL_BASE, steam_id, year), options)
+                    })
+            }
+        };
+        if let Err(e) = result {
+            println!("  Error: {:#}", e);
+            summary::failure(target.as_str(), format!("{:#}", e))?;
+            failed += 1;
+        }
+        println!();
+    }
+
+    if failed == targets.len() {
+        anyhow::bail!("None of the {} target(s) could be scraped", targets.len());
+    }
+    summary::count("replays_scraped", (targets.len() - failed) as u64);
+    println!("Scraped {} of {} target(s)", targets.len() - failed, targets.len());
+
+    Ok(())
+}
+
+fn scrape_replay(url: &str, options: &ScrapeOptions) -> Result<()> {
+    let (status, mut html_content) = match &options.from_html {
+        Some(path) => {
+            println!("Reading saved page for {} from: {}", url, path);
+            (reqwest::StatusCode::OK, saved_page::read(path)?)
+        }
+        None => {
+            println!("Fetching Steam Replay from: {}", url);
+
+            // Fetch the page, logged in when a session is available (needed for private profiles)
+            let mut request = http::client().get(url);
+            if url.contains("store.steampowered.com")
+                && let Some(cookie) = auth::resolve_login_cookie()?
+            {
+                request = request.header(reqwest::header::COOKIE, format!("steamLoginSecure={}", cookie));
+            }
+
+            let response = http::send(request)
+                .context("Failed to fetch the Steam Replay page")?;
+            let status = response.status();
+
+            (status, response.text().context("Failed to read response body")?)
+        }
+    };
 
-    // Parse the HTML
-    let document = Html::parse_document(&html_content);
     let selector = Selector::parse("#application_config")
         .expect("Failed to create selector");
 
+    // Removed pages 404 or come back without the replay data
+    let mut archived_from = None;
+    if options.allow_archive
+        && (!status.is_success() || Html::parse_document(&html_content).select(&selector).next().is_none())
+    {
+        println!("The live page is unavailable ({}); looking for an archived copy...", status);
+        let Some(snapshot) = archive::find_snapshot(url)? else {
+            anyhow::bail!("The Wayback Machine has no snapshot of {}", url);
+        };
+        println!(
+            "Using the Wayback Machine snapshot from {}",
+            archive::snapshot_date(&snapshot).unwrap_or_else(|| snapshot.clone())
+        );
+        html_content = archive::fetch_snapshot(&snapshot)?;
+        archived_from = Some(snapshot);
+    }
+
+    // Parse the HTML
+    let document = Html::parse_document(&html_content);
+
     // Find the application_config div
     if let Some(element) = document.select(&selector).next() {
         println!("Found application_config div!");
@@ -105,16 +970,32 @@ fn scrape_replay(url: &str) -> Result<()> {
         }
 
         // Create output JSON
-        let output = json!({
+        let mut output = json!({
             "url": url,
             "timestamp": chrono::Utc::now().to_rfc3339(),
             "data": data_attributes
         });
+        if let Some(snapshot) = &archived_from {
+            output["archived_from"] = json!(snapshot);
+        }
+        output["provenance"] = provenance::for_page(url, &html_content, archived_from.as_deref());
 
         // Generate output filename
         let steam_id = extract_steam_id(url).unwrap_or("unknown");
         let year = extract_year(url).unwrap_or("unknown");
-        let output_filename = format!("steam_replay_{}_{}.json", steam_id, year);
+        let output_filename = template::render(
+            options.name_template.as_deref().unwrap_or(REPLAY_NAME_TEMPLATE),
+            &[("steamid", steam_id), ("year", year)],
+        )?;
+        template::create_parent_dir(std::path::Path::new(&output_filename))?;
+
+        // Valve changing the page shouldn't slip into the archive unnoticed
+        let schema_check = drift::check(&data_attributes, year)?;
+        let schema_verified = schema_check.verified() || options.accept_schema;
+        output["provenance"]["schema_fingerprint"] = json!(schema_check.fingerprint);
+        output["provenance"]["schema_status"] = json!(if schema_verified { "verified" } else { "unverified" });
+        // The share image is saved next to it, with the image's extension
+        let output_stem = std::path::Path::new(&output_filename).with_extension("").display().to_string();
 
         // Write to file
         let output_json = serde_json::to_string_pretty(&output)
@@ -123,138 +1004,303 @@ fn scrape_replay(url: &str) -> Result<()> {
         fs::write(&output_filename, output_json)
             .context("Failed to write output file")?;
 
+        summary::file_written(&output_filename);
         println!("\nData saved to: {}", output_filename);
         println!("Found {} data attributes", data_attributes.len());
+
+        if schema_verified {
+            drift::accept(&schema_check, year)?;
+            if !schema_check.verified() {
+                println!("Recorded the page's structure (schema {}) as expected for {}", schema_check.fingerprint, year);
+            }
+        } else {
+            drift::print_changes(&schema_check);
+            summary::failure(output_filename.as_str(), "unverified page schema")?;
+        }
+
+        if options.keep_html {
+            let html_filename = format!("{}.html.gz", output_stem);
+            saved_page::write(&html_filename, &html_content)?;
+            summary::file_written(&html_filename);
+            println!("Page source saved to: {}", html_filename);
+        }
+
+        if options.with_share_image {
+            match share_image::download_share_image(&document, &output_stem)? {
+                Some(filename) => {
+                    summary::file_written(&filename);
+                    println!("Share image saved to: {}", filename);
+                }
+                None => println!("Warning: No share image found on the page"),
+            }
+        }
     } else {
-        eprintln!("Error: Could not find div with id 'application_config'");
-        std::process::exit(1);
+        anyhow::bail!("Could not find div with id 'application_config'");
     }
 
     Ok(())
 }
 
-fn map_games_master(json_files: &[String]) -> Result<()> {
+/// Writes game_mapping_master.csv, which the other commands read, plus a
+/// JSON or TOML copy keyed by app ID when `format` asks for one. `fields`
+/// are extra appdetails columns after the name. With a `subset` (a trial
+/// run) only those games are looked up.
+fn map_games_master(
+    json_files: &[String],
+    format: &str,
+    fields: &[String],
+    subset: Option<trial::Subset>,
+) -> Result<()> {
     println!("Processing {} JSON file(s)...", json_files.len());
 
     // Collect all unique app IDs from all files
     let mut all_app_ids = HashSet::new();
+    // (file, error) for inputs that couldn't be read
+    let mut failures: Vec<(String, String)> = Vec::new();
 
     for json_file in json_files {
         println!("Reading: {}", json_file);
 
-        let file_content = fs::read_to_string(json_file)
-            .with_context(|| format!("Failed to read {}", json_file))?;
-
-        let data: Value = serde_json::from_str(&file_content)
-            .with_context(|| format!("Failed to parse {}", json_file))?;
+        let data = match read_json(json_file) {
+            Ok(data) => data,
+            Err(e) => {
+                println!("  Error: {:#}", e);
+                summary::failure(json_file.as_str(), format!("{:#}", e))?;
+                failures.push((json_file.clone(), format!("{:#}", e)));
+                continue;
+            }
+        };
 
         let app_ids = extract_app_ids(&data);
         println!("  Found {} app IDs", app_ids.len());
         all_app_ids.extend(app_ids);
     }
 
+    summary::write_failure_report(&failures, json_files.len())?;
+    if !json_files.is_empty() && failures.len() == json_files.len() {
+        anyhow::bail!("None of the {} input file(s) could be read", json_files.len());
+    }
+
+    let app_filter = config::app_filter()?;
+    let found = all_app_ids.len();
+    all_app_ids.retain(|app_id| app_filter.allows(app_id));
+    if all_app_ids.len() < found {
+        println!("Skipping {} app ID(s) excluded in the config", found - all_app_ids.len());
+    }
+
     println!("\nTotal unique app IDs across all files: {}", all_app_ids.len());
 
-    // Fetch game names from Steam API
-    let mut game_mapping: HashMap<String, String> = HashMap::new();
-    let total = all_app_ids.len();
+    let mut app_ids: Vec<String> = all_app_ids.into_iter().collect();
+    app_ids.sort_by_key(|app_id| app_id.parse::<u64>().unwrap_or(u64::MAX));
+    if let Some(subset) = subset {
+        app_ids = subset.apply(app_ids);
+    }
 
-    for (index, app_id) in all_app_ids.iter().enumerate() {
+    // Fetch game names from Steam API; names of games imported from other
+    // launchers ("<source>:<id>" keys) are carried over from the old file.
+    // A trial run adds to the existing mapping instead of replacing it.
+    let mapping_filename = "game_mapping_master.csv";
+    let (mut game_mapping, mut mapping_fields) = if subset.is_some() {
+        let (mapping, mut mapping_fields) = dataset::load_mapping_with_fields(mapping_filename)?;
+        for field in fields {
+            if !mapping_fields.names.contains(field) {
+                mapping_fields.names.push(field.clone());
+            }
+        }
+        (mapping, mapping_fields)
+    } else {
+        // Without the overrides, which stay in their own file
+        let mapping: HashMap<String, String> = dataset::load_mapping_with_fields(mapping_filename)?
+            .0
+            .into_iter()
+            .filter(|(key, _)| key.contains(':'))
+            .collect();
+        (mapping, dataset::MappingFields { names: fields.to_vec(), ..Default::default() })
+    };
+    let total = app_ids.len();
+
+    // A trial run keeps the earlier failures of apps it didn't look up
+    let mut lookup_failures = Vec::new();
+    if subset.is_some() {
+        lookup_failures = mapping_failures::load()?;
+        lookup_failures.retain(|failure| !app_ids.contains(&failure.app_id));
+    }
+    let mut mapped = 0;
+    let mut unresolved = 0;
+
+    // Offline, apps missing from the appdetails cache keep their entry in
+    // the existing mapping rather than failing
+    let (previous_mapping, previous_fields) = if http::offline() {
+        dataset::load_mapping_with_fields(mapping_filename)?
+    } else {
+        Default::default()
+    };
+
+    progress::phase("map_games", total);
+    for (index, app_id) in app_ids.iter().enumerate() {
+        progress::item(app_id, index);
         println!("[{}/{}] Fetching info for app ID: {}", index + 1, total, app_id);
 
-        match fetch_game_name(app_id) {
-            Ok(Some(name)) => {
-                game_mapping.insert(app_id.clone(), name);
-            }
-            Ok(None) => {
-                println!("  Warning: No data available for app ID {}", app_id);
-            }
-            Err(e) => {
-                println!("  Error fetching app ID {}: {}", app_id, e);
+        if http::offline()
+            && details::cached(app_id)?.is_none()
+            && let Some(name) = previous_mapping.get(app_id)
+        {
+            println!("  Not cached, keeping the name from {}", mapping_filename);
+            game_mapping.insert(app_id.clone(), name.clone());
+            if let Some(values) = previous_fields.values.get(app_id) {
+                mapping_fields.values.insert(app_id.clone(), values.clone());
             }
+            mapped += 1;
+            continue;
         }
 
-        // Rate limiting - Steam API recommends spacing requests
-        if index < total - 1 {
-            thread::sleep(Duration::from_millis(1500));
+        match map_game(app_id, false, None, &mut game_mapping, &mut mapping_fields)? {
+            Some(failure) => {
+                lookup_failures.push(failure);
+                unresolved += 1;
+            }
+            None => mapped += 1,
         }
     }
 
-    // Write master mapping as CSV
-    let mapping_filename = "game_mapping_master.csv";
-    let mut csv_content = String::from("app_id,game\n");
-
-    let mut sorted_ids: Vec<_> = game_mapping.iter().collect();
-    sorted_ids.sort_by_key(|&(id, _)| id);
-
-    for (app_id, game_name) in sorted_ids {
-        // Escape commas and quotes in game names
-        let escaped_name = if game_name.contains(',') || game_name.contains('"') {
-            format!("\"{}\"", game_name.replace('"', "\"\""))
-        } else {
-            game_name.clone()
-        };
-        csv_content.push_str(&format!("{},{}\n", app_id, escaped_name));
+    mapping_failures::save(&lookup_failures)?;
+    write_game_mapping(&game_mapping, &mapping_fields, format)?;
+    summary::count("games_mapped", mapped as u64);
+    println!("Successfully mapped {} games", mapped);
+    if http::offline() && unresolved > 0 {
+        println!(
+            "{} app ID(s) weren't cached and stay unmapped; run 'map-games --retry-failed' once online",
+            unresolved
+        );
     }
 
-    fs::write(mapping_filename, csv_content)
-        .context("Failed to write mapping file")?;
-
-    println!("\nMaster game mapping saved to: {}", mapping_filename);
-    println!("Successfully mapped {} games", game_mapping.len());
-
     Ok(())
 }
 
-fn convert_to_csv(json_files: &[String]) -> Result<()> {
-    println!("Converting {} JSON file(s) to CSV...", json_files.len());
-
-    let mut csv_rows: Vec<(String, u64, String, String)> = Vec::new(); // (app_id, playtime_seconds, year, section)
+/// `map-games --retry-failed`: looks up the apps in failures.json again and
+/// adds the ones that now resolve to the existing mapping. Apps the store
+/// had no data for wait `NOT_FOUND_RECHECK_DAYS`; rate-limited ones are
+/// spaced out further each time they hit the limit again.
+fn retry_failed_mappings(format: &str, fields: &[String], subset: Option<trial::Subset>) -> Result<()> {
+    let previous = mapping_failures::load()?;
+    if previous.is_empty() {
+        println!("No failed lookups to retry ({} not found)", mapping_failures::path()?.display());
+        return Ok(());
+    }
 
-    for json_file in json_files {
-        println!("Processing: {}", json_file);
+    let mapping_filename = "game_mapping_master.csv";
+    let (mut game_mapping, mut mapping_fields) = dataset::load_mapping_with_fields(mapping_filename)?;
+    for field in fields {
+        if !mapping_fields.names.contains(field) {
+            mapping_fields.names.push(field.clone());
+        }
+    }
 
-        let file_content = fs::read_to_string(json_file)
-            .with_context(|| format!("Failed to read {}", json_file))?;
+    let (mut due, mut waiting): (Vec<_>, Vec<_>) = previous.into_iter().partition(|failure| failure.due());
+    if !waiting.is_empty() {
+        println!("Skipping {} app ID(s) the store had no data for recently", waiting.len());
+    }
+    if let Some(subset) = subset {
+        let app_ids = subset.apply(due.iter().map(|failure| failure.app_id.clone()).collect());
+        let untried;
+        (due, untried) = due.into_iter().partition(|failure| app_ids.contains(&failure.app_id));
+        waiting.extend(untried);
+    }
+    println!("Retrying {} failed lookup(s)...", due.len());
+
+    let mut remaining = waiting;
+    let mut mapped = 0;
+
+    progress::phase("retry_failed", due.len());
+    for (index, failure) in due.iter().enumerate() {
+        progress::item(&failure.app_id, index);
+        let delay = failure.delay();
+        if !delay.is_zero() {
+            println!("  Rate limited before, waiting {}s", delay.as_secs());
+            std::thread::sleep(delay);
+        }
+        println!("[{}/{}] Fetching info for app ID: {}", index + 1, due.len(), failure.app_id);
 
-        let data: Value = serde_json::from_str(&file_content)
-            .with_context(|| format!("Failed to parse {}", json_file))?;
+        // The store's "no data" answer is cached; ask again
+        let refresh = failure.error_class == mapping_failures::ErrorClass::NotFound;
+        match map_game(&failure.app_id, refresh, Some(failure), &mut game_mapping, &mut mapping_fields)? {
+            Some(failure) => remaining.push(failure),
+            None => mapped += 1,
+        }
+    }
 
-        // Extract year from filename or data
-        let year = extract_year_from_data(&data, json_file);
-        println!("  Year: {}", year);
+    mapping_failures::save(&remaining)?;
+    if mapped > 0 {
+        write_game_mapping(&game_mapping, &mapping_fields, format)?;
+    }
+    summary::count("games_mapped", mapped as u64);
+    println!("Mapped {} of {} retried games", mapped, due.len());
 
-        // Extract playtime data with section tracking
-        let playtime_data = extract_playtime_data(&data);
-        println!("  Found {} playtime entries", playtime_data.len());
+    Ok(())
+}
 
-        for (app_id, playtime_seconds, section) in playtime_data {
-            csv_rows.push((app_id, playtime_seconds, year.clone(), section));
+/// Adds one game to the mapping, or returns the failure to record when the
+/// lookup fails. `previous` is the app's failure from an earlier run.
+fn map_game(
+    app_id: &str,
+    refresh: bool,
+    previous: Option<&mapping_failures::MappingFailure>,
+    game_mapping: &mut HashMap<String, String>,
+    mapping_fields: &mut dataset::MappingFields,
+) -> Result<Option<mapping_failures::MappingFailure>> {
+    let (error_class, error) = match fetch_game_details(app_id, refresh) {
+        Ok(Some((name, entry))) => {
+            game_mapping.insert(app_id.to_string(), name);
+            let values = mapping_fields
+                .names
+                .iter()
+                .map(|field| (field.clone(), details::mapping_field(&entry, field)))
+                .collect();
+            mapping_fields.values.insert(app_id.to_string(), values);
+            return Ok(None);
         }
-    }
+        Ok(None) => {
+            println!("  Warning: No data available for app ID {}", app_id);
+            (mapping_failures::ErrorClass::NotFound, "No data available".to_string())
+        }
+        Err(e) => {
+            println!("  Error fetching app ID {}: {}", app_id, e);
+            (mapping_failures::classify(&e), format!("{:#}", e))
+        }
+    };
 
-    // Write CSV
-    let csv_filename = "steam_replay_data.csv";
-    let mut csv_content = String::from("app_id,playtime_in_seconds,year,month\n");
+    summary::failure(app_id, &error)?;
 
-    csv_rows.sort_by(|a, b| {
-        // Sort by year, then app_id, then section
-        a.2.cmp(&b.2).then(a.0.cmp(&b.0)).then(a.3.cmp(&b.3))
-    });
+    Ok(Some(mapping_failures::MappingFailure::new(app_id, error_class, error, previous)))
+}
 
-    for (app_id, playtime_seconds, year, section) in csv_rows {
-        // Convert section to readable month name
-        let month = convert_section_to_month(&section);
+/// Writes game_mapping_master.csv, plus the JSON or TOML copy `format` asks for.
+fn write_game_mapping(
+    game_mapping: &HashMap<String, String>,
+    mapping_fields: &dataset::MappingFields,
+    format: &str,
+) -> Result<()> {
+    let mapping_filename = "game_mapping_master.csv";
+    dataset::write_mapping(mapping_filename, game_mapping, mapping_fields)?;
+    summary::file_written(mapping_filename);
+    println!("\nMaster game mapping saved to: {}", mapping_filename);
 
-        csv_content.push_str(&format!("{},{},{},{}\n", app_id, playtime_seconds, year, month));
+    if format != "csv" {
+        let structured_filename = format!("game_mapping_master.{}", format);
+        dataset::write_mapping(&structured_filename, game_mapping, mapping_fields)?;
+        summary::file_written(&structured_filename);
+        println!("Master game mapping saved to: {}", structured_filename);
     }
 
-    fs::write(csv_filename, csv_content)
-        .context("Failed to write CSV file")?;
+    Ok(())
+}
 
-    println!("\nCSV data saved to: {}", csv_filename);
+fn read_json(path: &str) -> Result<Value> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
 
-    Ok(())
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path))
 }
 
 fn extract_app_ids(value: &Value) -> HashSet<String> {
@@ -267,16 +1313,16 @@ fn extract_app_ids_recursive(value: &Value, app_ids: &mut HashSet<String>) {
     match value {
         Value::Object(map) => {
             // Check if this object has an "appid" field
-            if let Some(Value::Number(app_id)) = map.get("appid") {
-                if let Some(id) = app_id.as_u64() {
-                    app_ids.insert(id.to_string());
-                }
+            if let Some(Value::Number(app_id)) = map.get("appid")
+                && let Some(id) = app_id.as_u64()
+            {
+                app_ids.insert(id.to_string());
             }
             // Also check for "app_id" field
-            if let Some(Value::Number(app_id)) = map.get("app_id") {
-                if let Some(id) = app_id.as_u64() {
-                    app_ids.insert(id.to_string());
-                }
+            if let Some(Value::Number(app_id)) = map.get("app_id")
+                && let Some(id) = app_id.as_u64()
+            {
+                app_ids.insert(id.to_string());
             }
             // Recursively search all values
             for val in map.values() {
@@ -292,39 +1338,26 @@ fn extract_app_ids_recursive(value: &Value, app_ids: &mut HashSet<String>) {
     }
 }
 
-fn fetch_game_name(app_id: &str) -> Result<Option<String>> {
-    let url = format!("https://store.steampowered.com/api/appdetails?appids={}", app_id);
-
-    let response = reqwest::blocking::get(&url)
-        .context("Failed to fetch game details")?;
+/// The game's name with its appdetails entry, or None when the store has
+/// no data for it.
+fn fetch_game_details(app_id: &str, refresh: bool) -> Result<Option<(String, Value)>> {
+    // Goes through the appdetails cache, so repeated runs don't refetch
+    let entry = details::load(app_id, refresh)?;
 
-    let data: Value = response.json()
-        .context("Failed to parse response")?;
-
-    // Steam API returns: { "appid": { "success": true/false, "data": {...} } }
-    if let Some(app_data) = data.get(app_id) {
-        if let Some(success) = app_data.get("success").and_then(|v| v.as_bool()) {
-            if success {
-                if let Some(name) = app_data
-                    .get("data")
-                    .and_then(|d| d.get("name"))
-                    .and_then(|n| n.as_str())
-                {
-                    return Ok(Some(name.to_string()));
-                }
-            }
-        }
-    }
+    let name = details::data(&entry)
+        .and_then(|d| d.get("name"))
+        .and_then(|n| n.as_str())
+        .map(|name| name.to_string());
 
-    Ok(None)
+    Ok(name.map(|name| (name, entry)))
 }
 
 fn extract_year_from_data(data: &Value, filename: &str) -> String {
     // Try to extract year from URL in data
-    if let Some(url) = data.get("url").and_then(|v| v.as_str()) {
-        if let Some(year) = extract_year(url) {
-            return year.to_string();
-        }
+    if let Some(url) = data.get("url").and_then(|v| v.as_str())
+        && let Some(year) = extract_year(url)
+    {
+        return year.to_string();
     }
 
     // Try to extract from filename
@@ -367,16 +1400,16 @@ fn extract_playtime_recursive(value: &Value, playtime_data: &mut Vec<(String, u6
                     .and_then(|stats| stats.get("total_playtime_seconds"))
                     .and_then(|v| v.as_u64());
 
-                if let (Some(app_id), Some(playtime)) = (app_id, playtime_seconds) {
-                    if playtime > 0 {
-                        // Build section identifier from path
-                        let section = if path.is_empty() {
-                            "unknown".to_string()
-                        } else {
-                            path.join(".")
-                        };
-                        playtime_data.push((app_id, playtime, section));
-                    }
+                if let (Some(app_id), Some(playtime)) = (app_id, playtime_seconds)
+                    && playtime > 0
+                {
+                    // Build section identifier from path
+                    let section = if path.is_empty() {
+                        "unknown".to_string()
+                    } else {
+                        path.join(".")
+                    };
+                    playtime_data.push((app_id, playtime, section));
                 }
             }
 
@@ -390,13 +1423,13 @@ fn extract_playtime_recursive(value: &Value, playtime_data: &mut Vec<(String, u6
                     new_path.push(key.clone());
                 } else if key == "rtime_month" && path.last().map(|s| s.starts_with("month_")).unwrap_or(false) {
                     // For month objects, add the readable month to the path
-                    if let Some(Value::Number(ts)) = map.get("rtime_month") {
-                        if let Some(timestamp) = ts.as_i64() {
-                            let month_str = format_month_from_timestamp(timestamp);
-                            // Replace the last element (month_N) with readable month
-                            if let Some(last) = new_path.last_mut() {
-                                *last = month_str;
-                            }
+                    if let Some(Value::Number(ts)) = map.get("rtime_month")
+                        && let Some(timestamp) = ts.as_i64()
+                    {
+                        let month_str = format_month_from_timestamp(timestamp);
+                        // Replace the last element (month_N) with readable month
+                        if let Some(last) = new_path.last_mut() {
+                            *last = month_str;
                         }
                     }
                 }
@@ -434,10 +1467,10 @@ fn convert_section_to_month(section: &str) -> String {
     }
 
     // Extract month number from "playtime_stats.months.month_N"
-    if let Some(month_part) = section.strip_prefix("playtime_stats.months.month_") {
-        if let Ok(month_num) = month_part.parse::<usize>() {
-            return get_month_name(month_num);
-        }
+    if let Some(month_part) = section.strip_prefix("playtime_stats.months.month_")
+        && let Ok(month_num) = month_part.parse::<usize>()
+    {
+        return get_month_name(month_num);
     }
 
     // Default: return as is
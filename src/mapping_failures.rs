@@ -0,0 +1,132 @@
+//! Apps `map-games` couldn't name, kept in failures.json in the data
+//! directory so `map-games --retry-failed` can re-attempt just those. How soon a failure
+//! is retried depends on its class: rate-limited lookups are retried with a
+//! growing pause between requests, apps the store has no data for only after
+//! `NOT_FOUND_RECHECK_DAYS`, anything else right away.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const FAILURES_FILENAME: &str = "failures.json";
+
+/// Delisted or region-locked apps rarely come back; don't ask every run
+const NOT_FOUND_RECHECK_DAYS: i64 = 7;
+
+/// Pause before retrying a rate-limited lookup, doubled per failed attempt
+const RATE_LIMIT_BASE_DELAY: Duration = Duration::from_secs(2);
+const RATE_LIMIT_MAX_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    /// HTTP 429
+    RateLimited,
+    /// HTTP 404, or the store answered without data for the app
+    NotFound,
+    /// HTTP 5xx
+    ServerError,
+    /// No response: DNS, connection or timeout
+    Network,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MappingFailure {
+    pub app_id: String,
+    pub error_class: ErrorClass,
+    pub error: String,
+    /// RFC 3339 time of the last attempt
+    pub timestamp: String,
+    /// Failed attempts so far
+    pub attempts: u32,
+}
+
+impl MappingFailure {
+    /// `previous` is the app's earlier failure, if it had one.
+    pub fn new(app_id: &str, error_class: ErrorClass, error: String, previous: Option<&MappingFailure>) -> MappingFailure {
+        MappingFailure {
+            app_id: app_id.to_string(),
+            error_class,
+            error,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            attempts: previous.map(|p| p.attempts).unwrap_or(0) + 1,
+        }
+    }
+
+    /// Whether `--retry-failed` should try the app again now.
+    pub fn due(&self) -> bool {
+        if self.error_class != ErrorClass::NotFound {
+            return true;
+        }
+        chrono::DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|last| chrono::Utc::now().signed_duration_since(last) >= chrono::Duration::days(NOT_FOUND_RECHECK_DAYS))
+            .unwrap_or(true)
+    }
+
+    /// Pause before the retry.
+    pub fn delay(&self) -> Duration {
+        if self.error_class != ErrorClass::RateLimited {
+            return Duration::ZERO;
+        }
+        RATE_LIMIT_BASE_DELAY
+            .saturating_mul(1 << self.attempts.saturating_sub(1).min(10))
+            .min(RATE_LIMIT_MAX_DELAY)
+    }
+}
+
+pub fn classify(error: &anyhow::Error) -> ErrorClass {
+    let Some(error) = error.chain().find_map(|cause| cause.downcast_ref::<reqwest::Error>()) else {
+        return ErrorClass::Other;
+    };
+
+    match error.status() {
+        Some(status) if status.as_u16() == 429 => ErrorClass::RateLimited,
+        Some(status) if status.as_u16() == 404 => ErrorClass::NotFound,
+        Some(status) if status.is_server_error() => ErrorClass::ServerError,
+        Some(_) => ErrorClass::Other,
+        None if error.is_connect() || error.is_timeout() || error.is_request() => ErrorClass::Network,
+        None => ErrorClass::Other,
+    }
+}
+
+pub fn path() -> Result<PathBuf> {
+    Ok(crate::config::data_dir()?.join(FAILURES_FILENAME))
+}
+
+/// The failures recorded by the last run; none when there's no file.
+pub fn load() -> Result<Vec<MappingFailure>> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Writes failures.json, or removes it once nothing is left to retry.
+pub fn save(failures: &[MappingFailure]) -> Result<()> {
+    let path = path()?;
+    if failures.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(failures)? + "\n")
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    crate::summary::file_written(&path);
+    println!("{} failed lookup(s) saved to: {} (retry with map-games --retry-failed)", failures.len(), path.display());
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! Consolidated multi-year document (`merge --output master.json`): every
+//! scraped replay keyed by Steam ID and year, with playtime normalised to a
+//! yearly total plus twelve monthly values per game. `report` and `to-csv`
+//! accept it wherever they accept replay JSON.
+
+use anyhow::{Context, Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::dataset::PlaytimeRow;
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Master {
+    pub generated_at: String,
+    /// steam_id -> year -> playtime
+    pub players: BTreeMap<String, BTreeMap<String, YearPlaytime>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct YearPlaytime {
+    /// Replay page the year was scraped from, when known
+    pub url: Option<String>,
+    pub scraped_at: Option<String>,
+    /// SHA-256 of the page the year was extracted from, when recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_sha256: Option<String>,
+    /// app_id -> playtime
+    pub games: BTreeMap<String, GamePlaytime>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct GamePlaytime {
+    pub total_seconds: u64,
+    /// January..December
+    pub monthly_seconds: [u64; 12],
+}
+
+/// Whether a parsed JSON file is a master document rather than a replay scrape.
+pub fn is_master(data: &Value) -> bool {
+    data.get("players").is_some_and(|p| p.is_object()) && data.get("data").is_none()
+}
+
+pub fn from_value(data: Value, source: &str) -> Result<Master> {
+    serde_json::from_value(data).with_context(|| format!("Failed to parse {} as a master file", source))
+}
+
+impl Master {
+    /// (steam_id, year, rows) for every year in the document, in the
+    /// dataset's row form.
+    pub fn player_years(&self) -> Vec<(&str, &str, Vec<PlaytimeRow>)> {
+        let mut result = Vec::new();
+
+        for (steam_id, years) in &self.players {
+            for (year, playtime) in years {
+                let mut rows = Vec::new();
+                for (app_id, game) in &playtime.games {
+                    let row = |playtime_seconds, month: String| PlaytimeRow {
+                        app_id: app_id.clone(),
+                        playtime_seconds,
+                        year: year.clone(),
+                        month,
+                    };
+                    if game.total_seconds > 0 {
+                        rows.push(row(game.total_seconds, "total".to_string()));
+                    }
+                    for (index, &seconds) in game.monthly_seconds.iter().enumerate() {
+                        if seconds > 0 {
+                            rows.push(row(seconds, crate::get_month_name(index)));
+                        }
+                    }
+                }
+                result.push((steam_id.as_str(), year.as_str(), rows));
+            }
+        }
+
+        result
+    }
+
+    pub fn rows(&self) -> Vec<PlaytimeRow> {
+        self.player_years()
+            .into_iter()
+            .flat_map(|(_, _, rows)| rows)
+            .collect()
+    }
+}
+
+pub fn merge(inputs: &[String], output: &str) -> Result<()> {
+    println!("Merging {} file(s)...", inputs.len());
+
+    let mut master = Master::default();
+
+    for input in inputs {
+        println!("Processing: {}", input);
+
+        let file_content = fs::read_to_string(input)
+            .with_context(|| format!("Failed to read {}", input))?;
+
+        let data: Value = serde_json::from_str(&file_content)
+            .with_context(|| format!("Failed to parse {}", input))?;
+
+        // Earlier master files can be merged with newer scrapes
+        if is_master(&data) {
+            for (steam_id, years) in from_value(data, input)?.players {
+                for (year, playtime) in years {
+                    insert_year(&mut master, &steam_id, &year, playtime);
+                }
+            }
+            continue;
+        }
+
+        let url = data.get("url").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let Some(steam_id) = url.as_deref().and_then(crate::extract_steam_id).map(|s| s.to_string()) else {
+            bail!("No Steam ID found in {}", input);
+        };
+        let year = crate::extract_year_from_data(&data, input);
+
+        let mut games: BTreeMap<String, GamePlaytime> = BTreeMap::new();
+        for (app_id, playtime_seconds, section) in crate::replay::extract_playtime(&data, input, false)? {
+            let game = games.entry(app_id).or_default();
+            match crate::convert_section_to_month(&section).as_str() {
+                "total" => game.total_seconds = playtime_seconds,
+                month => match crate::dataset::month_order(month) {
+                    index @ 1..=12 => game.monthly_seconds[index - 1] = playtime_seconds,
+                    _ => println!("  Warning: Skipping playtime in unknown section {}", section),
+                },
+            }
+        }
+
+        let playtime = YearPlaytime {
+            url,
+            scraped_at: data.get("timestamp").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            page_sha256: data
+                .pointer("/provenance/page_sha256")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            games,
+        };
+        insert_year(&mut master, &steam_id, &year, playtime);
+    }
+
+    master.generated_at = chrono::Utc::now().to_rfc3339();
+
+    fs::write(output, serde_json::to_string_pretty(&master)?)
+        .with_context(|| format!("Failed to write {}", output))?;
+    crate::summary::file_written(output);
+
+    let years: usize = master.players.values().map(|years| years.len()).sum();
+    println!(
+        "\nMerged {} year(s) for {} account(s) into: {}",
+        years,
+        master.players.len(),
+        output
+    );
+
+    Ok(())
+}
+
+/// Later inputs replace earlier ones for the same account and year.
+fn insert_year(master: &mut Master, steam_id: &str, year: &str, playtime: YearPlaytime) {
+    let years = master.players.entry(steam_id.to_string()).or_default();
+    if years.insert(year.to_string(), playtime).is_some() {
+        println!("  Note: Replacing earlier data for {} in {}", steam_id, year);
+    }
+}
@@ -0,0 +1,91 @@
+//! Milestones crossed during each replay year, with the month they fell in,
+//! for `report --milestones`:
+//!
+//! - a game's lifetime playtime passing `LIFETIME_HOURS`, which needs a
+//!   lifetime figure from `import-local` or `community-games`. The figure is
+//!   taken as the game's total at the end of the row's year, and earlier
+//!   months are worked out by taking the replay months off it;
+//! - the year's hours passing each multiple of `YEAR_STEP_HOURS`.
+
+use std::collections::HashMap;
+
+use crate::analytics;
+use crate::dataset::PlaytimeRow;
+
+pub const LIFETIME_HOURS: &[u64] = &[100, 250, 500, 1000, 2000, 5000];
+pub const YEAR_STEP_HOURS: u64 = 100;
+
+pub struct Milestone {
+    pub year: String,
+    /// 0 = January
+    pub month: usize,
+    pub hours: u64,
+    /// The game passing a lifetime mark; None for the year's total
+    pub app_id: Option<String>,
+}
+
+/// Every milestone, by year and month, and whether the dataset had lifetime
+/// figures to find per-game ones with.
+pub fn milestones(rows: &[PlaytimeRow]) -> (Vec<Milestone>, bool) {
+    let monthly = analytics::monthly_by_game(rows);
+    let mut milestones = Vec::new();
+
+    for (year, games) in &monthly {
+        let mut previous = 0u64;
+        let mut running = 0u64;
+        for month in 0..12 {
+            running += games.values().map(|months| months[month]).sum::<u64>();
+            for step in (previous / 3600 / YEAR_STEP_HOURS + 1)..=(running / 3600 / YEAR_STEP_HOURS) {
+                milestones.push(Milestone {
+                    year: year.to_string(),
+                    month,
+                    hours: step * YEAR_STEP_HOURS,
+                    app_id: None,
+                });
+            }
+            previous = running;
+        }
+    }
+
+    // app_id -> (lifetime seconds, year it was recorded), the largest figure
+    let mut lifetimes: HashMap<&str, (u64, &str)> = HashMap::new();
+    for row in rows.iter().filter(|row| row.month == "lifetime") {
+        let entry = lifetimes.entry(row.app_id.as_str()).or_insert((0, row.year.as_str()));
+        if row.playtime_seconds >= entry.0 {
+            *entry = (row.playtime_seconds, row.year.as_str());
+        }
+    }
+
+    for (app_id, (lifetime, recorded)) in &lifetimes {
+        // The game's replay months in order, as (year, month, seconds)
+        let months: Vec<(&str, usize, u64)> = monthly
+            .iter()
+            .filter_map(|(year, games)| games.get(app_id).map(|months| (*year, months)))
+            .flat_map(|(year, months)| months.iter().enumerate().map(move |(month, seconds)| (year, month, *seconds)))
+            .collect();
+
+        // Lifetime playtime before the first replay month
+        let through_recorded: u64 = months.iter().filter(|(year, _, _)| year <= recorded).map(|(_, _, s)| s).sum();
+        let mut total = lifetime.saturating_sub(through_recorded);
+
+        for (year, month, seconds) in months {
+            let before = total;
+            total += seconds;
+            for &hours in LIFETIME_HOURS {
+                if before < hours * 3600 && total >= hours * 3600 {
+                    milestones.push(Milestone {
+                        year: year.to_string(),
+                        month,
+                        hours,
+                        app_id: Some(app_id.to_string()),
+                    });
+                }
+            }
+        }
+    }
+
+    milestones.sort_by(|a, b| {
+        (&a.year, a.month, a.app_id.is_some(), &a.app_id, a.hours).cmp(&(&b.year, b.month, b.app_id.is_some(), &b.app_id, b.hours))
+    });
+    (milestones, !lifetimes.is_empty())
+}
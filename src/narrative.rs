@@ -0,0 +1,242 @@
+//! A paragraph-style recap of each year for `report --narrative`, e.g.
+//! "In 2024 you played 47 games for 612 hours. March was your biggest month
+//! (98 hours), thanks mostly to Factorio. ..."
+//!
+//! The facts are picked out of the aggregates here and phrased by the
+//! `narrative-*` messages in the locale files, whose selectors choose the
+//! wording (more or less than last year, a game leading or dominating the
+//! year, and so on). Nothing is generated online. A sentence is left out
+//! when the dataset can't support it: the month ones need monthly rows, the
+//! comparisons an earlier year with the same kind of data (a totals-only
+//! year against one with months reads as a jump that isn't there). Totals
+//! come from the same yearly rows as the report's table, so the two agree.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::analytics;
+use crate::dataset::PlaytimeRow;
+use crate::i18n::{self, t};
+use crate::report::game_name;
+
+/// A top game with at least this share of the year "dominated" it
+const DOMINANT_SHARE: f64 = 50.0;
+/// Changes from the previous year smaller than this read as "about the same"
+const FLAT_CHANGE: f64 = 5.0;
+/// Shorter runs of months aren't worth a sentence
+const MIN_STREAK: usize = 3;
+
+pub struct YearRecap {
+    pub year: String,
+    pub hours: f64,
+    pub games: usize,
+    /// (app_id, hours, share of the year in percent)
+    pub top_game: Option<(String, f64, f64)>,
+    /// (month index, hours, the month's most played app)
+    pub biggest_month: Option<(usize, f64, String)>,
+    /// (month index, hours), over the months up to the last one played
+    pub quietest_month: Option<(usize, f64)>,
+    /// Games not seen in an earlier year; None for the first year
+    pub new_games: Option<usize>,
+    /// (previous year, change in percent), when the year before is in the
+    /// dataset with the same kind of data
+    pub change: Option<(String, f64)>,
+    /// (app_id, months): the longest run of consecutive months one game was played
+    pub streak: Option<(String, usize)>,
+}
+
+/// One recap per replay year with playtime, oldest first.
+pub fn recaps(rows: &[PlaytimeRow]) -> Vec<YearRecap> {
+    let monthly = analytics::monthly_by_game(rows);
+
+    // year -> (app_id, seconds), most played first
+    let mut by_year: HashMap<&str, Vec<(&str, u64)>> = HashMap::new();
+    for (year, games) in analytics::yearly_totals(rows) {
+        if year.parse::<i32>().is_err() {
+            continue;
+        }
+        let mut played: Vec<(&str, u64)> = games
+            .iter()
+            .filter(|row| row.playtime_seconds > 0)
+            .map(|row| (row.app_id.as_str(), row.playtime_seconds))
+            .collect();
+        if played.is_empty() {
+            continue;
+        }
+        played.sort_by_key(|&(app_id, seconds)| (std::cmp::Reverse(seconds), app_id));
+        by_year.insert(year, played);
+    }
+    let total = |year: &str| by_year[year].iter().map(|(_, seconds)| seconds).sum::<u64>();
+    let mut years: Vec<&str> = by_year.keys().copied().collect();
+    years.sort_unstable();
+
+    let mut seen: BTreeSet<&str> = BTreeSet::new();
+    let mut recaps = Vec::new();
+    for (index, &year) in years.iter().enumerate() {
+        let played = &by_year[year];
+        let seconds = total(year);
+        let hours = seconds as f64 / 3600.0;
+
+        let top_game = played
+            .first()
+            .map(|&(app_id, top)| (app_id.to_string(), top as f64 / 3600.0, top as f64 / seconds as f64 * 100.0));
+
+        let new_games = (index > 0).then(|| played.iter().filter(|(app_id, _)| !seen.contains(app_id)).count());
+        seen.extend(played.iter().map(|(app_id, _)| *app_id));
+
+        let change = index
+            .checked_sub(1)
+            .map(|previous| years[previous])
+            .filter(|previous| previous.parse::<i32>().ok() == year.parse::<i32>().ok().map(|year| year - 1))
+            .filter(|previous| monthly.contains_key(previous) == monthly.contains_key(year))
+            .map(|previous| {
+                let before = total(previous);
+                (previous.to_string(), (seconds as f64 - before as f64) / before.max(1) as f64 * 100.0)
+            });
+
+        let (biggest_month, quietest_month, streak) = match monthly.get(year) {
+            Some(months_by_game) => month_facts(months_by_game),
+            None => (None, None, None),
+        };
+
+        recaps.push(YearRecap {
+            year: year.to_string(),
+            hours,
+            games: played.len(),
+            top_game,
+            biggest_month,
+            quietest_month,
+            new_games,
+            change,
+            streak,
+        });
+    }
+    recaps
+}
+
+type MonthFacts = (Option<(usize, f64, String)>, Option<(usize, f64)>, Option<(String, usize)>);
+
+fn month_facts(months_by_game: &BTreeMap<&str, [u64; 12]>) -> MonthFacts {
+    let mut totals = [0u64; 12];
+    for months in months_by_game.values() {
+        for (total, seconds) in totals.iter_mut().zip(months) {
+            *total += seconds;
+        }
+    }
+    let Some(last_played) = totals.iter().rposition(|seconds| *seconds > 0) else {
+        return (None, None, None);
+    };
+
+    // Earliest month wins ties
+    let biggest = (0..=last_played).rev().max_by_key(|&month| totals[month]).expect("at least one month");
+    let biggest_game = months_by_game
+        .iter()
+        .max_by_key(|&(app_id, months)| (months[biggest], std::cmp::Reverse(*app_id)))
+        .map(|(app_id, _)| app_id.to_string())
+        .expect("a month with playtime has a game");
+    let biggest_month = Some((biggest, totals[biggest] as f64 / 3600.0, biggest_game));
+
+    let quietest_month = (last_played > 0)
+        .then(|| (0..=last_played).rev().min_by_key(|&month| totals[month]).expect("at least one month"))
+        .map(|month| (month, totals[month] as f64 / 3600.0));
+
+    let streak = months_by_game
+        .iter()
+        .map(|(app_id, months)| {
+            let longest = months
+                .split(|seconds| *seconds == 0)
+                .map(|run| run.len())
+                .max()
+                .unwrap_or(0);
+            (app_id.to_string(), longest)
+        })
+        .max_by_key(|(app_id, longest)| (*longest, std::cmp::Reverse(app_id.clone())))
+        .filter(|(_, longest)| *longest >= MIN_STREAK);
+
+    (biggest_month, quietest_month, streak)
+}
+
+/// The recap as one paragraph, a sentence per fact.
+pub fn paragraph(recap: &YearRecap, mapping: &HashMap<String, String>) -> String {
+    let mut sentences = vec![t!(
+        "narrative-overview",
+        year = recap.year.as_str(),
+        games = recap.games,
+        hours = format!("{:.0}", recap.hours)
+    )];
+
+    if let Some((previous, percent)) = &recap.change {
+        let direction = match percent {
+            p if p.abs() < FLAT_CHANGE => "flat",
+            p if *p > 0.0 => "up",
+            _ => "down",
+        };
+        sentences.push(t!(
+            "narrative-change",
+            direction = direction,
+            percent = format!("{:.0}", percent.abs()),
+            previous = previous.as_str()
+        ));
+    }
+
+    if let Some(new_games) = recap.new_games {
+        sentences.push(t!("narrative-new-games", new = new_games, games = recap.games));
+    }
+
+    if let Some((app_id, hours, share)) = &recap.top_game
+        && recap.games > 1
+    {
+        let kind = if *share >= DOMINANT_SHARE { "dominant" } else { "leading" };
+        sentences.push(t!(
+            "narrative-top-game",
+            kind = kind,
+            game = game_name(mapping, app_id),
+            hours = format!("{:.0}", hours),
+            share = format!("{:.0}", share)
+        ));
+    }
+
+    if let Some((month, hours, app_id)) = &recap.biggest_month {
+        sentences.push(t!(
+            "narrative-biggest-month",
+            month = i18n::month_name(*month),
+            hours = format!("{:.0}", hours),
+            game = game_name(mapping, app_id)
+        ));
+    }
+
+    if let Some((month, hours)) = &recap.quietest_month {
+        let kind = if *hours == 0.0 { "off" } else { "quiet" };
+        sentences.push(t!(
+            "narrative-quietest-month",
+            kind = kind,
+            month = i18n::month_name(*month),
+            hours = format!("{:.1}", hours)
+        ));
+    }
+
+    if let Some((app_id, months)) = &recap.streak {
+        let kind = if *months == 12 { "all-year" } else { "run" };
+        sentences.push(t!("narrative-streak", kind = kind, game = game_name(mapping, app_id), months = *months));
+    }
+
+    sentences.join(" ")
+}
+
+/// Breaks `text` into lines of at most `width` characters, on spaces.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
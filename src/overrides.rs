@@ -0,0 +1,80 @@
+//! User overrides from `overrides.toml`, read from the working directory
+//! (next to the mapping) or else the config directory:
+//!
+//! ```toml
+//! [1172470]
+//! name = "Apex Legends"
+//! tags = ["shooter", "with friends"]
+//! notes = "Season 20 onwards"
+//! ```
+//!
+//! Names replace the mapped ones wherever the mapping is read, so reports,
+//! exports and badges show them without touching the generated mapping.
+//! Tags group games in the report's "Hours by tag" section and, with notes,
+//! show up in `game` and the `tags` / `notes` columns of `to-csv`.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+pub const OVERRIDES_FILENAME: &str = "overrides.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Override {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+}
+
+fn path() -> Result<Option<PathBuf>> {
+    let local = PathBuf::from(OVERRIDES_FILENAME);
+    if local.exists() {
+        return Ok(Some(local));
+    }
+    let global = crate::config::config_dir()?.join(OVERRIDES_FILENAME);
+    Ok(global.exists().then_some(global))
+}
+
+/// app_id -> override, loaded once per run; empty without a file.
+pub fn load() -> Result<&'static BTreeMap<String, Override>> {
+    static OVERRIDES: OnceLock<BTreeMap<String, Override>> = OnceLock::new();
+    if let Some(overrides) = OVERRIDES.get() {
+        return Ok(overrides);
+    }
+
+    let overrides = match path()? {
+        Some(path) => {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?
+        }
+        None => BTreeMap::new(),
+    };
+    Ok(OVERRIDES.get_or_init(|| overrides))
+}
+
+/// The override for a game, if any. A file that fails to load counts as
+/// empty here; `dataset::load_mapping` has reported it already.
+pub fn get(app_id: &str) -> Option<&'static Override> {
+    load().ok()?.get(app_id)
+}
+
+pub fn tags(app_id: &str) -> &'static [String] {
+    get(app_id).map(|entry| entry.tags.as_slice()).unwrap_or_default()
+}
+
+/// Replaces mapped names with the overridden ones, adding games the mapping
+/// doesn't have.
+pub fn apply_names(mapping: &mut HashMap<String, String>) -> Result<()> {
+    for (app_id, entry) in load()? {
+        if let Some(name) = &entry.name {
+            mapping.insert(app_id.clone(), name.clone());
+        }
+    }
+    Ok(())
+}
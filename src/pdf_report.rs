@@ -0,0 +1,266 @@
+//! The report as a paginated A4 PDF (`report --format pdf`), for printing:
+//! the same sections as the HTML report (each year's top games with bars,
+//! the monthly heatmap), with a bookmark per year and page numbers.
+//!
+//! A TrueType font is embedded so names print the same everywhere: the one
+//! given with `--font`, else the first common system font found. Without
+//! one the PDF falls back to the viewer's Helvetica, which only covers
+//! Latin-1, so other characters are replaced.
+
+use anyhow::{Context, Result, anyhow};
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, PdfPageIndex,
+    Rect, Rgb,
+};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::dataset;
+use crate::i18n::{self, t};
+use crate::report::{ReportOptions, game_name};
+
+pub const DEFAULT_FILENAME: &str = "steam_replay_report.pdf";
+
+/// Tried in order when no `--font` is given
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/liberation-sans/LiberationSans-Regular.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+// A4 portrait, in millimetres
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const LINE: f32 = 6.0;
+
+// Columns of the top-games tables
+const RANK_X: f32 = MARGIN;
+const NAME_X: f32 = MARGIN + 9.0;
+const HOURS_X: f32 = 122.0;
+const BAR_X: f32 = 142.0;
+const BAR_WIDTH: f32 = PAGE_WIDTH - MARGIN - BAR_X;
+/// Longer names are cut so they don't run into the hours column
+const NAME_CHARS: usize = 48;
+
+// Heatmap grid
+const CELL: f32 = 11.0;
+const GAP: f32 = 1.5;
+const LABEL_WIDTH: f32 = 16.0;
+
+const BAR_COLOR: &str = "#40c463";
+const MUTED_COLOR: &str = "#57606a";
+const TEXT_COLOR: &str = "#24292f";
+
+struct Writer {
+    doc: PdfDocumentReference,
+    pages: Vec<PdfPageIndex>,
+    layers: Vec<PdfLayerReference>,
+    /// Pages with a bookmark; the viewer shows one per page
+    bookmarked: Vec<usize>,
+    font: IndirectFontRef,
+    /// Whether `font` is an embedded TrueType font rather than Helvetica
+    embedded: bool,
+    /// Baseline of the next line, from the bottom of the page
+    y: f32,
+}
+
+impl Writer {
+    fn new(title: &str, font_path: Option<&str>) -> Result<Writer> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Report");
+
+        let font_path = match font_path {
+            Some(path) => Some(path),
+            None => SYSTEM_FONTS.iter().copied().find(|path| Path::new(path).is_file()),
+        };
+        let (font, embedded) = match font_path {
+            Some(path) => {
+                let file = File::open(path).with_context(|| format!("Failed to open font {}", path))?;
+                let font = doc
+                    .add_external_font(file)
+                    .map_err(|e| anyhow!("Failed to load font {}: {:?}", path, e))?;
+                (font, true)
+            }
+            None => {
+                println!("Warning: No TrueType font found; names outside Latin-1 won't print. Pass one with --font FILE");
+                let font = doc
+                    .add_builtin_font(BuiltinFont::Helvetica)
+                    .map_err(|e| anyhow!("Failed to load Helvetica: {:?}", e))?;
+                (font, false)
+            }
+        };
+
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(Writer {
+            doc,
+            pages: vec![page],
+            layers: vec![layer],
+            bookmarked: Vec::new(),
+            font,
+            embedded,
+            y: PAGE_HEIGHT - MARGIN,
+        })
+    }
+
+    fn layer(&self) -> &PdfLayerReference {
+        self.layers.last().expect("the document has a first page")
+    }
+
+    /// Starts a new page unless `height` more millimetres fit on this one.
+    fn ensure_space(&mut self, height: f32) {
+        // Room is kept at the bottom for the page number
+        if self.y - height >= MARGIN + LINE {
+            return;
+        }
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Report");
+        self.pages.push(page);
+        self.layers.push(self.doc.get_page(page).get_layer(layer));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Bookmarks the current page, unless an earlier section already did.
+    fn bookmark(&mut self, name: &str) {
+        let index = self.pages.len() - 1;
+        if !self.bookmarked.contains(&index) {
+            self.doc.add_bookmark(name, self.pages[index]);
+            self.bookmarked.push(index);
+        }
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, y: f32, color: &str) {
+        let text = if self.embedded {
+            text.to_string()
+        } else {
+            text.chars().map(|c| if (c as u32) < 0x100 { c } else { '?' }).collect()
+        };
+        self.layer().set_fill_color(color_from_hex(color));
+        self.layer().use_text(text, size, Mm(x), Mm(y), &self.font);
+    }
+
+    fn rect(&self, x: f32, y: f32, width: f32, height: f32, color: &str) {
+        self.layer().set_fill_color(color_from_hex(color));
+        self.layer().add_rect(Rect::new(Mm(x), Mm(y), Mm(x + width), Mm(y + height)));
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        self.ensure_space(LINE * 3.0);
+        self.y -= LINE * 0.5;
+        self.text(text, size, MARGIN, self.y, TEXT_COLOR);
+        self.y -= LINE * 1.5;
+    }
+
+    /// Numbers the pages and writes the PDF.
+    fn save(self, path: &str) -> Result<()> {
+        let footer = format!(
+            "steamreplay {} · {}",
+            env!("CARGO_PKG_VERSION"),
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        );
+        let pages = self.layers.len();
+        for (index, layer) in self.layers.iter().enumerate() {
+            layer.set_fill_color(color_from_hex(MUTED_COLOR));
+            layer.use_text(footer.as_str(), 8.0, Mm(MARGIN), Mm(MARGIN / 2.0), &self.font);
+            layer.use_text(
+                format!("{} / {}", index + 1, pages),
+                8.0,
+                Mm(PAGE_WIDTH - MARGIN - 10.0),
+                Mm(MARGIN / 2.0),
+                &self.font,
+            );
+        }
+
+        let file = File::create(path).with_context(|| format!("Failed to create {}", path))?;
+        self.doc
+            .save(&mut BufWriter::new(file))
+            .map_err(|e| anyhow!("Failed to write {}: {:?}", path, e))
+    }
+}
+
+pub fn write_pdf(inputs: &[String], options: &ReportOptions, path: &str, font_path: Option<&str>) -> Result<()> {
+    let rows = crate::report::load_rows(inputs, options)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+    let title = t!("report-title");
+
+    let mut writer = Writer::new(&title, font_path)?;
+    writer.text(&title, 20.0, MARGIN, writer.y, TEXT_COLOR);
+    writer.y -= LINE * 1.5;
+    if let Some(months) = &options.months {
+        writer.text(&t!("report-months", months = dataset::describe_months(months)), 10.0, MARGIN, writer.y, MUTED_COLOR);
+        writer.y -= LINE;
+    }
+
+    for (year, games) in crate::analytics::yearly_totals(&rows) {
+        let total_hours: f64 = games.iter().map(|row| row.hours()).sum();
+        writer.heading(
+            &t!("report-year-summary", year = year, hours = format!("{:.1}", total_hours), games = games.len()),
+            14.0,
+        );
+        writer.bookmark(year);
+
+        let top_hours = games.first().map(|row| row.hours()).unwrap_or(0.0).max(f64::EPSILON);
+        for (rank, row) in games.iter().take(options.top_n).enumerate() {
+            writer.ensure_space(LINE);
+            let mut name = game_name(&mapping, &row.app_id);
+            if name.chars().count() > NAME_CHARS {
+                name = name.chars().take(NAME_CHARS - 1).collect::<String>() + "…";
+            }
+            writer.text(&format!("{}.", rank + 1), 10.0, RANK_X, writer.y, MUTED_COLOR);
+            writer.text(&name, 10.0, NAME_X, writer.y, TEXT_COLOR);
+            writer.text(&format!("{:.1}h", row.hours()), 10.0, HOURS_X, writer.y, TEXT_COLOR);
+            let width = (row.hours() / top_hours) as f32 * BAR_WIDTH;
+            writer.rect(BAR_X, writer.y - 0.5, width.max(0.5), 3.0, BAR_COLOR);
+            writer.y -= LINE;
+        }
+    }
+
+    let hours = crate::analytics::monthly_hours(&rows);
+    if !hours.is_empty() {
+        // Heading, month labels and the grid stay on one page
+        writer.ensure_space(LINE * 3.0 + hours.len() as f32 * (CELL + GAP));
+        writer.heading(&t!("heatmap-title"), 14.0);
+        write_heatmap(&mut writer, &hours);
+    }
+
+    writer.save(path)?;
+    crate::summary::file_written(path);
+
+    println!("PDF report saved to: {}", path);
+
+    Ok(())
+}
+
+fn write_heatmap(writer: &mut Writer, hours: &BTreeMap<String, [f64; 12]>) {
+    let max = hours.values().flatten().copied().fold(0.0, f64::max);
+
+    for index in 0..12 {
+        let x = MARGIN + LABEL_WIDTH + index as f32 * (CELL + GAP) + 2.0;
+        writer.text(&i18n::month_abbreviation(index, 3), 8.0, x, writer.y, MUTED_COLOR);
+    }
+    writer.y -= GAP * 2.0;
+
+    for (year, months) in hours {
+        writer.y -= CELL;
+        writer.text(year, 9.0, MARGIN, writer.y + CELL / 2.0 - 1.5, MUTED_COLOR);
+        for (index, &value) in months.iter().enumerate() {
+            let x = MARGIN + LABEL_WIDTH + index as f32 * (CELL + GAP);
+            writer.rect(x, writer.y, CELL, CELL, crate::heatmap::COLORS[crate::heatmap::level(value, max)]);
+        }
+        writer.y -= GAP;
+    }
+}
+
+/// `#rrggbb` as a PDF colour.
+fn color_from_hex(hex: &str) -> Color {
+    let channel = |index: usize| {
+        u8::from_str_radix(hex.get(index..index + 2).unwrap_or("00"), 16).unwrap_or(0) as f32 / 255.0
+    };
+    Color::Rgb(Rgb::new(channel(1), channel(3), channel(5), None))
+}
@@ -0,0 +1,142 @@
+//! `split-per-game`: one JSON document per game with its playtime by year
+//! and month, for static-site generators that render a page per game. Each
+//! page carries a `provenance` object naming the inputs it was built from.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use crate::dataset::{self, PlaytimeRow};
+use crate::master::GamePlaytime;
+
+pub struct SplitOptions {
+    pub output_dir: String,
+    pub mapping_path: String,
+    /// Copy each game's header image next to the JSON files (downloaded
+    /// once into the shared artwork cache)
+    pub with_artwork: bool,
+}
+
+#[derive(Serialize)]
+struct GamePage {
+    app_id: String,
+    name: Option<String>,
+    store_url: String,
+    artwork_url: String,
+    /// Relative to the output directory; only set when artwork was downloaded
+    artwork_path: Option<String>,
+    /// Sum of the yearly totals
+    total_seconds: u64,
+    /// Playtime reported by local or community imports, when present
+    lifetime_seconds: Option<u64>,
+    years: BTreeMap<String, GamePlaytime>,
+    provenance: Value,
+}
+
+pub fn split_per_game(inputs: &[String], options: &SplitOptions) -> Result<()> {
+    let rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+    let provenance = crate::provenance::for_inputs(inputs);
+
+    let mut by_game: BTreeMap<&str, Vec<&PlaytimeRow>> = BTreeMap::new();
+    for row in &rows {
+        by_game.entry(row.app_id.as_str()).or_default().push(row);
+    }
+
+    let output_dir = Path::new(&options.output_dir);
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create {}", output_dir.display()))?;
+
+    println!("Writing {} game file(s) to {}", by_game.len(), output_dir.display());
+
+    for (app_id, game_rows) in by_game {
+        let mut page = build_page(app_id, &game_rows, &mapping, &provenance);
+
+        if options.with_artwork {
+            match download_artwork(&page.artwork_url, output_dir, app_id) {
+                Ok(path) => page.artwork_path = Some(path),
+                Err(e) => {
+                    println!("  Warning: No artwork for app ID {}: {:#}", app_id, e);
+                    crate::summary::failure(app_id, format!("{:#}", e))?;
+                }
+            }
+        }
+
+        let filename = output_dir.join(format!("{}.json", app_id));
+        fs::write(&filename, serde_json::to_string_pretty(&page)?)
+            .with_context(|| format!("Failed to write {}", filename.display()))?;
+        crate::summary::file_written(&filename);
+    }
+
+    println!("\nPer-game data saved to: {}", output_dir.display());
+
+    Ok(())
+}
+
+fn build_page(app_id: &str, rows: &[&PlaytimeRow], mapping: &HashMap<String, String>, provenance: &Value) -> GamePage {
+    let mut years: BTreeMap<String, GamePlaytime> = BTreeMap::new();
+    let mut lifetime_seconds = None;
+
+    for row in rows {
+        match row.month.as_str() {
+            // Imports report one lifetime figure; keep the largest
+            "lifetime" => {
+                lifetime_seconds = lifetime_seconds.max(Some(row.playtime_seconds));
+            }
+            "total" => years.entry(row.year.clone()).or_default().total_seconds = row.playtime_seconds,
+            month => {
+                if let index @ 1..=12 = dataset::month_order(month) {
+                    years.entry(row.year.clone()).or_default().monthly_seconds[index - 1] = row.playtime_seconds;
+                }
+            }
+        }
+    }
+
+    GamePage {
+        app_id: app_id.to_string(),
+        name: mapping.get(app_id).cloned(),
+        store_url: format!("https://store.steampowered.com/app/{}/", app_id),
+        artwork_url: format!("https://cdn.cloudflare.steamstatic.com/steam/apps/{}/header.jpg", app_id),
+        artwork_path: None,
+        total_seconds: years.values().map(|year| year.total_seconds).sum(),
+        lifetime_seconds,
+        years,
+        provenance: provenance.clone(),
+    }
+}
+
+/// Copies the game's header image into `<output_dir>/artwork`, downloading
+/// it into the shared artwork cache first if no earlier run has.
+fn download_artwork(url: &str, output_dir: &Path, app_id: &str) -> Result<String> {
+    let relative_path = format!("artwork/{}.jpg", app_id);
+    let path = output_dir.join(&relative_path);
+
+    // Artwork doesn't change; skip what earlier runs already fetched
+    if path.exists() {
+        return Ok(relative_path);
+    }
+
+    let cached = crate::config::artwork_dir()?.join(format!("{}.jpg", app_id));
+    if !cached.exists() {
+        let bytes = crate::http::get(url)
+            .context("Failed to fetch artwork")?
+            .error_for_status()
+            .context("Artwork request failed")?
+            .bytes()
+            .context("Failed to read artwork body")?;
+
+        fs::create_dir_all(crate::config::artwork_dir()?)?;
+        fs::write(&cached, &bytes)
+            .with_context(|| format!("Failed to write {}", cached.display()))?;
+    }
+
+    fs::create_dir_all(output_dir.join("artwork"))?;
+    fs::copy(&cached, &path)
+        .with_context(|| format!("Failed to copy artwork to {}", path.display()))?;
+    crate::summary::file_written(&path);
+
+    Ok(relative_path)
+}
@@ -0,0 +1,72 @@
+//! `--progress json`: newline-delimited JSON progress events on stderr, for
+//! GUI wrappers and CI jobs. Stdout keeps the usual log lines.
+//!
+//! ```text
+//! {"event":"start","command":"map-games"}
+//! {"event":"phase","phase":"map_games","total":120}
+//! {"event":"item","phase":"map_games","item":"730","completed":0,"total":120}
+//! {"event":"error","phase":"map_games","item":"730","message":"..."}
+//! {"event":"done","success":true,"errors":1}
+//! ```
+//!
+//! `completed` counts the items finished before the one named. Errors are
+//! the per-item failures from `summary::failure`; a run that stops on an
+//! error ends with `"success":false` and its message.
+
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::io::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ERRORS: AtomicUsize = AtomicUsize::new(0);
+/// (phase, total items) of the running phase
+static PHASE: Mutex<Option<(String, usize)>> = Mutex::new(None);
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn emit(event: Value) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut stderr = std::io::stderr().lock();
+    let _ = writeln!(stderr, "{}", event);
+}
+
+pub fn start(command: &str) {
+    emit(json!({ "event": "start", "command": command }));
+}
+
+/// Starts a phase of `total` items, ending the previous one.
+pub fn phase(name: &str, total: usize) {
+    *PHASE.lock().unwrap() = Some((name.to_string(), total));
+    emit(json!({ "event": "phase", "phase": name, "total": total }));
+}
+
+/// The phase's item at `index` (0-based) is starting.
+pub fn item(item: &str, index: usize) {
+    let phase = PHASE.lock().unwrap().clone();
+    let (phase, total) = phase.unwrap_or_default();
+    emit(json!({ "event": "item", "phase": phase, "item": item, "completed": index, "total": total }));
+}
+
+pub fn error(item: &str, message: &str) {
+    ERRORS.fetch_add(1, Ordering::Relaxed);
+    let phase = PHASE.lock().unwrap().as_ref().map(|(phase, _)| phase.clone());
+    emit(json!({ "event": "error", "phase": phase, "item": item, "message": message }));
+}
+
+pub fn done(result: &Result<()>) {
+    let mut event = json!({
+        "event": "done",
+        "success": result.is_ok(),
+        "errors": ERRORS.load(Ordering::Relaxed),
+    });
+    if let Err(e) = result {
+        event["error"] = json!(format!("{:#}", e));
+    }
+    emit(event);
+}
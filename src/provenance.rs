@@ -0,0 +1,129 @@
+//! Where an output came from. Scraped replay JSON carries a `provenance`
+//! object (tool version, source URL, SHA-256 of the fetched page, extraction
+//! time), and so do the `split-per-game` pages, listing the inputs they were
+//! built from. Every other CSV or JSON a run writes gets a `<file>.provenance`
+//! JSON sidecar naming the tool version, the command and each input with its
+//! hash and, for replay JSON, the capture it was extracted from. Playnite
+//! exports and shields.io endpoints keep theirs in the sidecar: their schemas
+//! leave no room for an extra field.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// Not `.json`, so sidecars stay out of `*.json` input globs
+pub const SIDECAR_SUFFIX: &str = ".provenance";
+
+#[derive(Serialize)]
+struct Sidecar<'a> {
+    file: &'a str,
+    tool: &'static str,
+    version: &'static str,
+    generated_at: String,
+    command: &'a str,
+    args: &'a [String],
+    inputs: Vec<Input>,
+}
+
+#[derive(Serialize)]
+struct Input {
+    path: String,
+    sha256: Option<String>,
+    /// The replay capture behind a scraped JSON input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<Value>,
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The `provenance` object embedded in a scraped replay JSON.
+pub fn for_page(url: &str, html: &str, archived_from: Option<&str>) -> Value {
+    let mut provenance = json!({
+        "tool": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "source_url": url,
+        "page_sha256": sha256_hex(html.as_bytes()),
+        "extracted_at": chrono::Utc::now().to_rfc3339(),
+    });
+    if let Some(snapshot) = archived_from {
+        provenance["archived_from"] = json!(snapshot);
+    }
+    provenance
+}
+
+/// The `provenance` object embedded in JSON built from `inputs`.
+pub fn for_inputs(inputs: &[String]) -> Value {
+    json!({
+        "tool": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+        "inputs": inputs.iter().map(|path| describe_input(path)).collect::<Vec<_>>(),
+    })
+}
+
+/// Writes a sidecar next to each CSV and JSON in `files` that doesn't embed
+/// its own provenance. `args` must already be redacted
+/// (`summary::redact_args`): sidecars travel with the outputs.
+pub fn write_sidecars(command: &str, args: &[String], inputs: &[&String], files: &[String]) -> Result<()> {
+    let inputs: Vec<Input> = inputs.iter().map(|path| describe_input(path)).collect();
+    let generated_at = chrono::Utc::now().to_rfc3339();
+
+    for file in files.iter().filter(|file| needs_sidecar(file)) {
+        let sidecar = Sidecar {
+            file,
+            tool: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            generated_at: generated_at.clone(),
+            command,
+            args,
+            // A CSV merged into itself isn't its own source
+            inputs: inputs
+                .iter()
+                .filter(|input| input.path != *file)
+                .map(|input| Input {
+                    path: input.path.clone(),
+                    sha256: input.sha256.clone(),
+                    source: input.source.clone(),
+                })
+                .collect(),
+        };
+
+        let path = format!("{}{}", file, SIDECAR_SUFFIX);
+        fs::write(&path, serde_json::to_string_pretty(&sidecar)?)
+            .with_context(|| format!("Failed to write {}", path))?;
+    }
+
+    Ok(())
+}
+
+fn needs_sidecar(file: &str) -> bool {
+    if !Path::new(file).is_file() {
+        return false;
+    }
+    if file.ends_with(".csv") {
+        return true;
+    }
+    file.ends_with(".json") && embedded(file).is_none()
+}
+
+/// The `provenance` object of a JSON file, if it carries one
+fn embedded(path: &str) -> Option<Value> {
+    let bytes = fs::read(path).ok()?;
+    serde_json::from_slice::<Value>(&bytes).ok()?.get("provenance").cloned()
+}
+
+fn describe_input(path: &str) -> Input {
+    let content = fs::read(path).ok();
+    let source = if path.ends_with(".json") { embedded(path) } else { None };
+
+    Input {
+        path: path.to_string(),
+        sha256: content.as_deref().map(sha256_hex),
+        source,
+    }
+}
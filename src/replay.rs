@@ -0,0 +1,153 @@
+//! Typed model of the Year in Review payload stored in scraped replay JSON
+//! (`data["data-yearinreview"]`). Extraction goes through these structs first
+//! and compares against the recursive walk in main.rs, so entries that the
+//! schema doesn't account for are reported instead of silently dropped.
+
+use anyhow::{Result, bail};
+use chrono::Datelike;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A replay page as saved by `scrape`: every `data-*` attribute of the
+/// page's application_config element, keyed by attribute name.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[allow(dead_code)] // url and timestamp are only required, not read
+pub struct ReplayFile {
+    /// Replay page the data was scraped from
+    pub url: String,
+    /// RFC 3339 time of the scrape
+    pub timestamp: String,
+    pub data: ReplayData,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReplayData {
+    #[serde(rename = "data-yearinreview")]
+    pub year_in_review: YearInReview,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct YearInReview {
+    pub playtime_stats: PlaytimeStats,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PlaytimeStats {
+    /// Whole-year totals per game
+    pub games: Vec<GameStats>,
+    /// One entry per calendar month, January first
+    pub months: Vec<MonthStats>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MonthStats {
+    pub rtime_month: i64,
+    pub game_summary: Vec<GameStats>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GameStats {
+    pub appid: u64,
+    pub relative_game_stats: RelativeGameStats,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RelativeGameStats {
+    pub total_playtime_seconds: u64,
+}
+
+impl PlaytimeStats {
+    /// (app_id, playtime_seconds, section) in the same form as
+    /// `extract_playtime_data`, skipping games without playtime.
+    pub fn entries(&self) -> Vec<(String, u64, String)> {
+        let games = self
+            .games
+            .iter()
+            .map(|game| (game, "playtime_stats.games".to_string()));
+        let months = self.months.iter().enumerate().flat_map(|(index, month)| {
+            month
+                .game_summary
+                .iter()
+                .map(move |game| (game, format!("playtime_stats.months.month_{}", index)))
+        });
+
+        games
+            .chain(months)
+            .filter(|(game, _)| game.relative_game_stats.total_playtime_seconds > 0)
+            .map(|(game, section)| {
+                (
+                    game.appid.to_string(),
+                    game.relative_game_stats.total_playtime_seconds,
+                    section,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Extracts playtime entries from a scraped replay file.
+///
+/// In strict mode the payload must match the typed schema and every entry
+/// the recursive walk finds must be covered by it. Otherwise mismatches are
+/// printed as warnings and the recursive walk's result is used.
+pub fn extract_playtime(data: &Value, source: &str, strict: bool) -> Result<Vec<(String, u64, String)>> {
+    if data.pointer("/provenance/schema_status").and_then(|status| status.as_str()) == Some("unverified") {
+        println!("  Warning: {} was scraped from a page with an unverified schema; check its figures", source);
+    }
+    let walked = crate::extract_playtime_data(data);
+
+    let replay = match ReplayFile::deserialize(data) {
+        Ok(replay) => replay,
+        Err(e) if strict => bail!("{} does not match the replay schema: {}", source, e),
+        Err(e) => {
+            println!(
+                "  Warning: {} does not match the replay schema ({}); falling back to a recursive search",
+                source, e
+            );
+            return Ok(walked);
+        }
+    };
+
+    let stats = &replay.data.year_in_review.playtime_stats;
+
+    // Months are attributed by position, so they have to be in calendar order
+    for (index, month) in stats.months.iter().enumerate() {
+        let actual = chrono::DateTime::from_timestamp(month.rtime_month, 0).map(|d| d.month0() as usize);
+        if actual != Some(index) {
+            let message = format!(
+                "{}: months[{}] has rtime_month {}, which is not {}",
+                source,
+                index,
+                month.rtime_month,
+                crate::get_month_name(index)
+            );
+            if strict {
+                bail!(message);
+            }
+            println!("  Warning: {}", message);
+        }
+    }
+
+    let typed = stats.entries();
+    let uncovered = walked.iter().filter(|entry| !typed.contains(entry)).count();
+
+    if uncovered == 0 {
+        return Ok(typed);
+    }
+
+    if strict {
+        bail!(
+            "{} has {} playtime entries outside the known replay schema",
+            source,
+            uncovered
+        );
+    }
+
+    println!(
+        "  Warning: {} playtime entries in {} are outside the known replay schema; keeping them",
+        uncovered, source
+    );
+
+    Ok(walked)
+}
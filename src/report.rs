@@ -0,0 +1,545 @@
+use anyhow::Result;
+use chrono::Datelike;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::analytics;
+use crate::dataset::{self, PlaytimeRow};
+use crate::i18n::{self, t};
+
+pub struct ReportOptions {
+    pub mapping_path: String,
+    pub achievements_path: String,
+    pub unlocks_path: String,
+    pub profile_path: Option<String>,
+    pub reviews_path: String,
+    pub top_n: usize,
+    /// Include the month-over-month changes section
+    pub deltas: bool,
+    /// Add a section with hours per quarter or season
+    pub group_by: Option<dataset::Grouping>,
+    /// Split each year's hours between that year's releases and older games
+    pub release_split: bool,
+    /// Only these months, with yearly totals recomputed from them
+    pub months: Option<dataset::MonthSet>,
+    /// Only games whose mapped names match
+    pub name_filter: Option<dataset::NameFilter>,
+    /// Fold demos, playtests and betas into their base game
+    pub fold_demos: bool,
+    /// Add a section projecting next year's hours
+    pub forecast: bool,
+    /// Add a section listing the milestones each year crossed
+    pub milestones: bool,
+    /// Add a written recap of each year
+    pub narrative: bool,
+}
+
+pub fn print_report(inputs: &[String], options: &ReportOptions) -> Result<()> {
+    let rows = load_rows(inputs, options)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+
+    let title = t!("report-title");
+    println!("{}", title);
+    println!("{}", "=".repeat(title.chars().count()));
+    if let Some(months) = &options.months {
+        println!("{}", t!("report-months", months = dataset::describe_months(months)));
+    }
+
+    if options.narrative {
+        print_narrative_section(&rows, &mapping);
+    }
+
+    print_yearly_section(&rows, &mapping, options.top_n);
+
+    if let Some(grouping) = options.group_by {
+        print_grouped_section(&rows, &mapping, grouping);
+    }
+
+    if crate::overrides::load()?.values().any(|entry| !entry.tags.is_empty()) {
+        print_tags_section(&rows, &mapping);
+    }
+
+    if options.release_split {
+        print_release_split_section(&rows, &mapping);
+    }
+
+    if options.deltas {
+        print_delta_section(&rows, &mapping, options.top_n);
+    }
+
+    if options.forecast {
+        print_forecast_section(&rows, &mapping);
+    }
+
+    if options.milestones {
+        print_milestones_section(&rows, &mapping);
+    }
+
+    if let Some(profile_path) = &options.profile_path {
+        crate::badges::print_profile_section(profile_path)?;
+    }
+
+    if Path::new(&options.achievements_path).exists() {
+        print_achievements_section(&options.achievements_path, &mapping, options.top_n)?;
+    }
+
+    if Path::new(&options.unlocks_path).exists() {
+        print_unlock_timeline_section(&rows, &options.unlocks_path)?;
+    }
+
+    if Path::new(&options.reviews_path).exists() {
+        crate::reviews::print_reviews_section(&rows, &mapping, &options.reviews_path, options.top_n)?;
+    }
+
+    Ok(())
+}
+
+/// The report's rows, limited to `--filter-name` and `--months` when given.
+pub fn load_rows(inputs: &[String], options: &ReportOptions) -> Result<Vec<PlaytimeRow>> {
+    let mut rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+    if options.fold_demos {
+        rows = crate::consolidate::fold_related_apps(rows, &mapping);
+    }
+    if let Some(name_filter) = &options.name_filter {
+        rows = name_filter.apply(rows, &mapping);
+    }
+    Ok(match &options.months {
+        Some(months) => dataset::filter_months(rows, months),
+        None => rows,
+    })
+}
+
+pub fn game_name(mapping: &HashMap<String, String>, app_id: &str) -> String {
+    mapping
+        .get(app_id)
+        .cloned()
+        .unwrap_or_else(|| t!("report-app-fallback", app_id = app_id))
+}
+
+fn print_yearly_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>, top_n: usize) {
+    let monthly = analytics::monthly_by_game(rows);
+    for (year, games) in analytics::yearly_totals(rows) {
+        let total_hours: f64 = games.iter().map(|row| row.hours()).sum();
+        println!(
+            "\n{}",
+            t!("report-year-summary", year = year, hours = format!("{:.1}", total_hours), games = games.len())
+        );
+
+        for (rank, row) in games.iter().take(top_n).enumerate() {
+            let months = monthly.get(year).and_then(|games| games.get(row.app_id.as_str()));
+            let line = format!(
+                "  {:>2}. {:<40} {:>8.1}h  {}",
+                rank + 1,
+                game_name(mapping, &row.app_id),
+                row.hours(),
+                months.map(sparkline).unwrap_or_default()
+            );
+            println!("{}", line.trim_end());
+        }
+    }
+}
+
+fn print_grouped_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>, grouping: dataset::Grouping) {
+    let grouped = dataset::group_rows(rows.to_vec(), grouping);
+    let labels = grouping.labels();
+
+    // year -> period -> rows
+    let mut by_year: BTreeMap<&str, HashMap<&str, Vec<&PlaytimeRow>>> = BTreeMap::new();
+    for row in grouped.iter().filter(|row| labels.contains(&row.month.as_str())) {
+        by_year
+            .entry(row.year.as_str())
+            .or_default()
+            .entry(row.month.as_str())
+            .or_default()
+            .push(row);
+    }
+
+    let title = match grouping {
+        dataset::Grouping::Quarter => t!("report-hours-by-quarter"),
+        dataset::Grouping::Season { .. } => t!("report-hours-by-season"),
+    };
+    print_heading(&title);
+
+    for (year, periods) in by_year {
+        println!("\n{}", year);
+        for label in labels {
+            let Some(period_rows) = periods.get(label) else {
+                println!("  {:<9} {:>8}", i18n::period_name(label), "-");
+                continue;
+            };
+            let hours: f64 = period_rows.iter().map(|row| row.hours()).sum();
+            let top = period_rows.iter().max_by_key(|row| row.playtime_seconds);
+            print!("  {:<9} {:>7.1}h", i18n::period_name(label), hours);
+            if let Some(top) = top {
+                print!("  {}", top_label(mapping, top));
+            }
+            println!();
+        }
+    }
+}
+
+fn print_release_split_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>) {
+    let by_year = analytics::yearly_totals(rows);
+
+    // Release years come from the appdetails cache, fetching what's missing
+    let mut release_years: HashMap<&str, Option<i32>> = HashMap::new();
+    for row in by_year.values().flatten() {
+        release_years.entry(row.app_id.as_str()).or_insert_with(|| {
+            match crate::details::load(&row.app_id, false) {
+                Ok(entry) => crate::details::release_year(&entry),
+                Err(e) => {
+                    println!("  {}", t!("report-no-release-date", app_id = row.app_id.as_str(), error = format!("{:#}", e)));
+                    None
+                }
+            }
+        });
+    }
+
+    print_heading(&t!("report-release-split"));
+
+    for (year, games) in by_year {
+        let year_seconds: u64 = games.iter().map(|row| row.playtime_seconds).sum();
+
+        // [released that year, older, unknown]
+        let mut buckets: [Vec<&PlaytimeRow>; 3] = Default::default();
+        for row in games {
+            let bucket = match release_years[row.app_id.as_str()] {
+                Some(released) if released.to_string() == year => 0,
+                Some(_) => 1,
+                None => 2,
+            };
+            buckets[bucket].push(row);
+        }
+
+        println!("\n{}", year);
+        let labels = [t!("report-new-releases"), t!("report-back-catalog"), t!("report-unknown-release")];
+        for (label, bucket) in labels.iter().zip(&buckets) {
+            if bucket.is_empty() {
+                continue;
+            }
+            let seconds: u64 = bucket.iter().map(|row| row.playtime_seconds).sum();
+            print!(
+                "  {:<15} {:>7.1}h {:>5.1}%  {:>3} {}",
+                label,
+                seconds as f64 / 3600.0,
+                seconds as f64 / year_seconds.max(1) as f64 * 100.0,
+                bucket.len(),
+                t!("report-games", count = bucket.len())
+            );
+            if let Some(top) = bucket.iter().max_by_key(|row| row.playtime_seconds) {
+                print!("  {}", top_label(mapping, top));
+            }
+            println!();
+        }
+    }
+}
+
+fn print_forecast_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>) {
+    print_heading(&t!("report-forecast"));
+
+    let Some(forecast) = crate::forecast::forecast(rows, chrono::Local::now().date_naive()) else {
+        println!("{}", t!("report-forecast-none"));
+        return;
+    };
+
+    println!(
+        "{}",
+        t!("report-forecast-total", year = forecast.year, hours = format!("{:.0}", forecast.total_hours), years = forecast.years_used)
+    );
+    let mut busiest: Vec<(usize, f64)> = forecast.monthly_hours.iter().copied().enumerate().collect();
+    busiest.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let busiest: Vec<String> = busiest
+        .iter()
+        .take(3)
+        .map(|(index, hours)| format!("{} {:.0}h", i18n::month_name(*index), hours))
+        .collect();
+    println!("  {}", t!("report-forecast-busiest", months = busiest.join(", ")));
+    if let Some((app_id, hours)) = &forecast.top_game {
+        println!("  {}", t!("report-forecast-top", game = game_name(mapping, app_id), hours = format!("{:.0}", hours)));
+    }
+
+    if let Some(pace) = &forecast.pace {
+        println!(
+            "{}",
+            t!(
+                "report-forecast-pace",
+                year = pace.year,
+                hours = format!("{:.1}", pace.hours),
+                months = pace.months,
+                projected = format!("{:.0}", pace.projected)
+            )
+        );
+    }
+}
+
+/// Hours per `overrides.toml` tag each year; a game with several tags
+/// counts towards each.
+fn print_tags_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>) {
+    print_heading(&t!("report-tags"));
+
+    // year -> tag -> (seconds, games)
+    let mut by_year: BTreeMap<&str, HashMap<&str, (u64, usize)>> = BTreeMap::new();
+    let games = analytics::summarize(rows, mapping);
+    for game in &games {
+        for tag in crate::overrides::tags(&game.app_id) {
+            for (year, seconds) in &game.years {
+                let entry = by_year.entry(year.as_str()).or_default().entry(tag.as_str()).or_default();
+                entry.0 += seconds;
+                entry.1 += 1;
+            }
+        }
+    }
+
+    if by_year.is_empty() {
+        println!("  {}", t!("report-tags-none"));
+    }
+    for (year, tags) in by_year {
+        println!("\n{}", year);
+        let mut tags: Vec<(&str, (u64, usize))> = tags.into_iter().collect();
+        tags.sort_by_key(|&(tag, (seconds, _))| (std::cmp::Reverse(seconds), tag));
+        for (tag, (seconds, count)) in tags {
+            println!("  {:<30} {:>8.1}h  {}", tag, seconds as f64 / 3600.0, t!("report-tag-games", games = count));
+        }
+    }
+}
+
+fn print_narrative_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>) {
+    print_heading(&t!("report-narrative"));
+
+    for recap in crate::narrative::recaps(rows) {
+        println!();
+        for line in crate::narrative::wrap(&crate::narrative::paragraph(&recap, mapping), 76) {
+            println!("  {}", line);
+        }
+    }
+}
+
+fn print_milestones_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>) {
+    print_heading(&t!("report-milestones"));
+
+    let (milestones, has_lifetime) = crate::milestones::milestones(rows);
+    if milestones.is_empty() {
+        println!("{}", t!("report-milestones-none"));
+    }
+
+    let mut current_year = None;
+    for milestone in &milestones {
+        if current_year != Some(&milestone.year) {
+            println!("\n{}", milestone.year);
+            current_year = Some(&milestone.year);
+        }
+        let text = match &milestone.app_id {
+            Some(app_id) => t!("report-milestone-lifetime", game = game_name(mapping, app_id), hours = milestone.hours),
+            None => t!("report-milestone-year", hours = milestone.hours, year = milestone.year.as_str()),
+        };
+        println!("  {:<10} {}", i18n::month_name(milestone.month), text);
+    }
+
+    if !has_lifetime {
+        println!("\n{}", t!("report-milestones-no-lifetime"));
+    }
+}
+
+fn print_delta_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>, top_n: usize) {
+    print_heading(&t!("report-deltas"));
+
+    for (year, games) in analytics::monthly_by_game(rows) {
+        let mut overall = [0u64; 12];
+        for months in games.values() {
+            for (index, seconds) in months.iter().enumerate() {
+                overall[index] += seconds;
+            }
+        }
+
+        println!("\n{}", year);
+        println!("  {:<10} {:>9} {:>9} {:>8}", t!("column-month"), t!("column-hours"), t!("column-change"), "%");
+        for index in 0..12 {
+            let hours = overall[index] as f64 / 3600.0;
+            if index == 0 {
+                println!("  {:<10} {:>9.1} {:>9} {:>8}", i18n::month_name(index), hours, "-", "-");
+                continue;
+            }
+            let previous = overall[index - 1] as f64 / 3600.0;
+            println!(
+                "  {:<10} {:>9.1} {:>+9.1} {:>8}",
+                i18n::month_name(index),
+                hours,
+                hours - previous,
+                percent_change(previous, hours)
+            );
+        }
+
+        // (app_id, month index, change in hours, previous hours, hours)
+        let mut changes: Vec<(&str, usize, f64, f64, f64)> = Vec::new();
+        for (app_id, months) in &games {
+            for index in 1..12 {
+                let previous = months[index - 1] as f64 / 3600.0;
+                let hours = months[index] as f64 / 3600.0;
+                if hours != previous {
+                    changes.push((app_id, index, hours - previous, previous, hours));
+                }
+            }
+        }
+        changes.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        let print_change = |(app_id, index, change, previous, hours): &(&str, usize, f64, f64, f64)| {
+            println!(
+                "    {:<40} {:>3} -> {:<3} {:>+8.1}h {:>8}",
+                game_name(mapping, app_id),
+                i18n::month_abbreviation(index - 1, 3),
+                i18n::month_abbreviation(*index, 3),
+                change,
+                percent_change(*previous, *hours)
+            );
+        };
+
+        println!("  {}", t!("report-biggest-ramps"));
+        changes.iter().filter(|c| c.2 > 0.0).take(top_n).for_each(print_change);
+        println!("  {}", t!("report-biggest-drop-offs"));
+        changes.iter().rev().filter(|c| c.2 < 0.0).take(top_n).for_each(print_change);
+    }
+}
+
+fn percent_change(previous: f64, current: f64) -> String {
+    match (previous, current) {
+        (0.0, 0.0) => "-".to_string(),
+        (0.0, _) => t!("report-new"),
+        _ => format!("{:+.0}%", (current - previous) / previous * 100.0),
+    }
+}
+
+fn print_achievements_section(path: &str, mapping: &HashMap<String, String>, top_n: usize) -> Result<()> {
+    let content = std::fs::read_to_string(path)?;
+
+    // (app_id, achieved, total, completion)
+    let mut entries: Vec<(String, u64, u64, f64)> = content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = dataset::parse_csv_line(line);
+            Some((
+                fields.get(1)?.clone(),
+                fields.get(2)?.parse().ok()?,
+                fields.get(3)?.parse().ok()?,
+                fields.get(4)?.parse().ok()?,
+            ))
+        })
+        .filter(|entry| entry.1 < entry.2)
+        .collect();
+
+    entries.sort_by(|a, b| b.3.total_cmp(&a.3));
+
+    print_heading(&t!("report-achievements"));
+
+    if entries.is_empty() {
+        println!("  {}", t!("report-nothing-left"));
+    }
+
+    for (app_id, achieved, total, completion) in entries.iter().take(top_n) {
+        println!(
+            "  {:<40} {:>4}/{:<4} ({:.1}%)",
+            game_name(mapping, app_id),
+            achieved,
+            total,
+            completion
+        );
+    }
+
+    Ok(())
+}
+
+fn print_unlock_timeline_section(rows: &[PlaytimeRow], path: &str) -> Result<()> {
+    let content = std::fs::read_to_string(path)?;
+
+    // (year, month index) -> unlock count
+    let mut unlocks: HashMap<(String, usize), u64> = HashMap::new();
+    for line in content.lines().skip(1) {
+        let fields = dataset::parse_csv_line(line);
+        let Some(timestamp) = fields.get(3).and_then(|v| v.parse::<i64>().ok()) else {
+            continue;
+        };
+        if let Some(datetime) = chrono::DateTime::from_timestamp(timestamp, 0) {
+            let key = (datetime.year().to_string(), datetime.month0() as usize);
+            *unlocks.entry(key).or_default() += 1;
+        }
+    }
+
+    // (year, month index) -> playtime seconds, from the monthly rows
+    let mut playtime: BTreeMap<(String, usize), u64> = BTreeMap::new();
+    for row in rows {
+        if let index @ 1..=12 = dataset::month_order(&row.month) {
+            *playtime.entry((row.year.clone(), index - 1)).or_default() += row.playtime_seconds;
+        }
+    }
+
+    let mut years: Vec<&String> = playtime.keys().map(|(year, _)| year).collect();
+    years.dedup();
+
+    print_heading(&t!("report-timeline"));
+
+    // (label, unlocks per hour)
+    let mut best: Option<(String, f64)> = None;
+
+    for year in years {
+        println!("\n{}", year);
+        println!(
+            "  {:<10} {:>8} {:>9} {:>9}",
+            t!("column-month"),
+            t!("column-unlocks"),
+            t!("column-hours"),
+            t!("column-per-hour")
+        );
+
+        for index in 0..12 {
+            let key = (year.to_string(), index);
+            let count = unlocks.get(&key).copied().unwrap_or(0);
+            let hours = playtime.get(&key).copied().unwrap_or(0) as f64 / 3600.0;
+            if count == 0 && hours == 0.0 {
+                continue;
+            }
+
+            let month_name = i18n::month_name(index);
+            if hours > 0.0 {
+                let rate = count as f64 / hours;
+                println!("  {:<10} {:>8} {:>9.1} {:>9.2}", month_name, count, hours, rate);
+                if count > 0 && best.as_ref().is_none_or(|(_, best_rate)| rate > *best_rate) {
+                    best = Some((format!("{} {}", month_name, year), rate));
+                }
+            } else {
+                println!("  {:<10} {:>8} {:>9.1} {:>9}", month_name, count, hours, "-");
+            }
+        }
+    }
+
+    if let Some((label, rate)) = best {
+        println!("\n{}", t!("report-most-productive", month = label, rate = format!("{:.2}", rate)));
+    }
+
+    Ok(())
+}
+
+/// A section title, underlined.
+pub fn print_heading(title: &str) {
+    println!("\n{}", title);
+    println!("{}", "-".repeat(title.chars().count()));
+}
+
+/// Twelve block characters tracing a year's months, scaled to its busiest
+/// month; months without playtime are blank.
+pub fn sparkline(months: &[u64; 12]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = months.iter().copied().max().unwrap_or(0).max(1);
+    months
+        .iter()
+        .map(|&seconds| match seconds {
+            0 => ' ',
+            _ => BLOCKS[((seconds * 8).div_ceil(max) as usize).clamp(1, 8) - 1],
+        })
+        .collect()
+}
+
+/// "top: <game> (<hours>h)" for a period's most played game.
+fn top_label(mapping: &HashMap<String, String>, row: &PlaytimeRow) -> String {
+    t!("report-top", game = game_name(mapping, &row.app_id), hours = format!("{:.1}", row.hours()))
+}
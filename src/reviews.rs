@@ -0,0 +1,238 @@
+//! `reviews`: the reviews written on a community profile, scraped from its
+//! public "Recommended" pages into `steam_reviews.csv`, and the report
+//! section joining them to the dataset: which of the most played games were
+//! reviewed, the verdicts, and the hours at review time against now.
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::OnceLock;
+
+use crate::analytics;
+use crate::dataset::{self, PlaytimeRow};
+use crate::i18n::t;
+use crate::report::{game_name, print_heading};
+
+pub const REVIEWS_FILENAME: &str = "steam_reviews.csv";
+/// Stops a profile whose pages never run out from looping forever
+const MAX_PAGES: usize = 200;
+
+pub struct Review {
+    pub app_id: String,
+    pub recommended: bool,
+    pub hours_at_review: Option<f64>,
+    pub hours_on_record: Option<f64>,
+    /// As Steam shows it, e.g. "3 March, 2023"
+    pub posted: String,
+}
+
+pub fn scrape_reviews(profile: &str) -> Result<()> {
+    let profile_url = crate::community::build_profile_url(profile);
+    println!("Fetching reviews for: {}", profile_url);
+
+    let mut reviews: Vec<Review> = Vec::new();
+    for page in 1..=MAX_PAGES {
+        let url = format!("{}/recommended/?p={}&l=english", profile_url, page);
+        let html_content = crate::http::get(&url)
+            .with_context(|| format!("Failed to fetch reviews page {}", page))?
+            .text()
+            .context("Failed to read response body")?;
+
+        let found = parse_reviews_page(&html_content);
+        // Past the last page Steam serves an empty list (or the last page again)
+        let new: Vec<Review> = found
+            .into_iter()
+            .filter(|review| !reviews.iter().any(|seen| seen.app_id == review.app_id))
+            .collect();
+        if new.is_empty() {
+            if page == 1 && !html_content.contains("review_box") && html_content.contains("private_profile") {
+                bail!("The profile is private; its reviews can't be read");
+            }
+            break;
+        }
+        println!("  Page {}: {} reviews", page, new.len());
+        reviews.extend(new);
+    }
+
+    let recommended = reviews.iter().filter(|review| review.recommended).count();
+    println!("  Found {} reviews ({} recommended)", reviews.len(), recommended);
+    crate::summary::count("reviews", reviews.len() as u64);
+
+    let mut csv_content = String::from("app_id,recommended,hours_at_review,hours_on_record,posted\n");
+    for review in &reviews {
+        csv_content.push_str(&format!(
+            "{},{},{},{},{}\n",
+            review.app_id,
+            review.recommended,
+            review.hours_at_review.map(|hours| hours.to_string()).unwrap_or_default(),
+            review.hours_on_record.map(|hours| hours.to_string()).unwrap_or_default(),
+            dataset::escape_csv_field(&review.posted),
+        ));
+    }
+
+    fs::write(REVIEWS_FILENAME, csv_content)
+        .context("Failed to write reviews file")?;
+
+    crate::summary::file_written(REVIEWS_FILENAME);
+    println!("\nReviews saved to: {}", REVIEWS_FILENAME);
+
+    Ok(())
+}
+
+fn parse_reviews_page(html_content: &str) -> Vec<Review> {
+    let document = Html::parse_document(html_content);
+    let box_selector = Selector::parse(".review_box").expect("Failed to create selector");
+
+    document
+        .select(&box_selector)
+        .filter_map(|review_box| {
+            let app_id = review_app_id(&review_box)?;
+            let title = element_text(&review_box, ".title").unwrap_or_default();
+            let thumb = element_attr(&review_box, ".thumb img", "src").unwrap_or_default();
+            let recommended = if title.is_empty() {
+                thumb.contains("thumbsUp")
+            } else {
+                !title.to_lowercase().starts_with("not")
+            };
+
+            // "85.3 hrs on record (40.2 hrs at review time)"; the part in
+            // brackets only appears once the game was played past the review
+            let hours = element_text(&review_box, ".hours").unwrap_or_default();
+            let hours_on_record = capture_hours(on_record_pattern(), &hours);
+            let hours_at_review = capture_hours(at_review_pattern(), &hours).or(hours_on_record);
+
+            // The first text node; an edit date follows in a nested element
+            let posted_selector = Selector::parse(".posted").expect("Failed to create selector");
+            let posted = review_box
+                .select(&posted_selector)
+                .next()
+                .and_then(|posted| posted.text().next())
+                .map(|text| text.trim().trim_start_matches("Posted").trim().trim_end_matches('.').to_string())
+                .unwrap_or_default();
+
+            Some(Review {
+                app_id,
+                recommended,
+                hours_at_review,
+                hours_on_record,
+                posted,
+            })
+        })
+        .collect()
+}
+
+/// From the game's store or community link, else the review's own
+/// `/recommended/<appid>` link.
+fn review_app_id(review_box: &ElementRef) -> Option<String> {
+    let link_selector = Selector::parse("a[href]").expect("Failed to create selector");
+    review_box
+        .select(&link_selector)
+        .filter_map(|link| link.value().attr("href"))
+        .find_map(|href| app_link_pattern().captures(href))
+        .map(|captures| captures[1].to_string())
+}
+
+fn element_text(element: &ElementRef, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).expect("Failed to create selector");
+    let text = element.select(&selector).next()?.text().collect::<String>();
+    Some(text.trim().to_string())
+}
+
+fn element_attr(element: &ElementRef, selector: &str, attr: &str) -> Option<String> {
+    let selector = Selector::parse(selector).expect("Failed to create selector");
+    element.select(&selector).next()?.value().attr(attr).map(str::to_string)
+}
+
+fn capture_hours(pattern: &Regex, text: &str) -> Option<f64> {
+    pattern.captures(text)?[1].replace(',', "").parse().ok()
+}
+
+fn app_link_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"/(?:app|recommended)/(\d+)").expect("the app link pattern is valid"))
+}
+
+fn on_record_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"([\d,.]+)\s*hrs? on record").expect("the on-record pattern is valid"))
+}
+
+fn at_review_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"([\d,.]+)\s*hrs? at review time").expect("the at-review pattern is valid"))
+}
+
+pub fn load_reviews(path: &str) -> Result<Vec<Review>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path))?;
+
+    Ok(content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = dataset::parse_csv_line(line);
+            Some(Review {
+                app_id: fields.first()?.clone(),
+                recommended: fields.get(1)?.parse().ok()?,
+                hours_at_review: fields.get(2).and_then(|v| v.parse().ok()),
+                hours_on_record: fields.get(3).and_then(|v| v.parse().ok()),
+                posted: fields.get(4).cloned().unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+/// Prints the reviews section of the report: the `top_n` most played games
+/// and what was said about each, with hours now being the larger of the
+/// dataset's playtime and the review page's hours on record.
+pub fn print_reviews_section(rows: &[PlaytimeRow], mapping: &HashMap<String, String>, path: &str, top_n: usize) -> Result<()> {
+    let reviews = load_reviews(path)?;
+    let by_app: HashMap<&str, &Review> = reviews.iter().map(|review| (review.app_id.as_str(), review)).collect();
+
+    let mut games = analytics::summarize(rows, mapping);
+    games.sort_by(|a, b| b.playtime_seconds.cmp(&a.playtime_seconds).then(a.app_id.cmp(&b.app_id)));
+    games.truncate(top_n);
+
+    print_heading(&t!("report-reviews"));
+
+    let reviewed: Vec<&Review> = games.iter().filter_map(|game| by_app.get(game.app_id.as_str()).copied()).collect();
+    let recommended = reviewed.iter().filter(|review| review.recommended).count();
+    println!(
+        "  {}",
+        t!("report-reviews-summary", reviewed = reviewed.len(), games = games.len(), recommended = recommended)
+    );
+
+    for game in &games {
+        let hours = game.playtime_seconds as f64 / 3600.0;
+        match by_app.get(game.app_id.as_str()) {
+            Some(review) => {
+                let now = review.hours_on_record.unwrap_or(0.0).max(hours);
+                let verdict = if review.recommended {
+                    t!("report-review-recommended")
+                } else {
+                    t!("report-review-not-recommended")
+                };
+                let at_review = review
+                    .hours_at_review
+                    .map(|at_review| format!("{:.1}h", at_review))
+                    .unwrap_or_else(|| "?".to_string());
+                println!(
+                    "  {:<40} {:<16} {}",
+                    game_name(mapping, &game.app_id),
+                    verdict,
+                    t!("report-review-hours", at_review = at_review, now = format!("{:.1}h", now))
+                );
+            }
+            None => println!(
+                "  {:<40} {:<16} {:.1}h",
+                game_name(mapping, &game.app_id),
+                t!("report-review-none"),
+                hours
+            ),
+        }
+    }
+
+    Ok(())
+}
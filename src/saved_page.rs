@@ -0,0 +1,41 @@
+//! Page sources kept by `scrape --keep-html` (gzip-compressed, next to the
+//! JSON with the same name), and read back by `scrape --from-html` so old
+//! captures can be re-extracted after the parser improves.
+
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+
+pub fn write(path: &str, html: &str) -> Result<()> {
+    let file = File::create(path)
+        .with_context(|| format!("Failed to create {}", path))?;
+
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    encoder
+        .write_all(html.as_bytes())
+        .and_then(|_| encoder.finish().map(|_| ()))
+        .with_context(|| format!("Failed to write {}", path))?;
+
+    Ok(())
+}
+
+/// Reads a saved page; `.gz` files are decompressed, anything else is read
+/// as plain HTML (e.g. a page saved from the browser).
+pub fn read(path: &str) -> Result<String> {
+    if !path.ends_with(".gz") {
+        return fs::read_to_string(path).with_context(|| format!("Failed to read {}", path));
+    }
+
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path))?;
+
+    let mut html = String::new();
+    GzDecoder::new(file)
+        .read_to_string(&mut html)
+        .with_context(|| format!("Failed to decompress {}", path))?;
+
+    Ok(html)
+}
@@ -0,0 +1,40 @@
+//! JSON Schema for the files the tool writes, generated from the same types
+//! that read them so the schema can't drift from the code.
+
+use anyhow::{Context, Result, bail};
+use std::fs;
+
+use crate::dataset::PlaytimeRow;
+use crate::master::Master;
+use crate::replay::ReplayFile;
+
+/// Writes the schema for `kind` to `output`, or to a default file name when no
+/// output is given. `-` prints it instead.
+pub fn write_schema(kind: &str, output: Option<&str>) -> Result<()> {
+    let (mut schema, default_filename) = match kind {
+        // steam_replay_<steamid>_<year>.json
+        "replay" => (schemars::schema_for!(ReplayFile), "steam_replay.schema.json"),
+        // steam_replay_data.csv, with each CSV row as an object
+        "dataset" => (schemars::schema_for!(Vec<PlaytimeRow>), "steam_replay_data.schema.json"),
+        // merge --output master.json
+        "master" => (schemars::schema_for!(Master), "master.schema.json"),
+        other => bail!("Unknown schema '{}' (expected replay, dataset or master)", other),
+    };
+
+    schema.insert("title".to_string(), format!("steamreplay {}", kind).into());
+
+    let content = serde_json::to_string_pretty(&schema)
+        .context("Failed to serialize schema")?;
+
+    match output.unwrap_or(default_filename) {
+        "-" => println!("{}", content),
+        path => {
+            fs::write(path, content)
+                .with_context(|| format!("Failed to write {}", path))?;
+            crate::summary::file_written(path);
+            println!("Schema saved to: {}", path);
+        }
+    }
+
+    Ok(())
+}
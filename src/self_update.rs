@@ -0,0 +1,206 @@
+//! `self-update`: replaces the running binary with the latest GitHub release,
+//! for machines without a Rust toolchain.
+//!
+//! Each release carries one raw binary per platform, named
+//! `steamreplay-<arch>-<os>` (`.exe` on Windows, e.g.
+//! `steamreplay-x86_64-linux`), and a `SHA256SUMS` file in `sha256sum`
+//! format. The download is only installed when its SHA-256 matches that
+//! list. Releases aren't signed, so this catches corrupt or truncated
+//! downloads rather than a tampered release.
+//!
+//! `STEAMREPLAY_RELEASES_URL` points the check at another releases API
+//! (a mirror or a fork) with the same layout.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RELEASES_URL: &str = "https://api.github.com/repos/nithishbn/steamreplay/releases";
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+pub struct UpdateOptions {
+    /// Only report whether an update is available
+    pub check: bool,
+    /// Install a specific release tag instead of the latest
+    pub version: Option<String>,
+    /// Reinstall even when already on that version
+    pub force: bool,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+pub fn self_update(options: &UpdateOptions) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let base = std::env::var("STEAMREPLAY_RELEASES_URL").unwrap_or_else(|_| RELEASES_URL.to_string());
+    let url = match &options.version {
+        Some(tag) => format!("{}/tags/{}", base.trim_end_matches('/'), tag),
+        None => format!("{}/latest", base.trim_end_matches('/')),
+    };
+
+    println!("Checking for releases...");
+    let release: Release = crate::http::send(
+        crate::http::client().get(&url).header(reqwest::header::ACCEPT, "application/vnd.github+json"),
+    )
+    .and_then(|response| Ok(response.error_for_status()?.json()?))
+    .with_context(|| format!("Failed to fetch the release from {}", url))?;
+
+    let available = release.tag_name.trim_start_matches('v');
+    println!("Installed: {}, release: {}", current, available);
+
+    if options.version.is_none() && !options.force && version_key(available) <= version_key(current) {
+        println!("Already up to date");
+        return Ok(());
+    }
+    if options.check {
+        println!("Update available; run 'self-update' to install it");
+        return Ok(());
+    }
+    if available == current && !options.force {
+        println!("Already on {}; add --force to reinstall it", current);
+        return Ok(());
+    }
+
+    let asset_name = asset_name();
+    let Some(asset) = release.assets.iter().find(|asset| asset.name == asset_name) else {
+        bail!("Release {} has no binary for this platform ({})", release.tag_name, asset_name);
+    };
+    let Some(checksums) = release.assets.iter().find(|asset| asset.name == CHECKSUMS_ASSET) else {
+        bail!("Release {} has no {}; refusing to install an unverified binary", release.tag_name, CHECKSUMS_ASSET);
+    };
+
+    println!("Downloading {}...", asset.name);
+    let checksums = download(&checksums.browser_download_url)?;
+    let binary = download(&asset.browser_download_url)?;
+
+    let checksums = String::from_utf8_lossy(&checksums);
+    let Some(expected) = expected_checksum(&checksums, &asset.name) else {
+        bail!("{} doesn't list {}", CHECKSUMS_ASSET, asset.name);
+    };
+    let actual = crate::provenance::sha256_hex(&binary);
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!("Checksum mismatch for {}: expected {}, got {}", asset.name, expected, actual);
+    }
+    println!("Checksum verified ({})", actual);
+
+    let exe = std::env::current_exe().context("Could not locate the running executable")?;
+    let exe = fs::canonicalize(&exe).unwrap_or(exe);
+    replace_executable(&exe, &binary)?;
+
+    println!("Updated {} from {} to {}", exe.display(), current, available);
+
+    Ok(())
+}
+
+/// The release asset for this platform.
+fn asset_name() -> String {
+    format!(
+        "steamreplay-{}-{}{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    let bytes = crate::http::get(url)
+        .and_then(|response| Ok(response.error_for_status()?.bytes()?))
+        .with_context(|| format!("Failed to download {}", url))?;
+    Ok(bytes.to_vec())
+}
+
+/// The hash `sha256sum` output lists for `name` (`<hash>  <name>`, with a
+/// `*` before binary-mode names).
+fn expected_checksum<'a>(checksums: &'a str, name: &str) -> Option<&'a str> {
+    checksums.lines().find_map(|line| {
+        let (hash, file) = line.trim().split_once(char::is_whitespace)?;
+        (file.trim().trim_start_matches('*') == name).then_some(hash)
+    })
+}
+
+/// Numeric components of a version, ignoring any pre-release suffix.
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Writes the new binary next to the old one and renames it into place, so
+/// an interrupted update leaves the old binary working. Windows can't
+/// replace a running executable, so there the old one is moved aside first.
+fn replace_executable(exe: &Path, binary: &[u8]) -> Result<()> {
+    let staged = sibling(exe, "new");
+    fs::write(&staged, binary).with_context(|| format!("Failed to write {}", staged.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+
+    #[cfg(windows)]
+    {
+        let old = sibling(exe, "old");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old).with_context(|| format!("Failed to move {} aside", exe.display()))?;
+    }
+
+    fs::rename(&staged, exe).with_context(|| format!("Failed to replace {}", exe.display()))
+}
+
+fn sibling(exe: &Path, suffix: &str) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", suffix));
+    exe.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKSUMS: &str = "\
+0f1e2d3c  steamreplay-x86_64-unknown-linux-gnu.tar.gz
+a1b2c3d4 *steamreplay-x86_64-pc-windows-msvc.zip
+";
+
+    #[test]
+    fn finds_the_checksum_for_an_asset() {
+        assert_eq!(expected_checksum(CHECKSUMS, "steamreplay-x86_64-unknown-linux-gnu.tar.gz"), Some("0f1e2d3c"));
+        assert_eq!(expected_checksum(CHECKSUMS, "steamreplay-x86_64-pc-windows-msvc.zip"), Some("a1b2c3d4"));
+    }
+
+    #[test]
+    fn has_no_checksum_for_unlisted_assets() {
+        assert_eq!(expected_checksum(CHECKSUMS, "steamreplay-aarch64-apple-darwin.tar.gz"), None);
+        assert_eq!(expected_checksum(CHECKSUMS, "steamreplay"), None);
+        assert_eq!(expected_checksum("", "steamreplay-x86_64-unknown-linux-gnu.tar.gz"), None);
+    }
+
+    #[test]
+    fn orders_versions_numerically() {
+        assert!(version_key("0.10.0") > version_key("0.9.3"));
+        assert!(version_key("1.0.0") > version_key("0.99.99"));
+        assert_eq!(version_key("1.2.3"), [1, 2, 3]);
+    }
+
+    #[test]
+    fn ignores_pre_release_and_build_suffixes() {
+        assert_eq!(version_key("1.2.0-rc.1"), version_key("1.2.0"));
+        assert_eq!(version_key("1.2.0+build.5"), [1, 2, 0]);
+    }
+}
@@ -0,0 +1,159 @@
+//! `report --serve`: hosts the HTML report on localhost and opens it in the
+//! browser. The page polls `/version`; when an input, the mapping or an
+//! achievements file changes, the report is rebuilt and the page reloads.
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use crate::html_report;
+use crate::report::ReportOptions;
+
+pub const DEFAULT_PORT: u16 = 8750;
+/// Requests are answered one at a time, so a client that stalls can hold
+/// up the page for at most this long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Appended to the page: reloads it when the server reports a new version.
+const RELOAD_SCRIPT: &str = "<script>\
+const version = \"{version}\";\
+setInterval(async () => {\
+  try {\
+    const response = await fetch(\"/version\", { cache: \"no-store\" });\
+    if ((await response.text()) !== version) location.reload();\
+  } catch (e) {}\
+}, 1500);\
+</script>";
+
+struct Site {
+    page: String,
+    version: u64,
+    /// Modification times of the watched files when the page was built
+    stamps: Vec<Option<SystemTime>>,
+}
+
+pub fn serve_report(inputs: &[String], options: &ReportOptions, port: u16, open: bool) -> Result<()> {
+    let watched: Vec<&str> = inputs
+        .iter()
+        .map(|s| s.as_str())
+        .chain([
+            options.mapping_path.as_str(),
+            options.achievements_path.as_str(),
+            options.unlocks_path.as_str(),
+        ])
+        .collect();
+
+    let mut site = Site {
+        page: String::new(),
+        version: 0,
+        stamps: Vec::new(),
+    };
+    refresh(&mut site, &watched, inputs, options);
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("Failed to listen on port {}; pick another with --port", port))?;
+    let url = format!("http://{}/", listener.local_addr()?);
+
+    println!("Serving the report at {}", url);
+    println!("Watching {} file(s) for changes; press Ctrl+C to stop", watched.len());
+
+    if open {
+        open_browser(&url);
+    }
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("Warning: Connection failed: {}", e);
+                continue;
+            }
+        };
+        refresh(&mut site, &watched, inputs, options);
+        if let Err(e) = respond(stream, &site) {
+            println!("Warning: Failed to answer a request: {:#}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the page when any watched file changed since the last build.
+/// Build errors (say, a CSV caught mid-write) are shown on the page.
+fn refresh(site: &mut Site, watched: &[&str], inputs: &[String], options: &ReportOptions) {
+    let stamps: Vec<Option<SystemTime>> = watched
+        .iter()
+        .map(|path| Path::new(path).metadata().and_then(|m| m.modified()).ok())
+        .collect();
+    if site.version > 0 && stamps == site.stamps {
+        return;
+    }
+
+    site.version += 1;
+    site.stamps = stamps;
+    site.page = match html_report::render(inputs, options) {
+        Ok(page) => page,
+        Err(e) => format!(
+            "<!DOCTYPE html>\n<html><body><h1>Report failed</h1><pre>{}</pre></body></html>\n",
+            html_report::escape(&format!("{:#}", e))
+        ),
+    };
+    let script = RELOAD_SCRIPT.replace("{version}", &site.version.to_string());
+    site.page = match site.page.rfind("</body>") {
+        Some(index) => format!("{}{}{}", &site.page[..index], script, &site.page[index..]),
+        None => format!("{}{}", site.page, script),
+    };
+
+    if site.version > 1 {
+        println!("Rebuilt the report ({})", chrono::Local::now().format("%H:%M:%S"));
+    }
+}
+
+fn respond(mut stream: TcpStream, site: &Site) -> Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers; nothing in them matters here
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (status, content_type, body) = match path {
+        "/" | "/index.html" => ("200 OK", "text/html; charset=utf-8", site.page.clone()),
+        "/version" => ("200 OK", "text/plain", site.version.to_string()),
+        _ => ("404 Not Found", "text/plain", "Not found".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+
+    Ok(())
+}
+
+fn open_browser(url: &str) {
+    let result = if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).spawn()
+    } else if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", "start", "", url]).spawn()
+    } else {
+        Command::new("xdg-open").arg(url).spawn()
+    };
+
+    if let Err(e) = result {
+        println!("Could not open a browser ({}); open {} yourself", e, url);
+    }
+}
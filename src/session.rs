@@ -0,0 +1,160 @@
+//! Persisted Steam login session (obtained through `auth login` or
+//! `auth import-cookies`), stored encrypted as session.enc in the config
+//! directory. The AES-256-GCM key lives in the OS keyring, falling back to a
+//! user-only key file when no keyring is available.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result, anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+const KEYRING_SERVICE: &str = "steamreplay";
+const KEYRING_SESSION_KEY_USER: &str = "session-encryption-key";
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub steam_id: String,
+    pub account_name: Option<String>,
+    pub refresh_token: Option<String>,
+    /// Ready-to-send value of the `steamLoginSecure` cookie
+    pub steam_login_secure: String,
+    pub saved_at: String,
+}
+
+pub fn session_path() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("session.enc"))
+}
+
+pub fn load() -> Result<Option<Session>> {
+    let path = session_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let data = fs::read(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if data.len() < NONCE_LEN {
+        bail!("{} is truncated", path.display());
+    }
+
+    let Some(key) = load_key(false)? else {
+        bail!("The session encryption key is missing; run 'auth login' again");
+    };
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let plaintext = Aes256Gcm::new(&key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("Failed to decrypt {}; run 'auth login' again", path.display()))?;
+
+    let session = serde_json::from_slice(&plaintext)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    Ok(Some(session))
+}
+
+pub fn save(session: &Session) -> Result<PathBuf> {
+    let path = session_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let key = load_key(true)?.context("Failed to create a session encryption key")?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(&key)
+        .encrypt(&nonce, serde_json::to_vec(session)?.as_slice())
+        .map_err(|_| anyhow!("Failed to encrypt the session"))?;
+
+    let mut data = nonce.to_vec();
+    data.extend_from_slice(&ciphertext);
+    write_private(&path, &data)?;
+
+    Ok(path)
+}
+
+/// Loads the encryption key, generating and storing a new one if `create` is set.
+fn load_key(create: bool) -> Result<Option<Key<Aes256Gcm>>> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_SESSION_KEY_USER).ok();
+    let key_file = config::config_dir()?.join("session.key");
+
+    let stored = match entry.as_ref().map(|e| e.get_password()) {
+        Some(Ok(encoded)) => Some(encoded),
+        _ if key_file.exists() => Some(fs::read_to_string(&key_file)?.trim().to_string()),
+        _ => None,
+    };
+
+    if let Some(encoded) = stored {
+        let bytes = STANDARD.decode(encoded).context("Session encryption key is corrupt")?;
+        if bytes.len() != 32 {
+            bail!("Session encryption key has the wrong length");
+        }
+        return Ok(Some(*Key::<Aes256Gcm>::from_slice(&bytes)));
+    }
+
+    if !create {
+        return Ok(None);
+    }
+
+    let key = Aes256Gcm::generate_key(OsRng);
+    let encoded = STANDARD.encode(key);
+
+    let stored_in_keyring = entry.is_some_and(|e| e.set_password(&encoded).is_ok());
+    if !stored_in_keyring {
+        write_private(&key_file, encoded.as_bytes())?;
+    }
+
+    Ok(Some(key))
+}
+
+/// Session tokens are as good as a password, so the file is only ever
+/// readable by the user: created with mode 0600, and narrowed to it when an
+/// older file had wider permissions.
+fn write_private(path: &Path, data: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        if path.exists() {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    file.write_all(data)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn writes_private_files_user_only() {
+        let path = std::env::temp_dir().join(format!("steamreplay-session-test-{}", std::process::id()));
+        fs::write(&path, "older, world-readable").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, b"secret").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        let content = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(mode, 0o600);
+        assert_eq!(content, b"secret");
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::{Context, Result};
+use scraper::{Html, Selector};
+use std::fs;
+
+/// Downloads the replay's generated share image, advertised through the
+/// page's Open Graph / Twitter card metadata. Returns the written filename.
+pub fn download_share_image(document: &Html, output_stem: &str) -> Result<Option<String>> {
+    let Some(image_url) = find_share_image_url(document) else {
+        return Ok(None);
+    };
+
+    println!("Downloading share image: {}", image_url);
+
+    let response = crate::http::get(&image_url)
+        .context("Failed to fetch the share image")?
+        .error_for_status()
+        .context("Share image request failed")?;
+
+    let extension = match response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(content_type) if content_type.contains("jpeg") => "jpg",
+        Some(content_type) if content_type.contains("webp") => "webp",
+        Some(content_type) if content_type.contains("gif") => "gif",
+        _ => "png",
+    };
+
+    let bytes = response.bytes()
+        .context("Failed to read share image body")?;
+
+    let filename = format!("{}_share.{}", output_stem, extension);
+    fs::write(&filename, &bytes)
+        .context("Failed to write share image")?;
+
+    Ok(Some(filename))
+}
+
+fn find_share_image_url(document: &Html) -> Option<String> {
+    let selectors = [
+        "meta[property='og:image']",
+        "meta[name='twitter:image']",
+        "link[rel='image_src']",
+    ];
+
+    for selector in selectors {
+        let selector = Selector::parse(selector).expect("Failed to create selector");
+        if let Some(element) = document.select(&selector).next() {
+            let url = element.value().attr("content").or_else(|| element.value().attr("href"));
+            if let Some(url) = url.filter(|u| !u.is_empty()) {
+                return Some(url.to_string());
+            }
+        }
+    }
+
+    None
+}
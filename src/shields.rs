@@ -0,0 +1,183 @@
+//! `badge`: stat badges for a GitHub profile README. Each badge is written
+//! twice into the output directory: as shields.io endpoint JSON (`<name>.json`,
+//! for `https://img.shields.io/endpoint?url=...` once committed somewhere
+//! public) and as a standalone flat-style SVG (`<name>.svg`) to embed
+//! directly. Rerun after each refresh of the dataset to keep them current.
+//!
+//! Badges, for the chosen year (the latest by default):
+//! - `steam_hours`: "steam 2024 | 1,234 hrs"
+//! - `steam_games`: "games played 2024 | 37"
+//! - `steam_top_game`: "top game 2024 | Factorio"
+
+use anyhow::{Context, Result, bail};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+use crate::analytics;
+use crate::dataset;
+use crate::html_report::escape;
+
+pub const DEFAULT_OUTPUT_DIR: &str = "badges";
+/// Steam's navy, for the value side
+const COLOR: &str = "1b2838";
+const LABEL_COLOR: &str = "555";
+
+pub struct BadgeOptions {
+    pub mapping_path: String,
+    pub output_dir: String,
+    /// Defaults to the latest year in the dataset
+    pub year: Option<String>,
+}
+
+struct Badge {
+    name: &'static str,
+    label: String,
+    message: String,
+}
+
+pub fn write_badges(inputs: &[String], options: &BadgeOptions) -> Result<()> {
+    let rows = dataset::load_rows(inputs)?;
+    let mapping = dataset::load_mapping(&options.mapping_path)?;
+    let games = analytics::summarize(&rows, &mapping);
+
+    let year = match &options.year {
+        Some(year) => year.clone(),
+        None => match games
+            .iter()
+            .flat_map(|game| game.years.iter().map(|(year, _)| year))
+            .filter(|year| year.parse::<i32>().is_ok())
+            .max()
+        {
+            Some(year) => year.clone(),
+            None => bail!("No replay years in the dataset to make badges for"),
+        },
+    };
+
+    // (app_id, seconds) for the year, most played first
+    let mut played: Vec<(&str, u64)> = games
+        .iter()
+        .filter_map(|game| game.years.iter().find(|(y, _)| *y == year).map(|(_, seconds)| (game.app_id.as_str(), *seconds)))
+        .collect();
+    if played.is_empty() {
+        bail!("No playtime in {} in the dataset", year);
+    }
+    played.sort_by_key(|&(app_id, seconds)| (std::cmp::Reverse(seconds), app_id));
+    let seconds: u64 = played.iter().map(|(_, seconds)| seconds).sum();
+
+    let badges = [
+        Badge {
+            name: "steam_hours",
+            label: format!("steam {}", year),
+            message: format!("{} hrs", thousands((seconds as f64 / 3600.0).round() as u64)),
+        },
+        Badge {
+            name: "steam_games",
+            label: format!("games played {}", year),
+            message: thousands(played.len() as u64),
+        },
+        Badge {
+            name: "steam_top_game",
+            label: format!("top game {}", year),
+            message: crate::report::game_name(&mapping, played[0].0),
+        },
+    ];
+
+    let output_dir = Path::new(&options.output_dir);
+    fs::create_dir_all(output_dir).with_context(|| format!("Failed to create {}", output_dir.display()))?;
+
+    for badge in &badges {
+        let endpoint = json!({
+            "schemaVersion": 1,
+            "label": badge.label,
+            "message": badge.message,
+            "color": COLOR,
+            "namedLogo": "steam",
+        });
+        let json_path = output_dir.join(format!("{}.json", badge.name));
+        fs::write(&json_path, serde_json::to_string_pretty(&endpoint)?)
+            .with_context(|| format!("Failed to write {}", json_path.display()))?;
+        crate::summary::file_written(&json_path);
+
+        let svg_path = output_dir.join(format!("{}.svg", badge.name));
+        fs::write(&svg_path, render_svg(&badge.label, &badge.message))
+            .with_context(|| format!("Failed to write {}", svg_path.display()))?;
+        crate::summary::file_written(&svg_path);
+
+        println!("{}: {} | {}", badge.name, badge.label, badge.message);
+    }
+
+    println!("\nBadges saved to: {}", output_dir.display());
+    println!("Embed an SVG directly, or commit the JSON and use https://img.shields.io/endpoint?url=<raw URL of the JSON>");
+
+    Ok(())
+}
+
+/// A flat shields.io-style badge.
+fn render_svg(label: &str, message: &str) -> String {
+    let label_width = text_width(label) + 10;
+    let message_width = text_width(message) + 10;
+    let width = label_width + message_width;
+    let (label, message) = (escape(label), escape(message));
+
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"20\" role=\"img\" aria-label=\"{label}: {message}\">\n",
+            "  <title>{label}: {message}</title>\n",
+            "  <linearGradient id=\"s\" x2=\"0\" y2=\"100%\"><stop offset=\"0\" stop-color=\"#bbb\" stop-opacity=\".1\"/><stop offset=\"1\" stop-opacity=\".1\"/></linearGradient>\n",
+            "  <clipPath id=\"r\"><rect width=\"{width}\" height=\"20\" rx=\"3\" fill=\"#fff\"/></clipPath>\n",
+            "  <g clip-path=\"url(#r)\">\n",
+            "    <rect width=\"{label_width}\" height=\"20\" fill=\"#{label_color}\"/>\n",
+            "    <rect x=\"{label_width}\" width=\"{message_width}\" height=\"20\" fill=\"#{color}\"/>\n",
+            "    <rect width=\"{width}\" height=\"20\" fill=\"url(#s)\"/>\n",
+            "  </g>\n",
+            "  <g fill=\"#fff\" text-anchor=\"middle\" font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">\n",
+            "    <text x=\"{label_x}\" y=\"15\" fill=\"#010101\" fill-opacity=\".3\">{label}</text>\n",
+            "    <text x=\"{label_x}\" y=\"14\">{label}</text>\n",
+            "    <text x=\"{message_x}\" y=\"15\" fill=\"#010101\" fill-opacity=\".3\">{message}</text>\n",
+            "    <text x=\"{message_x}\" y=\"14\">{message}</text>\n",
+            "  </g>\n",
+            "</svg>\n"
+        ),
+        width = width,
+        label_width = label_width,
+        message_width = message_width,
+        label_x = label_width as f64 / 2.0,
+        message_x = label_width as f64 + message_width as f64 / 2.0,
+        label = label,
+        message = message,
+        label_color = LABEL_COLOR,
+        color = COLOR,
+    )
+}
+
+/// Approximate width in pixels of `text` in 11px Verdana, which the badge
+/// falls back from; close enough that the text doesn't overflow.
+fn text_width(text: &str) -> usize {
+    let width: f64 = text
+        .chars()
+        .map(|c| match c {
+            'i' | 'l' | 'j' | '.' | ',' | ':' | ';' | '\'' | '!' | '|' => 3.5,
+            'f' | 't' | 'r' | ' ' | '(' | ')' | '[' | ']' | '-' => 4.5,
+            'm' | 'w' | 'M' | 'W' => 10.5,
+            c if c.is_ascii_uppercase() || c.is_ascii_digit() => 7.5,
+            c if c.is_ascii() => 6.5,
+            // Wide scripts (CJK and the like)
+            _ => 11.0,
+        })
+        .sum();
+    width.ceil() as usize
+}
+
+/// 1234567 -> "1,234,567"
+fn thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut out = String::new();
+    for (index, c) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,284 @@
+//! Machine-readable record of a run. Commands report the files they write,
+//! per-item failures and counts as they go. Every run is appended to the
+//! history (see `history`); with `--summary` the record is also saved as
+//! summary.json so scripts can check the outcome without parsing stdout.
+//!
+//! Per-item failures don't stop a command: it finishes and exits with
+//! `EXIT_COMPLETED_WITH_WARNINGS`. With `--strict` the first failure aborts
+//! the run instead.
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+pub const SUMMARY_FILENAME: &str = "summary.json";
+/// Exit status of a run that finished but skipped some items
+pub const EXIT_COMPLETED_WITH_WARNINGS: i32 = 2;
+/// Inputs a batch command skipped, as `file,error`
+pub const FAILURE_REPORT_FILENAME: &str = "failed_inputs.csv";
+/// Options whose values are credentials, kept out of the history and sidecars
+const SECRET_OPTIONS: &[&str] = &["--api-key", "--token", "--webhook"];
+const REDACTED: &str = "<redacted>";
+
+#[derive(Default)]
+struct Record {
+    files_written: Vec<String>,
+    failures: Vec<Failure>,
+    counts: BTreeMap<String, u64>,
+    http_requests: BTreeMap<String, u64>,
+    /// Set by commands whose outcome scripts branch on (see `set_exit_status`)
+    exit_status: Option<i32>,
+}
+
+#[derive(Clone, Serialize)]
+struct Failure {
+    item: String,
+    reason: String,
+}
+
+#[derive(Serialize)]
+struct Summary<'a> {
+    command: &'a str,
+    args: &'a [String],
+    /// Arguments naming existing files
+    inputs: Vec<&'a String>,
+    started_at: String,
+    duration_seconds: f64,
+    success: bool,
+    error: Option<String>,
+    files_written: &'a [String],
+    failures: &'a [Failure],
+    counts: &'a BTreeMap<String, u64>,
+    http_requests: &'a BTreeMap<String, u64>,
+}
+
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_strict(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+pub fn is_strict() -> bool {
+    STRICT.load(Ordering::Relaxed)
+}
+
+fn record() -> &'static Mutex<Record> {
+    static RECORD: OnceLock<Mutex<Record>> = OnceLock::new();
+    RECORD.get_or_init(|| Mutex::new(Record::default()))
+}
+
+pub fn file_written(path: impl AsRef<Path>) {
+    let path = path.as_ref().display().to_string();
+    record().lock().unwrap().files_written.push(path);
+}
+
+/// Notes an item (file, app ID, ...) that was skipped because of an error.
+/// With `--strict` the failure is returned as an error to stop the command.
+pub fn failure(item: impl Into<String>, reason: impl ToString) -> Result<()> {
+    let failure = Failure {
+        item: item.into(),
+        reason: reason.to_string(),
+    };
+    crate::progress::error(&failure.item, &failure.reason);
+
+    if is_strict() {
+        bail!("{}: {} (--strict stops at the first failure)", failure.item, failure.reason);
+    }
+
+    record().lock().unwrap().failures.push(failure);
+
+    Ok(())
+}
+
+/// Prints how many items a finished run skipped, with its counts; returns
+/// whether there were any.
+pub fn print_warnings() -> bool {
+    let record = record().lock().unwrap();
+    if record.failures.is_empty() {
+        return false;
+    }
+
+    let counts: Vec<String> = record
+        .counts
+        .iter()
+        .map(|(name, count)| format!("{} {}", name, count))
+        .collect();
+    println!(
+        "\nCompleted with {} warning(s){}",
+        record.failures.len(),
+        if counts.is_empty() { String::new() } else { format!(" ({})", counts.join(", ")) }
+    );
+
+    true
+}
+
+/// Prints the inputs a batch command skipped and writes them to
+/// failed_inputs.csv. A run without failures removes a stale report.
+pub fn write_failure_report(failures: &[(String, String)], total: usize) -> Result<()> {
+    let path = Path::new(FAILURE_REPORT_FILENAME);
+
+    if failures.is_empty() {
+        if path.exists() {
+            fs::remove_file(path)
+                .with_context(|| format!("Failed to remove {}", FAILURE_REPORT_FILENAME))?;
+        }
+        return Ok(());
+    }
+
+    println!("\nFailed to process {} of {} input file(s):", failures.len(), total);
+    let mut content = String::from("file,error\n");
+    for (file, error) in failures {
+        println!("  {}: {}", file, error);
+        content.push_str(&format!(
+            "{},{}\n",
+            crate::dataset::escape_csv_field(file),
+            crate::dataset::escape_csv_field(error)
+        ));
+    }
+
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write {}", FAILURE_REPORT_FILENAME))?;
+    file_written(path);
+    println!("Failure report saved to: {}", FAILURE_REPORT_FILENAME);
+
+    Ok(())
+}
+
+pub fn count(name: &str, amount: u64) {
+    *record().lock().unwrap().counts.entry(name.to_string()).or_default() += amount;
+}
+
+/// Makes a successful run exit with `status`, for outcomes that aren't
+/// errors but that scripts want to tell apart (e.g. `check-new` finding
+/// nothing yet).
+pub fn set_exit_status(status: i32) {
+    record().lock().unwrap().exit_status = Some(status);
+}
+
+pub fn exit_status() -> Option<i32> {
+    record().lock().unwrap().exit_status
+}
+
+/// `args` (those after the command) with credential values replaced:
+/// those of `SECRET_OPTIONS`, in either `--opt VALUE` or `--opt=VALUE`
+/// form, and the key given to `auth set-key`.
+pub fn redact_args(command: &str, args: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut secret_next = false;
+    for (index, arg) in args.iter().enumerate() {
+        if secret_next {
+            redacted.push(REDACTED.to_string());
+            secret_next = false;
+            continue;
+        }
+        if let Some((option, _)) = arg.split_once('=')
+            && SECRET_OPTIONS.contains(&option)
+        {
+            redacted.push(format!("{}={}", option, REDACTED));
+            continue;
+        }
+        secret_next = SECRET_OPTIONS.contains(&arg.as_str()) || (command == "auth" && index == 0 && arg == "set-key");
+        redacted.push(arg.clone());
+    }
+    redacted
+}
+
+pub fn http_request(host: &str) {
+    *record().lock().unwrap().http_requests.entry(host.to_string()).or_default() += 1;
+}
+
+/// Records a finished run in the history, writes provenance sidecars for
+/// its CSVs and JSON and, with `--summary`, writes summary.json.
+pub fn finish(
+    args: &[String],
+    started_at: chrono::DateTime<chrono::Utc>,
+    started: Instant,
+    result: &Result<()>,
+    write_summary: bool,
+) -> Result<()> {
+    let record = record().lock().unwrap();
+    let command = args.first().map(|s| s.as_str()).unwrap_or_default();
+    let command_args = args.get(1..).unwrap_or_default();
+    let redacted_args = redact_args(command, command_args);
+
+    let summary = Summary {
+        command,
+        args: &redacted_args,
+        inputs: command_args.iter().filter(|arg| Path::new(arg).is_file()).collect(),
+        started_at: started_at.to_rfc3339(),
+        duration_seconds: started.elapsed().as_secs_f64(),
+        success: result.is_ok(),
+        error: result.as_ref().err().map(|e| format!("{:#}", e)),
+        files_written: &record.files_written,
+        failures: &record.failures,
+        counts: &record.counts,
+        http_requests: &record.http_requests,
+    };
+
+    if let Err(e) = crate::provenance::write_sidecars(summary.command, &redacted_args, &summary.inputs, &record.files_written) {
+        println!("Warning: Could not write provenance metadata: {:#}", e);
+    }
+
+    // Browsing the history isn't worth recording in it
+    if summary.command != "history"
+        && let Err(e) = crate::history::append(&serde_json::to_string(&summary)?)
+    {
+        println!("Warning: Could not record the run history: {:#}", e);
+    }
+
+    if !write_summary {
+        return Ok(());
+    }
+
+    fs::write(SUMMARY_FILENAME, serde_json::to_string_pretty(&summary)?)
+        .with_context(|| format!("Failed to write {}", SUMMARY_FILENAME))?;
+
+    println!("Run summary saved to: {}", SUMMARY_FILENAME);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redacted(command: &str, args: &[&str]) -> Vec<String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        redact_args(command, &args)
+    }
+
+    #[test]
+    fn redacts_secret_option_values() {
+        assert_eq!(redacted("reviews", &["--api-key", "ABC123", "out.csv"]), ["--api-key", REDACTED, "out.csv"]);
+        assert_eq!(redacted("push-hass", &["--token", "t0k3n"]), ["--token", REDACTED]);
+        assert_eq!(
+            redacted("check-new", &["--webhook", "https://discord.com/api/webhooks/1/x"]),
+            ["--webhook", REDACTED]
+        );
+    }
+
+    #[test]
+    fn redacts_inline_secret_values() {
+        assert_eq!(redacted("reviews", &["--api-key=ABC123"]), [format!("--api-key={}", REDACTED)]);
+        assert_eq!(redacted("push-hass", &["--token=t0k3n"]), [format!("--token={}", REDACTED)]);
+    }
+
+    #[test]
+    fn redacts_the_key_given_to_auth_set_key() {
+        assert_eq!(redacted("auth", &["set-key", "ABC123"]), ["set-key", REDACTED]);
+        assert_eq!(redacted("auth", &["status"]), ["status"]);
+    }
+
+    #[test]
+    fn keeps_other_args() {
+        assert_eq!(
+            redacted("to-csv", &["replay.json", "--mapping", "set-key", "--name-template={year}.csv"]),
+            ["replay.json", "--mapping", "set-key", "--name-template={year}.csv"]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Output filenames from `--name-template`. Placeholders:
+//!
+//! - `{steamid}`: the profile's SteamID64 (or vanity name for
+//!   community-games)
+//! - `{year}`: the replay year
+//! - `{date}`: the date of the run, YYYY-MM-DD
+//!
+//! Which placeholders an output supports depends on what it covers: the
+//! combined to-csv file spans players and years, so it only has `{date}`.
+//! Templates may contain directories, which are created as needed.
+
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::Path;
+
+/// Renders `template` with `values` (plus `{date}`).
+pub fn render(template: &str, values: &[(&str, &str)]) -> Result<String> {
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let mut values = values.to_vec();
+    values.push(("date", &date));
+
+    let mut path = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        path.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            bail!("Unclosed '{{' in --name-template '{}'", template);
+        };
+        let name = &rest[start + 1..start + end];
+        let Some((_, value)) = values.iter().find(|(key, _)| *key == name) else {
+            let available: Vec<String> = values.iter().map(|(key, _)| format!("{{{}}}", key)).collect();
+            bail!(
+                "Unknown placeholder {{{}}} in --name-template (available here: {})",
+                name,
+                available.join(", ")
+            );
+        };
+        path.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+    path.push_str(rest);
+
+    if path.trim().is_empty() || path.ends_with('/') {
+        bail!("--name-template '{}' doesn't name a file", template);
+    }
+
+    Ok(path)
+}
+
+/// Creates the directories a rendered path names.
+pub fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_placeholders() {
+        let values = [("steamid", "76561198000000000"), ("year", "2024")];
+        let path = render("exports/{steamid}/replay_{year}.json", &values).unwrap();
+
+        assert_eq!(path, "exports/76561198000000000/replay_2024.json");
+    }
+
+    #[test]
+    fn always_offers_the_date() {
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+        assert_eq!(render("data_{date}.csv", &[]).unwrap(), format!("data_{}.csv", today));
+    }
+
+    #[test]
+    fn names_the_available_placeholders() {
+        let error = render("{steamid}_{month}.json", &[("steamid", "123")]).unwrap_err().to_string();
+
+        assert!(error.contains("{month}"), "{}", error);
+        assert!(error.contains("{steamid}, {date}"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_unclosed_braces_and_empty_names() {
+        assert!(render("replay_{year.json", &[("year", "2024")]).is_err());
+        assert!(render("  ", &[]).is_err());
+        assert!(render("exports/{year}/", &[("year", "2024")]).is_err());
+    }
+}
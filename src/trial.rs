@@ -0,0 +1,75 @@
+//! `--limit N` and `--sample N` for commands that fetch something per game,
+//! so output format and credentials can be checked on a handful of games
+//! before a long, rate-limited crawl.
+
+use anyhow::{Result, bail};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Subset {
+    /// The first N games, in the command's usual order
+    Limit(usize),
+    /// N games picked at random
+    Sample(usize),
+}
+
+impl Subset {
+    pub fn parse(limit: Option<&str>, sample: Option<&str>) -> Result<Option<Subset>> {
+        let count = |flag: &str, value: &str| -> Result<usize> {
+            match value.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => bail!("{} expects a positive number, got '{}'", flag, value),
+            }
+        };
+
+        match (limit, sample) {
+            (Some(_), Some(_)) => bail!("--limit and --sample can't be combined"),
+            (Some(limit), None) => Ok(Some(Subset::Limit(count("--limit", limit)?))),
+            (None, Some(sample)) => Ok(Some(Subset::Sample(count("--sample", sample)?))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Keeps N of `items`, preserving their order.
+    pub fn apply<T: Hash>(self, mut items: Vec<T>) -> Vec<T> {
+        let total = items.len();
+
+        match self {
+            Subset::Limit(n) => items.truncate(n),
+            Subset::Sample(n) if n < total => {
+                // RandomState is seeded per process, so ranking by hash gives
+                // a different pick every run
+                let state = RandomState::new();
+                let mut ranked: Vec<(u64, usize)> = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| (state.hash_one(item), index))
+                    .collect();
+                ranked.sort_unstable();
+                let mut keep = vec![false; total];
+                for &(_, index) in ranked.iter().take(n) {
+                    keep[index] = true;
+                }
+                let mut index = 0;
+                items.retain(|_| {
+                    index += 1;
+                    keep[index - 1]
+                });
+            }
+            Subset::Sample(_) => {}
+        }
+
+        println!(
+            "Trial run: {} {} of {} game(s)",
+            match self {
+                Subset::Limit(_) => "processing the first",
+                Subset::Sample(_) => "processing a random sample of",
+            },
+            items.len(),
+            total
+        );
+
+        items
+    }
+}
@@ -0,0 +1,239 @@
+//! Parser for Valve's KeyValues text format, as used by localconfig.vdf,
+//! libraryfolders.vdf and appmanifest_*.acf.
+
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    String(String),
+    // Keys are kept in file order; Valve files occasionally repeat a key
+    Section(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Looks up a direct child. Keys are case-insensitive ("Steam" vs "steam"
+    /// both appear in the wild).
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries()?
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Follows a chain of keys, e.g. `["Software", "Valve", "Steam", "apps"]`.
+    pub fn get_path(&self, keys: &[&str]) -> Option<&Value> {
+        keys.iter().try_fold(self, |node, key| node.get(key))
+    }
+
+    pub fn entries(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Section(entries) => Some(entries),
+            Value::String(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            Value::Section(_) => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_str()?.parse().ok()
+    }
+}
+
+pub fn parse_file(path: &Path) -> Result<Value> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    parse(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Parses a KeyValues document. The result is a section holding the
+/// top-level keys (usually a single root such as "AppState").
+pub fn parse(input: &str) -> Result<Value> {
+    let mut tokens = tokenize(input)?.into_iter().peekable();
+    let mut stack: Vec<(String, Vec<(String, Value)>)> = vec![(String::new(), Vec::new())];
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Close => {
+                if stack.len() == 1 {
+                    bail!("Unexpected closing brace");
+                }
+                let (key, entries) = stack.pop().unwrap();
+                stack.last_mut().unwrap().1.push((key, Value::Section(entries)));
+            }
+            Token::Open => bail!("Unexpected opening brace without a key"),
+            Token::Condition => bail!("Unexpected conditional without a key"),
+            Token::Str(key) => {
+                match tokens.next() {
+                    Some(Token::Str(value)) => {
+                        stack.last_mut().unwrap().1.push((key, Value::String(value)));
+                    }
+                    Some(Token::Open) => stack.push((key, Vec::new())),
+                    _ => bail!("Missing value for key '{}'", key),
+                }
+                // Platform conditionals such as [$WIN32] trail the value; we keep every branch
+                if let Some(Token::Condition) = tokens.peek() {
+                    tokens.next();
+                }
+            }
+        }
+    }
+
+    if stack.len() != 1 {
+        bail!("Unterminated section '{}'", stack.last().unwrap().0);
+    }
+
+    Ok(Value::Section(stack.pop().unwrap().1))
+}
+
+enum Token {
+    Str(String),
+    Condition,
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(other) => value.push(other),
+                            None => bail!("Unterminated string"),
+                        },
+                        Some(other) => value.push(other),
+                        None => bail!("Unterminated string"),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '[' => {
+                for next in chars.by_ref() {
+                    if next == ']' {
+                        break;
+                    }
+                }
+                tokens.push(Token::Condition);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                // Line comment
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                // Unquoted token
+                let mut value = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '{' | '}' | '"' | '[') {
+                        break;
+                    }
+                    value.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Str(value));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_sections() {
+        let root = parse(
+            r#""AppState"
+{
+    "appid"     "620"
+    "UserConfig"
+    {
+        "language"  "english"
+    }
+}"#,
+        )
+        .unwrap();
+
+        assert_eq!(root.get_path(&["AppState", "appid"]).and_then(Value::as_u64), Some(620));
+        assert_eq!(
+            root.get_path(&["appstate", "userconfig", "LANGUAGE"]).and_then(Value::as_str),
+            Some("english")
+        );
+        assert!(root.get_path(&["AppState", "appid", "more"]).is_none());
+    }
+
+    #[test]
+    fn keeps_repeated_keys_in_file_order() {
+        let root = parse(r#""root" { "key" "first" "key" "second" }"#).unwrap();
+        let entries = root.get("root").and_then(Value::entries).unwrap();
+
+        let values: Vec<_> = entries.iter().filter_map(|(_, value)| value.as_str()).collect();
+        assert_eq!(values, ["first", "second"]);
+    }
+
+    #[test]
+    fn unescapes_strings() {
+        let root = parse(r#""path" "C:\\Games\\\"Steam\"\tlibrary\n""#).unwrap();
+
+        assert_eq!(root.get("path").and_then(Value::as_str), Some("C:\\Games\\\"Steam\"\tlibrary\n"));
+    }
+
+    #[test]
+    fn keeps_every_conditional_branch() {
+        let root = parse(
+            r#""launch"
+{
+    "exe"  "game.exe"  [$WIN32]
+    "exe"  "game.sh"   [$LINUX]
+    "args" "-novid"
+}"#,
+        )
+        .unwrap();
+        let entries = root.get("launch").and_then(Value::entries).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(root.get_path(&["launch", "exe"]).and_then(Value::as_str), Some("game.exe"));
+        assert_eq!(root.get_path(&["launch", "args"]).and_then(Value::as_str), Some("-novid"));
+    }
+
+    #[test]
+    fn skips_comments_and_reads_unquoted_tokens() {
+        let root = parse(
+            "\u{feff}// written by Steam\nlibraryfolders\n{\n    // the default library\n    0 { path /home/steam }\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(root.get_path(&["libraryfolders", "0", "path"]).and_then(Value::as_str), Some("/home/steam"));
+    }
+
+    #[test]
+    fn rejects_unterminated_input() {
+        for input in [r#""root" { "key" "value""#, r#""root" { "key" "val"#, r#""root" { "key" }"#, "}"] {
+            assert!(parse(input).is_err(), "{:?} should not parse", input);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! `verify-mapping`: looks the mapping's games up again on the live store
+//! and reports the ones whose name changed or that are no longer listed,
+//! so an old mapping file can be kept trustworthy. `--apply` writes the new
+//! names back; delisted games keep the name they had.
+
+use anyhow::{Context, Result};
+use std::fs;
+
+use crate::dataset::{self, escape_csv_field};
+use crate::details;
+use crate::trial::Subset;
+
+pub const MISMATCHES_FILENAME: &str = "mapping_mismatches.csv";
+
+pub struct VerifyOptions {
+    pub mapping_path: String,
+    pub output: String,
+    /// Check only some of the games (`--limit N`, `--sample N`)
+    pub subset: Option<Subset>,
+    pub apply: bool,
+}
+
+struct Mismatch {
+    app_id: String,
+    mapped_name: String,
+    /// The store's current name; None when the game is delisted
+    store_name: Option<String>,
+}
+
+pub fn verify_mapping(options: &VerifyOptions) -> Result<()> {
+    let (mut mapping, fields) = dataset::load_mapping_with_fields(&options.mapping_path)?;
+
+    // Games imported from other launchers have no store page to check
+    let mut app_ids: Vec<String> = mapping.keys().filter(|key| !key.contains(':')).cloned().collect();
+    app_ids.sort_by_key(|app_id| app_id.parse::<u64>().unwrap_or(u64::MAX));
+    if app_ids.is_empty() {
+        println!("No Steam games in {} to verify", options.mapping_path);
+        return Ok(());
+    }
+    if let Some(subset) = options.subset {
+        app_ids = subset.apply(app_ids);
+    }
+
+    println!("Verifying {} game(s) from {} against the store...", app_ids.len(), options.mapping_path);
+
+    let mut mismatches = Vec::new();
+    let mut checked = 0;
+
+    crate::progress::phase("verify_mapping", app_ids.len());
+    for (index, app_id) in app_ids.iter().enumerate() {
+        crate::progress::item(app_id, index);
+        println!("[{}/{}] Checking app ID: {}", index + 1, app_ids.len(), app_id);
+
+        // Always ask the store; the cache is what went stale
+        let entry = match details::load(app_id, true) {
+            Ok(entry) => entry,
+            Err(e) => {
+                println!("  Error fetching app ID {}: {}", app_id, e);
+                crate::summary::failure(app_id.as_str(), format!("{:#}", e))?;
+                continue;
+            }
+        };
+        checked += 1;
+
+        let mapped_name = &mapping[app_id];
+        let store_name = details::data(&entry)
+            .and_then(|d| d.get("name"))
+            .and_then(|n| n.as_str())
+            .map(|name| name.trim().to_string());
+
+        match &store_name {
+            Some(name) if name == mapped_name.trim() => continue,
+            Some(name) => println!("  Renamed: '{}' is now '{}'", mapped_name, name),
+            None => println!("  Delisted: no store data for '{}'", mapped_name),
+        }
+        mismatches.push(Mismatch {
+            app_id: app_id.clone(),
+            mapped_name: mapped_name.clone(),
+            store_name,
+        });
+    }
+
+    let renamed = mismatches.iter().filter(|m| m.store_name.is_some()).count();
+    crate::summary::count("games_verified", checked as u64);
+    crate::summary::count("games_renamed", renamed as u64);
+    crate::summary::count("games_delisted", (mismatches.len() - renamed) as u64);
+
+    println!(
+        "\n{} of {} checked game(s) match the store; {} renamed, {} delisted",
+        checked - mismatches.len(),
+        checked,
+        renamed,
+        mismatches.len() - renamed
+    );
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+
+    let mut content = String::from("app_id,mapped_name,store_name,status\n");
+    for mismatch in &mismatches {
+        content.push_str(&format!(
+            "{},{},{},{}\n",
+            escape_csv_field(&mismatch.app_id),
+            escape_csv_field(&mismatch.mapped_name),
+            escape_csv_field(mismatch.store_name.as_deref().unwrap_or_default()),
+            if mismatch.store_name.is_some() { "renamed" } else { "delisted" }
+        ));
+    }
+    fs::write(&options.output, content)
+        .with_context(|| format!("Failed to write {}", options.output))?;
+    crate::summary::file_written(&options.output);
+    println!("Mismatches saved to: {}", options.output);
+
+    if !options.apply {
+        if renamed > 0 {
+            println!("Rerun with --apply to update the {} renamed game(s) in {}", renamed, options.mapping_path);
+        }
+        return Ok(());
+    }
+    if renamed == 0 {
+        return Ok(());
+    }
+
+    for mismatch in &mismatches {
+        if let Some(name) = &mismatch.store_name {
+            mapping.insert(mismatch.app_id.clone(), name.clone());
+        }
+    }
+    dataset::write_mapping(&options.mapping_path, &mapping, &fields)?;
+    crate::summary::file_written(&options.mapping_path);
+    println!("Updated {} name(s) in: {}", renamed, options.mapping_path);
+
+    Ok(())
+}